@@ -0,0 +1,452 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::project::PageInventory;
+use crate::search::SearchSection;
+
+/// Directory (relative to the project root) holding the persistent build cache.
+pub const CACHE_DIR: &str = ".docanvil-cache";
+
+const CACHE_FILE: &str = "build.json";
+
+/// A cached page: the hash of its inputs, the search sections extracted from it
+/// and the files it included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: String,
+    sections: Vec<SearchSection>,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+}
+
+/// A file a page's output depends on besides its own source, such as a
+/// Markdown file it includes, with the hash of the contents it was rendered with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    pub path: PathBuf,
+    hash: String,
+}
+
+impl Dependency {
+    pub fn new(path: &Path, contents: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            hash: format!("{:016x}", Fingerprint::new().add(contents).finish()),
+        }
+    }
+
+    /// Whether the file still has the contents it was recorded with.
+    fn is_current(&self) -> bool {
+        std::fs::read_to_string(&self.path)
+            .is_ok_and(|contents| Self::new(&self.path, &contents).hash == self.hash)
+    }
+}
+
+/// On-disk cache format.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: String,
+    pages: HashMap<String, CacheEntry>,
+    /// Files that pages left out of `pages` depend on.
+    #[serde(default)]
+    dependencies: BTreeSet<PathBuf>,
+}
+
+/// Incremental build cache, keyed by output path.
+///
+/// A page is reused when its output file still exists, the hash of its inputs
+/// (source text, the pages linking to it and the shape of the inventory it
+/// links against) matches the previous build and the files it included are
+/// unchanged. Anything that affects every page — config, nav files, theme,
+/// assets, the docanvil version — goes into the site-wide fingerprint; when
+/// that changes, the whole cache is discarded.
+pub struct BuildCache {
+    path: Option<PathBuf>,
+    fingerprint: String,
+    previous: HashMap<String, CacheEntry>,
+    current: HashMap<String, CacheEntry>,
+    uncached: BTreeSet<PathBuf>,
+    reused: usize,
+}
+
+impl BuildCache {
+    /// Load the cache from `.docanvil-cache/` under the project root.
+    ///
+    /// When `read` is `false` (`--no-cache`), existing entries are ignored but a
+    /// fresh cache is still written at the end of the build.
+    pub fn load(project_root: &Path, fingerprint: u64, read: bool) -> Self {
+        let path = project_root.join(CACHE_DIR).join(CACHE_FILE);
+        let fingerprint = format!("{fingerprint:016x}");
+
+        let previous = if read {
+            std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<CacheFile>(&s).ok())
+                .filter(|file| file.fingerprint == fingerprint)
+                .map(|file| file.pages)
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        Self {
+            path: Some(path),
+            fingerprint,
+            previous,
+            current: HashMap::new(),
+            uncached: BTreeSet::new(),
+            reused: 0,
+        }
+    }
+
+    /// A cache that never hits and is never written.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            fingerprint: String::new(),
+            previous: HashMap::new(),
+            current: HashMap::new(),
+            uncached: BTreeSet::new(),
+            reused: 0,
+        }
+    }
+
+    /// Return the cached search sections for a page if its inputs are unchanged
+    /// and its output file still exists. A hit is carried over into the new cache.
+    pub fn lookup(
+        &mut self,
+        output_path: &Path,
+        input_hash: u64,
+        output_exists: impl FnOnce() -> bool,
+    ) -> Option<Vec<SearchSection>> {
+        let key = cache_key(output_path);
+        let hash = format!("{input_hash:016x}");
+        let entry = self.previous.get(&key)?;
+        if entry.hash != hash
+            || !entry.dependencies.iter().all(Dependency::is_current)
+            || !output_exists()
+        {
+            return None;
+        }
+        let sections = entry.sections.clone();
+        self.current.insert(key, entry.clone());
+        self.reused += 1;
+        Some(sections)
+    }
+
+    /// Record a freshly rendered page and the files it included.
+    pub fn store(
+        &mut self,
+        output_path: &Path,
+        input_hash: u64,
+        sections: &[SearchSection],
+        dependencies: &[Dependency],
+    ) {
+        self.current.insert(
+            cache_key(output_path),
+            CacheEntry {
+                hash: format!("{input_hash:016x}"),
+                sections: sections.to_vec(),
+                dependencies: dependencies.to_vec(),
+            },
+        );
+    }
+
+    /// Record the files a page that isn't cached depends on, so they are still
+    /// listed by [`dependency_paths`].
+    pub fn store_dependencies(&mut self, dependencies: &[Dependency]) {
+        self.uncached.extend(
+            dependencies
+                .iter()
+                .map(|dependency| dependency.path.clone()),
+        );
+    }
+
+    /// Number of pages reused from the previous build.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    /// Write the cache to disk. Entries for pages that no longer exist are dropped.
+    pub fn save(self) -> Result<()> {
+        let Some(path) = self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = CacheFile {
+            fingerprint: self.fingerprint,
+            pages: self.current,
+            dependencies: self.uncached,
+        };
+        let json = serde_json::to_string(&file).unwrap_or_default();
+        std::fs::write(&path, json)?;
+        Ok(())
+    }
+}
+
+/// Every file the pages of the last saved build depend on besides their own
+/// sources, such as included files and snippet sources.
+pub fn dependency_paths(project_root: &Path) -> BTreeSet<PathBuf> {
+    let path = project_root.join(CACHE_DIR).join(CACHE_FILE);
+    let Some(file) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<CacheFile>(&s).ok())
+    else {
+        return BTreeSet::new();
+    };
+    let mut paths = file.dependencies;
+    for entry in file.pages.into_values() {
+        paths.extend(
+            entry
+                .dependencies
+                .into_iter()
+                .map(|dependency| dependency.path),
+        );
+    }
+    paths
+}
+
+fn cache_key(output_path: &Path) -> String {
+    output_path.to_string_lossy().replace('\\', "/")
+}
+
+/// Incrementally hashes build inputs into a single fingerprint.
+#[derive(Default)]
+pub struct Fingerprint(DefaultHasher);
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<T: Hash + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.hash(&mut self.0);
+        self
+    }
+
+    /// Hash a file's contents (or its absence).
+    pub fn add_file(&mut self, path: &Path) -> &mut Self {
+        std::fs::read(path).ok().hash(&mut self.0);
+        self
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+/// Hash the parts of an inventory that other pages depend on: the set of pages,
+/// their slugs, output paths and titles. Any change here alters nav, wiki-link
+/// targets and prev/next links, so every page in the inventory is re-rendered.
+pub fn inventory_hash(inventory: &PageInventory) -> u64 {
+    let mut fp = Fingerprint::new();
+    for key in &inventory.ordered {
+        let page = &inventory.pages[key];
+        fp.add(key)
+            .add(&page.slug)
+            .add(&page.output_path)
+            .add(&page.title)
+            .add(&page.listing)
+            .add(&page.nav_label)
+            .add(&page.nav_order);
+    }
+    let mut aliases: Vec<_> = inventory.slug_aliases.iter().collect();
+    aliases.sort();
+    fp.add(&aliases);
+    let mut folders: Vec<_> = inventory.folders.iter().collect();
+    folders.sort_by_key(|(path, _)| *path);
+    fp.add(&folders);
+    fp.finish()
+}
+
+/// Hash the inputs of a single page: its source, the pages linking to it
+/// and the inventory.
+pub fn page_hash(source: &str, backlinks: &[String], inventory_hash: u64) -> u64 {
+    Fingerprint::new()
+        .add(source)
+        .add(backlinks)
+        .add(&inventory_hash)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(body: &str) -> SearchSection {
+        SearchSection {
+            id: "index".into(),
+            title: "Home".into(),
+            heading: String::new(),
+            anchor: String::new(),
+            url: "/index.html".into(),
+            body: body.into(),
+            breadcrumbs: vec!["Home".into()],
+        }
+    }
+
+    #[test]
+    fn round_trip_hit() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+        std::fs::write(&out, "<html>").unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_none()
+        );
+        cache.store(Path::new("index.html"), 42, &[section("hello")], &[]);
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        let sections = cache
+            .lookup(Path::new("index.html"), 42, || out.exists())
+            .unwrap();
+        assert_eq!(sections[0].body, "hello");
+        assert_eq!(cache.reused(), 1);
+    }
+
+    #[test]
+    fn changed_input_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+        std::fs::write(&out, "<html>").unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(Path::new("index.html"), 42, &[], &[]);
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 43, || out.exists())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn changed_dependency_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+        std::fs::write(&out, "<html>").unwrap();
+        let shared = dir.path().join("shared.md");
+        std::fs::write(&shared, "Shared").unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(
+            Path::new("index.html"),
+            42,
+            &[],
+            &[Dependency::new(&shared, "Shared")],
+        );
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_some()
+        );
+        cache.save().unwrap();
+
+        std::fs::write(&shared, "Changed").unwrap();
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn changed_fingerprint_discards_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+        std::fs::write(&out, "<html>").unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(Path::new("index.html"), 42, &[], &[]);
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 2, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn missing_output_file_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(Path::new("index.html"), 42, &[], &[]);
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn no_cache_ignores_existing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("index.html");
+        std::fs::write(&out, "<html>").unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(Path::new("index.html"), 42, &[], &[]);
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(dir.path(), 1, false);
+        assert!(
+            cache
+                .lookup(Path::new("index.html"), 42, || out.exists())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn disabled_cache_never_hits() {
+        let mut cache = BuildCache::disabled();
+        cache.store(Path::new("index.html"), 42, &[], &[]);
+        assert!(cache.lookup(Path::new("index.html"), 42, || true).is_none());
+        cache.save().unwrap();
+    }
+
+    #[test]
+    fn lists_dependencies_of_cached_and_uncached_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared.md");
+        let snippet = dir.path().join("example.rs");
+
+        let mut cache = BuildCache::load(dir.path(), 1, true);
+        cache.store(
+            Path::new("index.html"),
+            42,
+            &[],
+            &[Dependency::new(&shared, "Shared")],
+        );
+        cache.store_dependencies(&[Dependency::new(&snippet, "fn main() {}")]);
+        cache.save().unwrap();
+
+        assert_eq!(
+            dependency_paths(dir.path()),
+            BTreeSet::from([shared, snippet])
+        );
+    }
+}
@@ -32,6 +32,9 @@ pub fn run(
         OutputFormat::Junit => {
             print!("{}", doctor::format_junit(&diagnostics, project_root));
         }
+        OutputFormat::Json => {
+            print!("{}", doctor::format_json(&diagnostics, project_root));
+        }
         OutputFormat::Human => {
             if !quiet {
                 eprintln!();
@@ -7,14 +7,17 @@ use regex::Regex;
 use serde::Serialize;
 use tera::{Context, Tera};
 
-use crate::components::ComponentRegistry;
+use crate::components::{ComponentRegistry, templates};
 use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::nav;
 use crate::pipeline;
 use crate::pipeline::frontmatter::{self, FrontMatter};
 use crate::pipeline::syntax::SyntaxHighlighter;
+use crate::plugins::{Plugins, wasm};
 use crate::project::{NavNode, PageInfo, PageInventory, flatten_nav_pages};
+use crate::source::DiskFiles;
+use crate::util;
 
 use super::cdp;
 
@@ -257,7 +260,7 @@ fn render_toc_nodes(nodes: &[NavNode], html: &mut String, depth: usize) {
     let indent = "  ".repeat(depth);
     for node in nodes {
         match node {
-            NavNode::Page { label, slug } => {
+            NavNode::Page { label, slug, .. } => {
                 html.push_str(&format!(
                     "{indent}<li><a href=\"#{slug}\">{label}</a></li>\n",
                     label = crate::util::html_escape(label),
@@ -267,6 +270,7 @@ fn render_toc_nodes(nodes: &[NavNode], html: &mut String, depth: usize) {
                 label,
                 slug,
                 children,
+                ..
             } => {
                 if let Some(s) = slug {
                     html.push_str(&format!(
@@ -286,6 +290,8 @@ fn render_toc_nodes(nodes: &[NavNode], html: &mut String, depth: usize) {
                 }
                 html.push_str(&format!("{indent}</li>\n"));
             }
+            // External links have no place in a printed table of contents
+            NavNode::Link { .. } => {}
             NavNode::Separator { label } => {
                 if let Some(text) = label {
                     html.push_str(&format!(
@@ -396,6 +402,8 @@ fn run_single_locale(
     let mut sources: HashMap<String, String> = HashMap::new();
     let mut front_matters: HashMap<String, FrontMatter> = HashMap::new();
     let mut slug_updates: Vec<(String, String)> = Vec::new();
+    let mut unpublished: Vec<String> = Vec::new();
+    let now = util::unix_now();
 
     for key in &page_keys {
         // Extract owned values before any mutable borrow of inventory.
@@ -405,12 +413,15 @@ fn run_single_locale(
         };
 
         let source = std::fs::read_to_string(&source_path).map_err(io_context(&source_path))?;
-        let fm = frontmatter::extract(&source);
+        let fm = frontmatter::extract(&source, &source_path);
 
-        if let Some(ref title) = fm.title
-            && let Some(p) = inventory.pages.get_mut(key)
-        {
-            p.title = title.clone();
+        if let Some(p) = inventory.pages.get_mut(key) {
+            if let Some(ref title) = fm.title {
+                p.title = title.clone();
+            }
+            p.listing = fm.listing();
+            p.nav_label = fm.nav_label.clone();
+            p.nav_order = fm.nav_order;
         }
 
         let current_basename = slug.rsplit('/').next().unwrap_or(&slug).to_string();
@@ -430,6 +441,9 @@ fn run_single_locale(
             slug_updates.push((key.clone(), new_slug));
         }
 
+        if fm.unpublished(now).is_some() {
+            unpublished.push(key.clone());
+        }
         sources.insert(key.clone(), source);
         front_matters.insert(key.clone(), fm);
     }
@@ -449,6 +463,14 @@ fn run_single_locale(
         }
     }
 
+    // Draft and scheduled pages are left out, as in a production build
+    for key in unpublished {
+        let key = inventory.slug_aliases.get(&key).cloned().unwrap_or(key);
+        inventory.withhold(&key);
+        sources.remove(&key);
+        front_matters.remove(&key);
+    }
+
     // ── Build nav tree (after slug updates) ───────────────────────────────────
     let nav_tree = if let Some(locale) = export_locale {
         let nav_config = nav::load_nav_for_locale(project_root, locale)?;
@@ -477,13 +499,20 @@ fn run_single_locale(
         let n = flat_pages.len();
         eprintln!("Rendering {} page{}…", n, if n == 1 { "" } else { "s" });
     }
-    let registry = ComponentRegistry::with_builtins();
+    let plugins = Plugins::new(&config.plugins, project_root);
+    let mut registry = ComponentRegistry::with_builtins();
+    registry.load_templates(&DiskFiles, &project_root.join(templates::TEMPLATES_DIR))?;
+    registry.register_wasm_plugins(&DiskFiles, &project_root.join(wasm::PLUGINS_DIR))?;
+    plugins.register_components(&mut registry);
     let highlighter = if config.syntax.enabled {
         Some(SyntaxHighlighter::new(&config.syntax.theme))
     } else {
         None
     };
 
+    let variables = config.variables_for(None, export_locale);
+
+    let anchors = pipeline::anchors::Anchors::new();
     let mut chapters: Vec<ChapterData> = Vec::new();
 
     for (slug, _label) in &flat_pages {
@@ -500,16 +529,21 @@ fn run_single_locale(
             continue;
         };
 
-        let html_body = pipeline::process(
-            source,
-            &inventory,
-            &page.source_path,
-            &registry,
-            "/",
-            highlighter.as_ref(),
+        let ctx = pipeline::PipelineContext {
+            inventory: &inventory,
+            registry: &registry,
+            base_url: "/",
+            highlighter: highlighter.as_ref(),
             project_root,
-            export_locale,
-        )?;
+            files: &DiskFiles,
+            locale: export_locale,
+            version: None,
+            profile: None,
+            plugins: &plugins,
+            variables: &variables,
+            anchors: &anchors,
+        };
+        let html_body = pipeline::process(source, &page.source_path, &ctx)?;
         let html_body = rewrite_links_for_pdf(&html_body, &inventory, export_locale);
 
         chapters.push(ChapterData {
@@ -691,6 +725,7 @@ fn lookup_page<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::project::NavDecoration;
 
     // ── extract_css_vars_from_file ────────────────────────────────────────────
 
@@ -722,8 +757,8 @@ mod tests {
             "--color-primary: #bad;\nbody { --color-text: #333; }\n:root { --color-bg: #fff; }";
         let vars = extract_css_vars_from_file(css);
         // Only the :root block variable is captured
-        assert!(vars.get("--color-primary").is_none());
-        assert!(vars.get("--color-text").is_none());
+        assert!(!vars.contains_key("--color-primary"));
+        assert!(!vars.contains_key("--color-text"));
         assert_eq!(vars.get("--color-bg").map(String::as_str), Some("#fff"));
     }
 
@@ -802,10 +837,12 @@ mod tests {
             NavNode::Page {
                 label: "Home".into(),
                 slug: "index".into(),
+                decoration: NavDecoration::default(),
             },
             NavNode::Page {
                 label: "Getting Started".into(),
                 slug: "getting-started".into(),
+                decoration: NavDecoration::default(),
             },
         ];
         let toc = render_toc(&nodes);
@@ -821,9 +858,11 @@ mod tests {
         let nodes = vec![NavNode::Group {
             label: "Guides".into(),
             slug: None,
+            decoration: NavDecoration::default(),
             children: vec![NavNode::Page {
                 label: "Setup".into(),
                 slug: "guides/setup".into(),
+                decoration: NavDecoration::default(),
             }],
         }];
         let toc = render_toc(&nodes);
@@ -843,6 +882,7 @@ mod tests {
             NavNode::Page {
                 label: "Page".into(),
                 slug: "page".into(),
+                decoration: NavDecoration::default(),
             },
         ];
         let toc = render_toc(&nodes);
@@ -856,6 +896,7 @@ mod tests {
         let nodes = vec![NavNode::Page {
             label: "A & B <test>".into(),
             slug: "a-b".into(),
+            decoration: NavDecoration::default(),
         }];
         let toc = render_toc(&nodes);
         assert!(toc.contains("A &amp; B &lt;test&gt;"));
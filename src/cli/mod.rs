@@ -20,6 +20,8 @@ pub enum OutputFormat {
     Checkstyle,
     /// JUnit XML (compatible with test result reporters)
     Junit,
+    /// JSON array of diagnostics
+    Json,
 }
 
 #[derive(Parser)]
@@ -95,6 +97,21 @@ pub enum Command {
         /// Treat warnings as errors
         #[arg(long)]
         strict: bool,
+        /// Ignore the incremental build cache and re-render every page
+        #[arg(long)]
+        no_cache: bool,
+        /// Include draft and scheduled pages, marked with a banner
+        #[arg(long)]
+        drafts: bool,
+        /// Build profile that `:::only{profile="..."}` blocks are matched against
+        #[arg(long)]
+        profile: Option<String>,
+        /// Number of pages to render in parallel (defaults to the number of CPUs)
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
+        /// Output format for build warnings
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
         /// Path to the project root
         #[arg(long, default_value = ".")]
         path: PathBuf,
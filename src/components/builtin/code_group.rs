@@ -1,3 +1,4 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 use crate::util::html_escape;
@@ -9,6 +10,10 @@ impl Component for CodeGroup {
         "code-group"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(&[])
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         // Code groups work like tabs but are specifically for code blocks.
         // Parse code blocks from the raw body, using ```lang as tab labels.
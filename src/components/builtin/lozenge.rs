@@ -1,13 +1,26 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 
 pub struct Lozenge;
 
+/// Variations styled by the default theme.
+const TYPES: &[&str] = &["default", "in-progress", "warning", "success", "error"];
+
+const SCHEMA: &[AttributeSpec] = &[
+    AttributeSpec::new("type").one_of(TYPES),
+    AttributeSpec::new("text").required(),
+];
+
 impl Component for Lozenge {
     fn name(&self) -> &str {
         "lozenge"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(SCHEMA)
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         let variation = ctx
             .attributes
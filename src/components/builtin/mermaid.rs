@@ -1,3 +1,4 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 
@@ -8,6 +9,10 @@ impl Component for Mermaid {
         "mermaid"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(&[])
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         Ok(format!("<pre class=\"mermaid\">{}</pre>", ctx.body_raw))
     }
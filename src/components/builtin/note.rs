@@ -1,13 +1,20 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 
 pub struct Note;
 
+const SCHEMA: &[AttributeSpec] = &[AttributeSpec::new("title")];
+
 impl Component for Note {
     fn name(&self) -> &str {
         "note"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(SCHEMA)
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         let title = ctx
             .attributes
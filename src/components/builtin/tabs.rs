@@ -1,3 +1,4 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 use crate::pipeline::directives;
@@ -9,6 +10,10 @@ impl Component for Tabs {
         "tabs"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(&[])
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         // Parse inner :::tab directives from the raw body
         let mut tabs = Vec::new();
@@ -1,13 +1,20 @@
+use crate::components::schema::AttributeSpec;
 use crate::components::{Component, ComponentContext};
 use crate::error::Result;
 
 pub struct Warning;
 
+const SCHEMA: &[AttributeSpec] = &[AttributeSpec::new("title")];
+
 impl Component for Warning {
     fn name(&self) -> &str {
         "warning"
     }
 
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        Some(SCHEMA)
+    }
+
     fn render(&self, ctx: &ComponentContext) -> Result<String> {
         let title = ctx
             .attributes
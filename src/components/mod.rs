@@ -1,9 +1,16 @@
 pub mod builtin;
+pub mod schema;
+pub mod templates;
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::error::Result;
 use crate::pipeline::directives::DirectiveBlock;
+use crate::source::FileSource;
+
+use self::schema::{AttributeSpec, Violation};
+use self::templates::TemplateComponents;
 
 /// Context passed to a component when rendering.
 pub struct ComponentContext {
@@ -18,11 +25,18 @@ pub struct ComponentContext {
 pub trait Component: Send + Sync {
     fn name(&self) -> &str;
     fn render(&self, ctx: &ComponentContext) -> Result<String>;
+
+    /// The attributes this component accepts, checked before each render.
+    /// `None` (the default) accepts any attributes without checking.
+    fn schema(&self) -> Option<&[AttributeSpec]> {
+        None
+    }
 }
 
 /// Registry mapping directive names to component implementations.
 pub struct ComponentRegistry {
     components: HashMap<String, Box<dyn Component>>,
+    templates: TemplateComponents,
 }
 
 impl Default for ComponentRegistry {
@@ -35,6 +49,7 @@ impl ComponentRegistry {
     pub fn new() -> Self {
         Self {
             components: HashMap::new(),
+            templates: TemplateComponents::default(),
         }
     }
 
@@ -55,9 +70,44 @@ impl ComponentRegistry {
             .insert(component.name().to_string(), component);
     }
 
-    /// Render a directive block using the registered component.
-    /// Falls back to a generic div wrapper if no component is registered.
-    pub fn render_block(&self, block: &DirectiveBlock) -> String {
+    /// Register every WebAssembly component (`*.wasm`) directly inside `dir`,
+    /// usually the project's `plugins/` directory. See [`crate::plugins::wasm`].
+    pub fn register_wasm_plugins(&mut self, files: &dyn FileSource, dir: &Path) -> Result<()> {
+        for component in crate::plugins::wasm::load_dir(files, dir)? {
+            self.register(Box::new(component));
+        }
+        Ok(())
+    }
+
+    /// Load Tera templates from `dir`, usually the project's `theme/components/`,
+    /// to render directives that have no registered component.
+    pub fn load_templates(&mut self, files: &dyn FileSource, dir: &Path) -> Result<()> {
+        self.templates = TemplateComponents::load(files, dir)?;
+        Ok(())
+    }
+
+    /// Check a directive's attributes against its component's schema. Directives
+    /// without a registered component, or whose component declares no schema,
+    /// are always valid.
+    pub fn validate(&self, block: &DirectiveBlock) -> Vec<Violation> {
+        match self.components.get(&block.name).and_then(|c| c.schema()) {
+            Some(schema) => schema::validate(schema, &block.attributes),
+            None => Vec::new(),
+        }
+    }
+
+    /// Render a directive block using the registered component, or else a
+    /// `<name>.html` template. Falls back to a generic div wrapper if neither exists.
+    ///
+    /// Attributes that break the component's schema are reported as warnings;
+    /// the block is still rendered. A component that fails, such as a plugin
+    /// responding with an `error`, fails the page; a template that fails is
+    /// reported as a warning and shown in place of the block.
+    pub fn render_block(&self, block: &DirectiveBlock) -> Result<String> {
+        for violation in self.validate(block) {
+            crate::diagnostics::warn_invalid_directive_attribute(&block.name, &violation);
+        }
+
         let body_html = crate::pipeline::markdown::render(&block.body);
         let ctx = ComponentContext {
             attributes: block.attributes.clone(),
@@ -66,22 +116,25 @@ impl ComponentRegistry {
         };
 
         if let Some(component) = self.components.get(&block.name) {
-            match component.render(&ctx) {
-                Ok(html) => html,
-                Err(e) => {
+            component.render(&ctx)
+        } else if self.templates.has(&block.name) {
+            Ok(self
+                .templates
+                .render(&block.name, &ctx)
+                .unwrap_or_else(|e| {
+                    crate::diagnostics::warn_component_template(&block.name, &e.to_string());
                     format!(
                         "<div class=\"directive-error\">Error rendering {}: {}</div>",
                         block.name, e
                     )
-                }
-            }
+                }))
         } else {
             // Default: wrap in a div with the directive name as class
             let attrs = attr_string(&block.attributes);
-            format!(
+            Ok(format!(
                 "<div class=\"{}\"{}>\n{}</div>",
                 block.name, attrs, ctx.body_html
-            )
+            ))
         }
     }
 }
@@ -114,7 +167,7 @@ mod tests {
             attributes: HashMap::new(),
             body: "This is important.".to_string(),
         };
-        let html = registry.render_block(&block);
+        let html = registry.render_block(&block).unwrap();
         assert!(html.contains("note"));
         assert!(html.contains("This is important."));
     }
@@ -130,10 +183,70 @@ mod tests {
             ]),
             body: "".to_string(),
         };
-        let html = registry.render_block(&block);
+        let html = registry.render_block(&block).unwrap();
         assert!(html.contains("<span class=\"lozenge yellow\">Not Done</span>"));
     }
 
+    #[test]
+    fn builtin_schemas_reject_typos() {
+        let registry = ComponentRegistry::with_builtins();
+        let block = DirectiveBlock {
+            name: "note".to_string(),
+            attributes: HashMap::from([("titel".to_string(), "x".to_string())]),
+            body: "Body".to_string(),
+        };
+        let violations = registry.validate(&block);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "unknown attribute 'titel' (accepts title)"
+        );
+
+        // Still rendered, with the default title
+        let (html, warnings) =
+            crate::diagnostics::capture(|| registry.render_block(&block).unwrap());
+        assert!(html.contains("<p class=\"admonition-title\">Note</p>"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "invalid-directive-attribute");
+        assert_eq!(
+            warnings[0].message,
+            ":::note: unknown attribute 'titel' (accepts title)"
+        );
+    }
+
+    #[test]
+    fn lozenge_schema_checks_values() {
+        let registry = ComponentRegistry::with_builtins();
+        let block = DirectiveBlock {
+            name: "lozenge".to_string(),
+            attributes: HashMap::from([("type".to_string(), "purple".to_string())]),
+            body: String::new(),
+        };
+        let messages: Vec<_> = registry
+            .validate(&block)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "attribute 'type' must be one of default, in-progress, warning, success, error, got 'purple'",
+                "missing required attribute 'text'",
+            ]
+        );
+    }
+
+    #[test]
+    fn components_without_schema_accept_anything() {
+        let registry = ComponentRegistry::with_builtins();
+        let block = DirectiveBlock {
+            name: "custom-thing".to_string(),
+            attributes: HashMap::from([("anything".to_string(), "goes".to_string())]),
+            body: String::new(),
+        };
+        assert!(registry.validate(&block).is_empty());
+    }
+
     #[test]
     fn registry_renders_unknown_as_div() {
         let registry = ComponentRegistry::with_builtins();
@@ -142,10 +255,81 @@ mod tests {
             attributes: HashMap::new(),
             body: "Body text".to_string(),
         };
-        let html = registry.render_block(&block);
+        let html = registry.render_block(&block).unwrap();
         assert!(html.contains("<div class=\"custom-thing\">"));
     }
 
+    #[test]
+    fn registry_renders_template_component() {
+        let files = crate::source::MemoryFiles::new().with(
+            "theme/components/card.html",
+            "<section class=\"card\">{{ body_html | safe }}</section>",
+        );
+        let mut registry = ComponentRegistry::with_builtins();
+        registry
+            .load_templates(&files, Path::new("theme/components"))
+            .unwrap();
+
+        let block = DirectiveBlock {
+            name: "card".to_string(),
+            attributes: HashMap::new(),
+            body: "Body text".to_string(),
+        };
+        let html = registry.render_block(&block).unwrap();
+        assert_eq!(html, "<section class=\"card\"><p>Body text</p>\n</section>");
+    }
+
+    #[test]
+    fn template_errors_warn() {
+        let files = crate::source::MemoryFiles::new().with(
+            "theme/components/card.html",
+            "{{ attributes.title | upper }}",
+        );
+        let mut registry = ComponentRegistry::with_builtins();
+        registry
+            .load_templates(&files, Path::new("theme/components"))
+            .unwrap();
+
+        let block = DirectiveBlock {
+            name: "card".to_string(),
+            attributes: HashMap::new(),
+            body: String::new(),
+        };
+        let source = "# Page\n\n:::card\n:::\n";
+        let (html, warnings) = crate::diagnostics::capture(|| {
+            crate::diagnostics::in_file(Path::new("docs/page.md"), source, || {
+                registry.render_block(&block).unwrap()
+            })
+        });
+        assert!(html.starts_with("<div class=\"directive-error\">"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "component-template-error");
+        assert!(
+            warnings[0].message.starts_with(
+                "component template card.html failed to render :::card in docs/page.md:3: "
+            ),
+            "{}",
+            warnings[0].message
+        );
+    }
+
+    #[test]
+    fn registered_component_wins_over_template() {
+        let files =
+            crate::source::MemoryFiles::new().with("theme/components/note.html", "<p>template</p>");
+        let mut registry = ComponentRegistry::with_builtins();
+        registry
+            .load_templates(&files, Path::new("theme/components"))
+            .unwrap();
+
+        let block = DirectiveBlock {
+            name: "note".to_string(),
+            attributes: HashMap::new(),
+            body: "Body".to_string(),
+        };
+        assert!(!registry.render_block(&block).unwrap().contains("template"));
+    }
+
     #[test]
     fn custom_component_registration() {
         struct MyComponent;
@@ -166,7 +350,7 @@ mod tests {
             attributes: HashMap::new(),
             body: "hello".to_string(),
         };
-        let html = registry.render_block(&block);
+        let html = registry.render_block(&block).unwrap();
         assert_eq!(html, "<custom>hello</custom>");
     }
 }
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Attributes every directive accepts, set by the `{#id .class}` shorthand.
+pub const GLOBAL_ATTRIBUTES: &[&str] = &["id", "class"];
+
+/// The kind of value an attribute takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    String,
+    /// A whole number, e.g. `columns="3"`.
+    Integer,
+    /// `true` or `false`.
+    Boolean,
+}
+
+impl AttributeType {
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            AttributeType::String => true,
+            AttributeType::Integer => value.parse::<i64>().is_ok(),
+            AttributeType::Boolean => matches!(value, "true" | "false"),
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeType::String => write!(f, "a string"),
+            AttributeType::Integer => write!(f, "an integer"),
+            AttributeType::Boolean => write!(f, "true or false"),
+        }
+    }
+}
+
+/// An attribute a [`Component`](super::Component) accepts.
+///
+/// ```
+/// use docanvil::components::schema::{AttributeSpec, AttributeType};
+///
+/// const SCHEMA: &[AttributeSpec] = &[
+///     AttributeSpec::new("title"),
+///     AttributeSpec::new("columns").of_type(AttributeType::Integer),
+///     AttributeSpec::new("kind").required().one_of(&["info", "tip"]),
+/// ];
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeSpec {
+    pub name: &'static str,
+    pub kind: AttributeType,
+    pub required: bool,
+    /// The only values the attribute may take. Empty allows any value of `kind`.
+    pub allowed: &'static [&'static str],
+}
+
+impl AttributeSpec {
+    /// An optional string attribute.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: AttributeType::String,
+            required: false,
+            allowed: &[],
+        }
+    }
+
+    pub const fn of_type(mut self, kind: AttributeType) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub const fn one_of(mut self, allowed: &'static [&'static str]) -> Self {
+        self.allowed = allowed;
+        self
+    }
+}
+
+/// A way a directive's attributes break its component's schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    Unknown {
+        name: String,
+        value: String,
+        accepted: Vec<&'static str>,
+    },
+    Missing(&'static str),
+    WrongType {
+        name: &'static str,
+        value: String,
+        expected: AttributeType,
+    },
+    NotAllowed {
+        name: &'static str,
+        value: String,
+        allowed: &'static [&'static str],
+    },
+}
+
+impl Violation {
+    /// The `name="value"` text the violation was found in, if the attribute is present.
+    pub fn source_text(&self) -> Option<String> {
+        match self {
+            Violation::Unknown { name, value, .. } => Some(format!("{name}=\"{value}\"")),
+            Violation::WrongType { name, value, .. }
+            | Violation::NotAllowed { name, value, .. } => Some(format!("{name}=\"{value}\"")),
+            Violation::Missing(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Unknown { name, accepted, .. } if accepted.is_empty() => {
+                write!(f, "unknown attribute '{name}'")
+            }
+            Violation::Unknown { name, accepted, .. } => {
+                write!(
+                    f,
+                    "unknown attribute '{name}' (accepts {})",
+                    accepted.join(", ")
+                )
+            }
+            Violation::Missing(name) => write!(f, "missing required attribute '{name}'"),
+            Violation::WrongType {
+                name,
+                value,
+                expected,
+            } => write!(f, "attribute '{name}' must be {expected}, got '{value}'"),
+            Violation::NotAllowed {
+                name,
+                value,
+                allowed,
+            } => write!(
+                f,
+                "attribute '{name}' must be one of {}, got '{value}'",
+                allowed.join(", ")
+            ),
+        }
+    }
+}
+
+/// Check `attributes` against `schema`, returning every violation in a stable order:
+/// unknown attributes (sorted by name), then each spec in schema order.
+pub fn validate(schema: &[AttributeSpec], attributes: &HashMap<String, String>) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let mut unknown: Vec<_> = attributes
+        .iter()
+        .filter(|(name, _)| {
+            !GLOBAL_ATTRIBUTES.contains(&name.as_str()) && !schema.iter().any(|s| s.name == *name)
+        })
+        .collect();
+    unknown.sort();
+    for (name, value) in unknown {
+        violations.push(Violation::Unknown {
+            name: name.clone(),
+            value: value.clone(),
+            accepted: schema.iter().map(|s| s.name).collect(),
+        });
+    }
+
+    for spec in schema {
+        let Some(value) = attributes.get(spec.name) else {
+            if spec.required {
+                violations.push(Violation::Missing(spec.name));
+            }
+            continue;
+        };
+        if !spec.kind.accepts(value) {
+            violations.push(Violation::WrongType {
+                name: spec.name,
+                value: value.clone(),
+                expected: spec.kind,
+            });
+        } else if !spec.allowed.is_empty() && !spec.allowed.contains(&value.as_str()) {
+            violations.push(Violation::NotAllowed {
+                name: spec.name,
+                value: value.clone(),
+                allowed: spec.allowed,
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[AttributeSpec] = &[
+        AttributeSpec::new("title"),
+        AttributeSpec::new("columns").of_type(AttributeType::Integer),
+        AttributeSpec::new("open").of_type(AttributeType::Boolean),
+        AttributeSpec::new("kind")
+            .required()
+            .one_of(&["info", "tip"]),
+    ];
+
+    fn attrs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_attributes_pass() {
+        let attributes = attrs(&[
+            ("title", "Hi"),
+            ("columns", "3"),
+            ("open", "true"),
+            ("kind", "tip"),
+            ("id", "x"),
+            ("class", "wide"),
+        ]);
+        assert!(validate(SCHEMA, &attributes).is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_attribute() {
+        let violations = validate(SCHEMA, &attrs(&[("titel", "x"), ("kind", "info")]));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "unknown attribute 'titel' (accepts title, columns, open, kind)"
+        );
+        assert_eq!(violations[0].source_text().as_deref(), Some("titel=\"x\""));
+    }
+
+    #[test]
+    fn reports_missing_required_attribute() {
+        let violations = validate(SCHEMA, &attrs(&[]));
+        assert_eq!(violations, vec![Violation::Missing("kind")]);
+        assert_eq!(violations[0].source_text(), None);
+    }
+
+    #[test]
+    fn reports_wrong_type() {
+        let violations = validate(
+            SCHEMA,
+            &attrs(&[("columns", "three"), ("open", "yes"), ("kind", "info")]),
+        );
+        assert_eq!(
+            violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "attribute 'columns' must be an integer, got 'three'",
+                "attribute 'open' must be true or false, got 'yes'",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_value_not_allowed() {
+        let violations = validate(SCHEMA, &attrs(&[("kind", "danger")]));
+        assert_eq!(
+            violations[0].to_string(),
+            "attribute 'kind' must be one of info, tip, got 'danger'"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use tera::{Context, Tera};
+
+use crate::components::ComponentContext;
+use crate::error::{Error, Result};
+use crate::source::FileSource;
+
+/// Directory, relative to the project root, that component templates are loaded from.
+pub const TEMPLATES_DIR: &str = "theme/components";
+
+/// Tera templates that render `:::name` directives with no registered
+/// [`Component`](super::Component): `theme/components/<name>.html`.
+///
+/// Templates see `attributes`, `body_html` and `body_raw`. Every `.html` file
+/// under the directory is loaded, so templates can `{% include %}` or import
+/// macros from subdirectories.
+#[derive(Default)]
+pub struct TemplateComponents {
+    tera: Tera,
+}
+
+impl TemplateComponents {
+    /// Load every `.html` file under `dir`. A missing directory loads nothing.
+    pub fn load(files: &dyn FileSource, dir: &Path) -> Result<Self> {
+        let mut templates = Vec::new();
+        for path in files.walk(dir) {
+            if path.extension().is_none_or(|ext| ext != "html") {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let name = relative.to_string_lossy().replace('\\', "/");
+            templates.push((name, files.read_to_string(&path)?));
+        }
+
+        let mut tera = Tera::default();
+        tera.add_raw_templates(templates)
+            .map_err(|e| Error::Render(format!("failed to parse component template: {e}")))?;
+        Ok(Self { tera })
+    }
+
+    /// Whether there is a template for the directive `name`.
+    pub fn has(&self, name: &str) -> bool {
+        let file = format!("{name}.html");
+        self.tera.get_template_names().any(|t| t == file)
+    }
+
+    /// Render the template for the directive `name`.
+    pub fn render(&self, name: &str, ctx: &ComponentContext) -> Result<String> {
+        let mut context = Context::new();
+        context.insert("attributes", &ctx.attributes);
+        context.insert("body_html", &ctx.body_html);
+        context.insert("body_raw", &ctx.body_raw);
+
+        self.tera
+            .render(&format!("{name}.html"), &context)
+            .map_err(|e| {
+                // Tera keeps the useful part of render errors in the source chain
+                let mut message = e.to_string();
+                let mut source = std::error::Error::source(&e);
+                while let Some(cause) = source {
+                    message.push_str(&format!(": {cause}"));
+                    source = cause.source();
+                }
+                Error::Render(message)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MemoryFiles;
+    use std::collections::HashMap;
+
+    fn context(attributes: &[(&str, &str)], body: &str) -> ComponentContext {
+        ComponentContext {
+            attributes: attributes
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            body_raw: body.to_string(),
+            body_html: format!("<p>{body}</p>\n"),
+        }
+    }
+
+    #[test]
+    fn renders_with_attributes_and_body() {
+        let files = MemoryFiles::new().with(
+            "theme/components/card.html",
+            r#"<div class="card"><h3>{{ attributes.title }}</h3>{{ body_html | safe }}<!-- {{ body_raw }} --></div>"#,
+        );
+        let templates = TemplateComponents::load(&files, Path::new(TEMPLATES_DIR)).unwrap();
+        assert!(templates.has("card"));
+        assert!(!templates.has("banner"));
+
+        let html = templates
+            .render("card", &context(&[("title", "Tips & tricks")], "Hello"))
+            .unwrap();
+        assert_eq!(
+            html,
+            "<div class=\"card\"><h3>Tips &amp; tricks</h3><p>Hello</p>\n<!-- Hello --></div>"
+        );
+    }
+
+    #[test]
+    fn includes_partials_from_subdirectories() {
+        let files = MemoryFiles::new()
+            .with(
+                "theme/components/banner.html",
+                r#"{% include "partials/icon.html" %}{{ body_raw }}"#,
+            )
+            .with("theme/components/partials/icon.html", "<i></i>");
+        let templates = TemplateComponents::load(&files, Path::new(TEMPLATES_DIR)).unwrap();
+        let html = templates.render("banner", &context(&[], "Hi")).unwrap();
+        assert_eq!(html, "<i></i>Hi");
+    }
+
+    #[test]
+    fn missing_directory_loads_nothing() {
+        let templates =
+            TemplateComponents::load(&MemoryFiles::new(), Path::new(TEMPLATES_DIR)).unwrap();
+        assert!(!templates.has("card"));
+    }
+
+    #[test]
+    fn parse_error_fails_to_load() {
+        let files = MemoryFiles::new().with("theme/components/card.html", "{% if %}");
+        let err = TemplateComponents::load(&files, Path::new(TEMPLATES_DIR))
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("failed to parse component template")
+        );
+    }
+
+    #[test]
+    fn render_error_explains_cause() {
+        let files = MemoryFiles::new().with(
+            "theme/components/card.html",
+            "{{ attributes.title | upper }}",
+        );
+        let templates = TemplateComponents::load(&files, Path::new(TEMPLATES_DIR)).unwrap();
+        let err = templates.render("card", &context(&[], "")).unwrap_err();
+        assert!(err.to_string().contains("title"), "{err}");
+    }
+}
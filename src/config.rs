@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::path::{Path, PathBuf};
 
@@ -60,6 +60,8 @@ pub struct VersionConfig {
     pub enabled: Vec<String>,
     /// Human-readable display names for versions (e.g. {"v1": "v1.0", "v2": "v2.0 (latest)"}).
     pub display_names: HashMap<String, String>,
+    /// Per-version overrides of `[variables]` (`[version.variables.v1]`).
+    pub variables: HashMap<String, HashMap<String, String>>,
 }
 
 /// Localisation configuration for multi-language documentation sites.
@@ -76,6 +78,8 @@ pub struct LocaleConfig {
     pub auto_detect: bool,
     /// Flag emoji overrides for locales (e.g. {"en": "🇺🇸"} to use US flag instead of default GB).
     pub flags: HashMap<String, String>,
+    /// Per-locale overrides of `[variables]` (`[locale.variables.fr]`).
+    pub variables: HashMap<String, HashMap<String, String>>,
 }
 
 impl Default for LocaleConfig {
@@ -86,6 +90,7 @@ impl Default for LocaleConfig {
             display_names: HashMap::new(),
             auto_detect: true,
             flags: HashMap::new(),
+            variables: HashMap::new(),
         }
     }
 }
@@ -127,6 +132,66 @@ impl Default for DoctorConfig {
     }
 }
 
+/// Front matter configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FrontMatterConfig {
+    /// Rules for front matter fields, keyed by field name (`[frontmatter.schema.owner]`).
+    /// Checked on every build and by `docanvil doctor`.
+    pub schema: BTreeMap<String, FieldRule>,
+}
+
+/// A `[frontmatter.schema.<field>]` rule.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FieldRule {
+    /// The type the field's value must have. Any type is accepted if unset.
+    #[serde(rename = "type")]
+    pub kind: Option<FieldType>,
+    pub required: bool,
+    /// The only values the field may take.
+    #[serde(rename = "enum")]
+    pub allowed: Vec<String>,
+    /// Layout of a `date` field using `YYYY`, `MM` and `DD` (default: "YYYY-MM-DD").
+    pub format: Option<String>,
+    /// Content folders the rule applies to, relative to the content directory
+    /// (e.g. `["compliance"]`). Applies to every page if empty.
+    pub folders: Vec<PathBuf>,
+}
+
+impl FieldRule {
+    /// Whether the rule applies to a page at `path`, relative to the content directory.
+    pub fn applies_to(&self, path: &Path) -> bool {
+        self.folders.is_empty() || self.folders.iter().any(|f| path.starts_with(f))
+    }
+}
+
+/// The type of a front matter field's value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// A string in the rule's `format`.
+    Date,
+    List,
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FieldType::String => "a string",
+            FieldType::Integer => "an integer",
+            FieldType::Number => "a number",
+            FieldType::Boolean => "true or false",
+            FieldType::Date => "a date",
+            FieldType::List => "a list",
+        })
+    }
+}
+
 /// Returns `true` for right-to-left locales.
 pub fn is_rtl_locale(code: &str) -> bool {
     matches!(code, "ar" | "he" | "ur" | "fa" | "ug")
@@ -147,6 +212,41 @@ pub struct Config {
     pub version: VersionConfig,
     pub pdf: PdfConfig,
     pub doctor: DoctorConfig,
+    pub frontmatter: FrontMatterConfig,
+    pub plugins: Vec<PluginConfig>,
+    /// Values for `{{ name }}` placeholders in pages (`[variables]`).
+    pub variables: HashMap<String, String>,
+}
+
+/// An external plugin from a `[[plugins]]` entry.
+///
+/// The command is started once per build and exchanges newline-delimited JSON
+/// over stdin/stdout (see [`crate::plugins`]).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    /// Executable to run. Paths containing a `/` are relative to the project root.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Pipeline stages the plugin transforms or observes.
+    #[serde(default)]
+    pub hooks: Vec<PluginHook>,
+    /// Directive names (`:::name`) rendered by the plugin.
+    #[serde(default)]
+    pub components: Vec<String>,
+}
+
+/// A pipeline stage a plugin can hook into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginHook {
+    /// Transform page Markdown before any other processing.
+    Markdown,
+    /// Transform each page's rendered HTML body.
+    Html,
+    /// Run once after the site has been written.
+    PostBuild,
 }
 
 #[derive(Debug, Deserialize)]
@@ -367,6 +467,24 @@ impl Config {
             .unwrap_or_else(|| code.to_uppercase())
     }
 
+    /// The `[variables]` for pages of `version` in `locale`: the site-wide
+    /// values, overridden by the version's and then the locale's.
+    pub fn variables_for(
+        &self,
+        version: Option<&str>,
+        locale: Option<&str>,
+    ) -> HashMap<String, String> {
+        let mut variables = self.variables.clone();
+        let overrides = [
+            version.and_then(|v| self.version.variables.get(v)),
+            locale.and_then(|l| self.locale.variables.get(l)),
+        ];
+        for values in overrides.into_iter().flatten() {
+            variables.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        variables
+    }
+
     /// Validate version configuration. Returns an error if the config is inconsistent.
     fn validate_version(&self, config_path: &Path) -> Result<()> {
         if let Some(ref current) = self.version.current {
@@ -387,6 +505,20 @@ impl Config {
                 )));
             }
         }
+        if let Some(version) = self
+            .version
+            .variables
+            .keys()
+            .find(|v| !self.version.enabled.contains(v))
+        {
+            return Err(Error::General(format!(
+                "{}: version.variables.{} is set but '{}' is not in version.enabled {:?}",
+                config_path.display(),
+                version,
+                version,
+                self.version.enabled
+            )));
+        }
         Ok(())
     }
 
@@ -416,6 +548,20 @@ impl Config {
                 config_path.display()
             )));
         }
+        if let Some(locale) = self
+            .locale
+            .variables
+            .keys()
+            .find(|l| !self.locale.enabled.contains(l))
+        {
+            return Err(Error::General(format!(
+                "{}: locale.variables.{} is set but '{}' is not in locale.enabled {:?}",
+                config_path.display(),
+                locale,
+                locale,
+                self.locale.enabled
+            )));
+        }
         Ok(())
     }
 
@@ -635,6 +781,129 @@ default = "en"
         assert!(result.is_err());
     }
 
+    #[test]
+    fn plugins_config() {
+        let toml = r#"
+[[plugins]]
+name = "lint"
+command = "python3"
+args = ["plugins/lint.py"]
+hooks = ["markdown", "post-build"]
+
+[[plugins]]
+name = "api"
+command = "./plugins/api"
+components = ["api-endpoint"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.plugins.len(), 2);
+        assert_eq!(config.plugins[0].args, vec!["plugins/lint.py"]);
+        assert_eq!(
+            config.plugins[0].hooks,
+            vec![PluginHook::Markdown, PluginHook::PostBuild]
+        );
+        assert!(config.plugins[1].hooks.is_empty());
+        assert_eq!(config.plugins[1].components, vec!["api-endpoint"]);
+    }
+
+    #[test]
+    fn plugins_unknown_hook_errors() {
+        let toml = r#"
+[[plugins]]
+name = "lint"
+command = "lint"
+hooks = ["pre-render"]
+"#;
+        assert!(toml::from_str::<Config>(toml).is_err());
+    }
+
+    #[test]
+    fn frontmatter_schema_config() {
+        let config: Config = toml::from_str(
+            r#"
+[frontmatter.schema.owner]
+type = "string"
+required = true
+folders = ["compliance"]
+
+[frontmatter.schema.status]
+enum = ["draft", "approved"]
+
+[frontmatter.schema.review_date]
+type = "date"
+format = "DD/MM/YYYY"
+"#,
+        )
+        .unwrap();
+        let schema = &config.frontmatter.schema;
+        assert_eq!(schema.len(), 3);
+        assert_eq!(schema["owner"].kind, Some(FieldType::String));
+        assert!(schema["owner"].required);
+        assert!(schema["owner"].applies_to(Path::new("compliance/policy.md")));
+        assert!(!schema["owner"].applies_to(Path::new("guides/setup.md")));
+        assert_eq!(schema["status"].allowed, ["draft", "approved"]);
+        assert!(schema["status"].applies_to(Path::new("guides/setup.md")));
+        assert_eq!(schema["review_date"].format.as_deref(), Some("DD/MM/YYYY"));
+    }
+
+    #[test]
+    fn variables_with_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+[variables]
+product_name = "Anvil"
+release = "2.0"
+
+[version]
+enabled = ["v1", "v2"]
+
+[version.variables.v1]
+release = "1.4"
+
+[locale]
+default = "en"
+enabled = ["en", "fr"]
+
+[locale.variables.fr]
+product_name = "Enclume"
+"#,
+        )
+        .unwrap();
+        let vars = config.variables_for(None, None);
+        assert_eq!(vars["product_name"], "Anvil");
+        assert_eq!(vars["release"], "2.0");
+        let vars = config.variables_for(Some("v1"), Some("fr"));
+        assert_eq!(vars["product_name"], "Enclume");
+        assert_eq!(vars["release"], "1.4");
+        assert_eq!(
+            config.variables_for(Some("v2"), Some("en")),
+            config.variables
+        );
+    }
+
+    #[test]
+    fn variables_for_unknown_locale_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = r#"
+[locale]
+default = "en"
+enabled = ["en", "fr"]
+
+[locale.variables.de]
+product_name = "Amboss"
+"#;
+        std::fs::write(dir.path().join("docanvil.toml"), toml).unwrap();
+        let msg = Config::load(dir.path()).unwrap_err().to_string();
+        assert!(msg.contains("locale.variables.de"), "{msg}");
+    }
+
+    #[test]
+    fn frontmatter_schema_unknown_type_errors() {
+        let result: std::result::Result<Config, _> =
+            toml::from_str("[frontmatter.schema.owner]\ntype = \"person\"\n");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn color_mode_display_and_serialize() {
         assert_eq!(ColorMode::Light.to_string(), "light");
@@ -1,12 +1,156 @@
-use std::path::Path;
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use owo_colors::OwoColorize;
 
+use crate::components::schema::Violation;
+use crate::doctor::{Diagnostic, Severity};
+use crate::pipeline::frontmatter::{ParseError, SchemaViolation};
+
 static WARNING_COUNT: AtomicUsize = AtomicUsize::new(0);
 
-fn increment() {
+thread_local! {
+    /// When set, warnings emitted on this thread are buffered instead of printed.
+    static CAPTURED: RefCell<Option<Vec<Warning>>> = const { RefCell::new(None) };
+
+    /// The file being processed on this thread and its contents, used to give
+    /// warnings a file and line.
+    static CURRENT_FILE: RefCell<Option<(PathBuf, String)>> = const { RefCell::new(None) };
+}
+
+/// A warning raised during a build, with a hint on how to fix it.
+///
+/// `check` and `category` use the same identifiers as the matching
+/// `docanvil doctor` check, so build warnings can be reported in the same
+/// machine-readable formats (see [`Warning::to_diagnostic`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub check: &'static str,
+    pub category: &'static str,
+    pub message: String,
+    pub hint: String,
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+}
+
+impl Warning {
+    /// Convert to a doctor [`Diagnostic`] for checkstyle, JUnit or JSON output.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            check: self.check,
+            category: self.category,
+            severity: Severity::Warning,
+            message: self.message.clone(),
+            file: self.file.clone(),
+            line: self.line,
+            fix: None,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}\n  {}: {}",
+            "warning".yellow().bold(),
+            self.message,
+            "hint".dimmed(),
+            self.hint
+        )
+    }
+}
+
+/// Where a warning points: a file and, when known, a 1-based line.
+type Location = (Option<PathBuf>, Option<usize>);
+
+/// Count a warning and report it.
+fn emit(
+    check: &'static str,
+    category: &'static str,
+    (file, line): Location,
+    message: impl Display,
+    hint: impl Display,
+) {
     WARNING_COUNT.fetch_add(1, Ordering::Relaxed);
+    report(Warning {
+        check,
+        category,
+        message: message.to_string(),
+        hint: hint.to_string(),
+        file,
+        line,
+    });
+}
+
+/// Buffer a warning if this thread is inside [`capture`], otherwise print it.
+fn report(warning: Warning) {
+    let warning = CAPTURED.with(|c| match c.borrow_mut().as_mut() {
+        Some(buffer) => {
+            buffer.push(warning);
+            None
+        }
+        None => Some(warning),
+    });
+    if let Some(warning) = warning {
+        eprintln!("{warning}");
+    }
+}
+
+/// Run `f`, buffering the warnings it emits on the current thread instead of
+/// printing them. Used by parallel rendering so warnings can be printed in page
+/// order with [`replay`], and to collect a build's warnings for
+/// [`Site::render`](crate::site::Site::render) and `docanvil build --format`.
+/// Buffered warnings still count towards [`warning_count`].
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Warning>) {
+    let previous = CAPTURED.with(|c| c.replace(Some(Vec::new())));
+    let result = f();
+    let captured = CAPTURED.with(|c| c.replace(previous)).unwrap_or_default();
+    (result, captured)
+}
+
+/// Run `f`, dropping the warnings it emits on this thread. Unlike [`capture`],
+/// they don't count towards [`warning_count`] either.
+pub fn discard<R>(f: impl FnOnce() -> R) -> R {
+    let (result, warnings) = capture(f);
+    WARNING_COUNT.fetch_sub(warnings.len(), Ordering::Relaxed);
+    result
+}
+
+/// Report warnings buffered by [`capture`] on another thread. They are printed,
+/// or buffered again if this thread is itself capturing; either way they are
+/// not counted twice.
+pub fn replay(warnings: &[Warning]) {
+    for warning in warnings {
+        report(warning.clone());
+    }
+}
+
+/// Run `f` with `file` (whose contents are `source`) as the current file, so
+/// warnings emitted by `f` point at it.
+pub fn in_file<R>(file: &Path, source: &str, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_FILE.with(|c| c.replace(Some((file.to_path_buf(), source.to_string()))));
+    let result = f();
+    CURRENT_FILE.with(|c| c.replace(previous));
+    result
+}
+
+/// Locate a warning in the current file: the first line containing `needle`, if any.
+fn locate(needle: Option<&str>) -> Location {
+    CURRENT_FILE.with(|c| match c.borrow().as_ref() {
+        Some((file, source)) => {
+            let line = needle.and_then(|needle| {
+                source
+                    .lines()
+                    .position(|line| line.contains(needle))
+                    .map(|i| i + 1)
+            });
+            (Some(file.clone()), line)
+        }
+        None => (None, None),
+    })
 }
 
 /// Return the number of warnings emitted since the last reset.
@@ -21,127 +165,318 @@ pub fn reset_warnings() {
 
 /// Emit a warning about a broken wiki-link.
 pub fn warn_broken_link(source_file: &Path, link_target: &str) {
-    increment();
-    eprintln!(
-        "{}: broken link [[{}]] in {}",
-        "warning".yellow().bold(),
-        link_target,
-        source_file.display()
+    let (file, line) = match locate(Some(&format!("[[{link_target}"))) {
+        (Some(file), line) if file == source_file => (file, line),
+        _ => (source_file.to_path_buf(), None),
+    };
+    let at = match line {
+        Some(line) => format!("{}:{line}", file.display()),
+        None => file.display().to_string(),
+    };
+    emit(
+        "broken-wiki-link",
+        "content",
+        (Some(file), line),
+        format!("broken link [[{link_target}]] in {at}"),
+        "Run 'docanvil doctor' to check all links.",
+    );
+}
+
+/// Emit a warning about an include whose file, page or heading doesn't exist.
+pub fn warn_broken_include(target: &str) {
+    let location = locate(Some(target));
+    let at = describe(&location);
+    emit(
+        "broken-include",
+        "content",
+        location,
+        format!("broken include {target}{at}"),
+        "Include paths are relative to the project root; pages are included by slug. Run 'docanvil doctor' to check all includes.",
     );
-    eprintln!(
-        "  {}: Run 'docanvil doctor' to check all links.",
-        "hint".dimmed()
+}
+
+/// Emit a warning about a link to an `#anchor` the target page doesn't have.
+pub fn warn_broken_anchor(source_file: &Path, link: &str) {
+    let (file, line) = match locate(Some(link)) {
+        (Some(file), line) if file == source_file => (file, line),
+        _ => (source_file.to_path_buf(), None),
+    };
+    let at = match line {
+        Some(line) => format!("{}:{line}", file.display()),
+        None => file.display().to_string(),
+    };
+    emit(
+        "broken-anchor",
+        "content",
+        (Some(file), line),
+        format!("broken anchor {link} in {at}"),
+        "Anchors are the IDs on the linked page: headings, {#id}s and component output. Run 'docanvil doctor' to check all links.",
+    );
+}
+
+/// Emit a warning about a Markdown link to a `.md` file that isn't a page.
+pub fn warn_broken_markdown_link(source_file: &Path, href: &str) {
+    let (file, line) = match locate(Some(&format!("]({href}"))) {
+        (Some(file), line) if file == source_file => (file, line),
+        _ => (source_file.to_path_buf(), None),
+    };
+    let at = match line {
+        Some(line) => format!("{}:{line}", file.display()),
+        None => file.display().to_string(),
+    };
+    emit(
+        "broken-markdown-link",
+        "content",
+        (Some(file), line),
+        format!("broken link {href} in {at}"),
+        "Links to .md files are relative to the page. Run 'docanvil doctor' to check all links.",
+    );
+}
+
+/// Emit a warning about a code snippet whose file, region or lines can't be found.
+pub fn warn_broken_snippet(file: &str, reason: &str) {
+    let location = locate(Some(file));
+    let at = describe(&location);
+    emit(
+        "broken-snippet",
+        "content",
+        location,
+        format!("broken snippet {file}: {reason}{at}"),
+        "Snippet paths are relative to the project root. Check the file, its region markers or the line range.",
     );
 }
 
+/// Emit a warning about a `theme/components/` template that failed to render a
+/// directive.
+pub fn warn_component_template(name: &str, error: &str) {
+    let location = locate(Some(&format!(":::{name}")));
+    let at = describe(&location);
+    emit(
+        "component-template-error",
+        "theme",
+        location,
+        format!("component template {name}.html failed to render :::{name}{at}: {error}"),
+        "Check the template in theme/components/ against the directive's attributes.",
+    );
+}
+
+/// Emit a warning about a `{{ name }}` placeholder with no matching variable.
+pub fn warn_undefined_variable(name: &str, placeholder: &str) {
+    let location = locate(Some(placeholder));
+    let at = describe(&location);
+    emit(
+        "undefined-variable",
+        "content",
+        location,
+        format!("undefined variable '{name}'{at}"),
+        "Define it under [variables] in docanvil.toml, or put the placeholder in backticks to show it as written.",
+    );
+}
+
+/// Emit a warning about a file that ends up including itself.
+pub fn warn_include_cycle(target: &str) {
+    let location = locate(Some(target));
+    let at = describe(&location);
+    emit(
+        "include-cycle",
+        "content",
+        location,
+        format!("include cycle: {target} includes itself{at}"),
+        "Remove one of the includes in the cycle.",
+    );
+}
+
+/// ` in file:line` for a warning message, or nothing when there is no file.
+fn describe((file, line): &Location) -> String {
+    match (file, line) {
+        (Some(file), Some(line)) => format!(" in {}:{line}", file.display()),
+        (Some(file), None) => format!(" in {}", file.display()),
+        (None, _) => String::new(),
+    }
+}
+
 /// Emit a warning about a nav.toml entry referencing a page that doesn't exist.
 pub fn warn_nav_missing_page(slug: &str) {
-    increment();
-    eprintln!(
-        "{}: nav.toml references page '{}' which does not exist",
-        "warning".yellow().bold(),
-        slug
+    emit(
+        "nav-missing-page",
+        "config",
+        locate(Some(&format!("\"{slug}\""))),
+        format!("nav.toml references page '{}' which does not exist", slug),
+        "Check nav.toml or run 'docanvil doctor' for details.",
+    );
+}
+
+/// Emit a warning about a nav.toml sidebar for a section that has no pages.
+pub fn warn_nav_sidebar_empty(section: &str) {
+    emit(
+        "nav-sidebar-empty",
+        "config",
+        locate(Some(&format!("sidebars.{section}"))),
+        format!("nav.toml sidebar '{section}' matches no pages"),
+        "Sidebars are named after a top-level folder of the content directory.",
     );
-    eprintln!(
-        "  {}: Check nav.toml or run 'docanvil doctor' for details.",
-        "hint".dimmed()
+}
+
+/// Emit a warning about a nav.toml entry naming an icon that doesn't exist.
+pub fn warn_nav_unknown_icon(icon: &str) {
+    emit(
+        "nav-unknown-icon",
+        "config",
+        locate(Some(&format!("\"{icon}\""))),
+        format!("nav.toml uses unknown icon '{icon}'"),
+        "See the configuration guide for the list of built-in icons.",
     );
 }
 
 /// Emit a warning that site_url is not configured (sitemap will use relative URLs).
 pub fn warn_no_site_url() {
-    increment();
-    eprintln!(
-        "{}: site_url not set in [build] — sitemap.xml will use relative URLs",
-        "warning".yellow().bold()
-    );
-    eprintln!(
-        "  {}: Add site_url to [build] in docanvil.toml for absolute URLs.",
-        "hint".dimmed()
+    emit(
+        "site-url-missing",
+        "config",
+        (None, None),
+        "site_url not set in [build] — sitemap.xml will use relative URLs",
+        "Add site_url to [build] in docanvil.toml for absolute URLs.",
     );
 }
 
 /// Emit a warning that an autodiscover folder has no matching pages.
 pub fn warn_nav_autodiscover_empty(folder: &str) {
-    increment();
-    eprintln!(
-        "{}: nav.toml autodiscover folder '{}' matches no pages",
-        "warning".yellow().bold(),
-        folder
-    );
-    eprintln!(
-        "  {}: Check the folder path in nav.toml or add pages to it.",
-        "hint".dimmed()
+    emit(
+        "nav-autodiscover-empty",
+        "config",
+        locate(Some(&format!("\"{folder}\""))),
+        format!("nav.toml autodiscover folder '{}' matches no pages", folder),
+        "Check the folder path in nav.toml or add pages to it.",
     );
 }
 
 /// Emit a warning about a malformed HTML tag that prevented attribute injection.
 pub fn warn_malformed_attribute_tag() {
-    increment();
-    eprintln!(
-        "{}: malformed HTML tag — could not inject attribute block",
-        "warning".yellow().bold(),
+    emit(
+        "malformed-attribute-tag",
+        "content",
+        locate(None),
+        "malformed HTML tag — could not inject attribute block",
+        "Check your Markdown for unclosed HTML tags. Run 'docanvil doctor' for details.",
+    );
+}
+
+/// Emit a warning about front matter that could not be parsed.
+pub fn warn_frontmatter_parse_error(file: &Path, error: &ParseError) {
+    emit(
+        "frontmatter-parse-error",
+        "content",
+        (Some(file.to_path_buf()), Some(error.line)),
+        format!(
+            "invalid {} front matter in {}:{}:{}: {}",
+            error.format,
+            file.display(),
+            error.line,
+            error.column,
+            error.message
+        ),
+        "The page was built without its front matter. Run 'docanvil doctor' to check all pages.",
+    );
+}
+
+/// Emit a warning about a front matter field that breaks `[frontmatter.schema]`.
+pub fn warn_frontmatter_schema(file: &Path, violation: &SchemaViolation) {
+    emit(
+        "frontmatter-schema",
+        "content",
+        (Some(file.to_path_buf()), Some(violation.line)),
+        format!(
+            "{} in {}:{}",
+            violation.message,
+            file.display(),
+            violation.line
+        ),
+        "Fix the field or the [frontmatter.schema] rule in docanvil.toml. Run 'docanvil doctor' to check all pages.",
+    );
+}
+
+/// Emit a warning that a page's `layout` names a template that doesn't exist.
+pub fn warn_unknown_layout(file: &Path, line: Option<usize>, template: &str) {
+    emit(
+        "unknown-layout",
+        "theme",
+        (Some(file.to_path_buf()), line),
+        format!(
+            "layout '{template}' in {} not found in theme/templates/",
+            file.display()
+        ),
+        "The page was rendered with layout.html. Check the layout name or add the template.",
     );
-    eprintln!(
-        "  {}: Check your Markdown for unclosed HTML tags. Run 'docanvil doctor' for details.",
-        "hint".dimmed()
+}
+
+/// Emit a warning about a directive attribute its component does not accept.
+pub fn warn_invalid_directive_attribute(directive: &str, violation: &Violation) {
+    let needle = violation
+        .source_text()
+        .unwrap_or_else(|| format!(":::{directive}"));
+    emit(
+        "invalid-directive-attribute",
+        "content",
+        locate(Some(&needle)),
+        format!(":::{directive}: {violation}"),
+        "Check the attribute names and values, or run 'docanvil doctor' to check every directive.",
     );
 }
 
 /// Emit a warning about an unexpected asset path (e.g. from symlinks).
 pub fn warn_unexpected_asset_path(path: &Path) {
-    increment();
-    eprintln!(
-        "{}: unexpected asset path {} — skipping file",
-        "warning".yellow().bold(),
-        path.display()
-    );
-    eprintln!(
-        "  {}: This may be caused by symlinks. Check your assets directory for symlinks pointing outside the project.",
-        "hint".dimmed()
+    emit(
+        "unexpected-asset-path",
+        "project",
+        (Some(path.to_path_buf()), None),
+        format!("unexpected asset path {} — skipping file", path.display()),
+        "This may be caused by symlinks. Check your assets directory for symlinks pointing outside the project.",
     );
 }
 
 /// Emit a warning about an unexpected content path (e.g. from symlinks).
 pub fn warn_unexpected_content_path(path: &Path) {
-    increment();
-    eprintln!(
-        "{}: unexpected content path {} — skipping file",
-        "warning".yellow().bold(),
-        path.display()
-    );
-    eprintln!(
-        "  {}: This may be caused by symlinks in your content directory. Run 'docanvil doctor' to check your project structure.",
-        "hint".dimmed()
+    emit(
+        "unexpected-content-path",
+        "project",
+        (Some(path.to_path_buf()), None),
+        format!("unexpected content path {} — skipping file", path.display()),
+        "This may be caused by symlinks in your content directory. Run 'docanvil doctor' to check your project structure.",
     );
 }
 
 /// Emit a warning about a missing translation for a page in a locale.
 pub fn warn_missing_translation(slug: &str, locale: &str) {
-    increment();
-    eprintln!(
-        "{}: page '{}' has no translation for locale '{}'",
-        "warning".yellow().bold(),
-        slug,
-        locale
+    emit(
+        "missing-translation",
+        "locale",
+        (None, None),
+        format!("page '{}' has no translation for locale '{}'", slug, locale),
+        format!(
+            "Create a file with the '.{}.md' suffix to add a translation.",
+            locale
+        ),
     );
-    eprintln!(
-        "  {}: Create a file with the '.{}.md' suffix to add a translation.",
-        "hint".dimmed(),
-        locale
+}
+
+/// Emit a warning reported by a `[[plugins]]` plugin.
+pub fn warn_plugin(plugin: &str, message: &str) {
+    emit(
+        "plugin-warning",
+        "plugin",
+        locate(None),
+        format!("plugin '{}': {}", plugin, message),
+        "This warning comes from a plugin configured in docanvil.toml.",
     );
 }
 
 /// Emit a warning that a custom CSS file was not found.
 pub fn warn_custom_css_not_found(path: &str) {
-    increment();
-    eprintln!(
-        "{}: custom_css file not found: {}",
-        "warning".yellow().bold(),
-        path
-    );
-    eprintln!(
-        "  {}: Check the path in docanvil.toml, or run 'docanvil doctor --fix' to create it.",
-        "hint".dimmed()
+    emit(
+        "custom-css-not-found",
+        "theme",
+        (None, None),
+        format!("custom_css file not found: {}", path),
+        "Check the path in docanvil.toml, or run 'docanvil doctor --fix' to create it.",
     );
 }
@@ -3,9 +3,11 @@ use std::path::Path;
 use crate::config::Config;
 use crate::doctor::{Diagnostic, Severity};
 use crate::nav;
-use crate::project::PageInventory;
+use crate::project::{FOLDER_META_FILE, FolderMeta, PageInventory};
+use crate::render::icons;
+use crate::source::{DiskFiles, FileSource};
 
-/// Check configuration validity: file references, nav.toml, URLs.
+/// Check configuration validity: file references, nav.toml, `_folder.toml` files, URLs.
 pub fn check_config(
     project_root: &Path,
     config: &Config,
@@ -76,11 +78,31 @@ pub fn check_config(
     // Validate nav.toml
     let nav_path = project_root.join("nav.toml");
     if nav_path.exists() {
-        match nav::load_nav(project_root) {
-            Ok(Some(entries)) => {
+        match nav::load_nav_config_for_target(&DiskFiles, project_root, None, None) {
+            Ok(Some(nav_config)) => {
                 // Check for nav references to nonexistent pages
                 if let Some(inv) = inventory {
-                    check_nav_entries(&entries, inv, &mut diags);
+                    let lists = nav_config
+                        .nav
+                        .iter()
+                        .chain(nav_config.menus.values())
+                        .chain(nav_config.sidebars.values());
+                    for entries in lists {
+                        check_nav_entries(entries, inv, &mut diags);
+                    }
+                    for section in nav_config.sidebars.keys() {
+                        if !inv.folder_has_pages(section) {
+                            diags.push(Diagnostic {
+                                check: "nav-sidebar-empty",
+                                category: "config",
+                                severity: Severity::Warning,
+                                message: format!("nav.toml sidebar '{section}' matches no pages"),
+                                file: Some(nav_path.clone()),
+                                line: None,
+                                fix: None,
+                            });
+                        }
+                    }
                 }
             }
             Ok(None) => {}
@@ -98,6 +120,29 @@ pub fn check_config(
         }
     }
 
+    // Validate folder metadata files in the content directory
+    let content_dir = project_root.join(&config.project.content_dir);
+    for path in DiskFiles.walk(&content_dir).into_iter().filter(|path| {
+        path.file_name()
+            .is_some_and(|name| name == FOLDER_META_FILE)
+    }) {
+        let parsed = DiskFiles
+            .read_to_string(&path)
+            .map_err(crate::error::Error::from)
+            .and_then(|content| FolderMeta::parse(&content, &path));
+        if let Err(e) = parsed {
+            diags.push(Diagnostic {
+                check: "folder-meta-parse-error",
+                category: "config",
+                severity: Severity::Error,
+                message: format!("{FOLDER_META_FILE} parse error: {e}"),
+                file: Some(path),
+                line: None,
+                fix: None,
+            });
+        }
+    }
+
     diags
 }
 
@@ -129,6 +174,9 @@ fn check_nav_entries(
                 fix: None,
             });
         }
+        if let Some(icon) = &entry.icon {
+            check_nav_icon(icon, diags);
+        }
         if let Some(group) = &entry.group {
             check_nav_group_items(group, inventory, diags);
         }
@@ -154,8 +202,25 @@ fn check_nav_group_items(
                 fix: None,
             });
         }
+        if let Some(icon) = &item.icon {
+            check_nav_icon(icon, diags);
+        }
         if let Some(group) = &item.group {
             check_nav_group_items(group, inventory, diags);
         }
     }
 }
+
+fn check_nav_icon(icon: &str, diags: &mut Vec<Diagnostic>) {
+    if !icons::exists(icon) {
+        diags.push(Diagnostic {
+            check: "nav-unknown-icon",
+            category: "config",
+            severity: Severity::Warning,
+            message: format!("nav.toml uses unknown icon: {icon}"),
+            file: None,
+            line: None,
+            fix: None,
+        });
+    }
+}
@@ -4,21 +4,46 @@ use std::path::Path;
 use regex::Regex;
 use std::sync::LazyLock;
 
+use crate::components::{ComponentRegistry, schema};
 use crate::config::Config;
+use crate::diagnostics;
 use crate::doctor::{Diagnostic, Severity};
-use crate::project::PageInventory;
+use crate::pipeline::anchors::Anchors;
+use crate::pipeline::conditions;
+use crate::pipeline::directives::{self, DirectiveBlock};
+use crate::pipeline::frontmatter;
+use crate::pipeline::includes::{self, Includes};
+use crate::pipeline::variables;
+use crate::pipeline::wikilinks::FindAnchor;
+use crate::pipeline::{self, PipelineContext, links, markdown};
+use crate::plugins::{Plugins, wasm};
+use crate::project::{PageInfo, PageInventory};
+use crate::source::DiskFiles;
 
 static OPEN_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^(:{3,})\s*([\w][\w-]*)\s*(\{.*\})?\s*$").unwrap());
 
-/// Check content: broken wiki-links, unclosed directives, front-matter errors, duplicate slugs.
+/// Stands in for each inline directive so its line can be found afterwards.
+const INLINE_MARKER: &str = "\u{0}";
+
+/// Check content: broken wiki-links, `.md` links and anchors, broken includes and
+/// snippets, undefined variables, unclosed directives, invalid directive
+/// attributes, front-matter errors, duplicate slugs.
 pub fn check_content(
-    _project_root: &Path,
-    _config: &Config,
+    project_root: &Path,
+    config: &Config,
     inventory: &PageInventory,
 ) -> Vec<Diagnostic> {
     let mut diags = Vec::new();
 
+    // Components as the build sees them, since plugins can replace built-ins
+    let mut registry = ComponentRegistry::with_builtins();
+    let _ = registry.register_wasm_plugins(&DiskFiles, &project_root.join(wasm::PLUGINS_DIR));
+    let plugins = Plugins::new(&config.plugins, project_root);
+    plugins.register_components(&mut registry);
+    // Element IDs of linked pages, worked out once per version and locale
+    let mut anchors: HashMap<(Option<&str>, Option<&str>), Anchors> = HashMap::new();
+
     // Check for duplicate slugs (detected by checking if the inventory has fewer entries
     // than files scanned — but since PageInventory uses HashMap, duplicates overwrite silently).
     // We re-scan to detect duplicates.
@@ -32,14 +57,36 @@ pub fn check_content(
             Err(_) => continue,
         };
 
+        let (version, locale) = (page.version.as_deref(), page.locale.as_deref());
+        let variables = config.variables_for(version, locale);
+        let ctx = PipelineContext {
+            inventory,
+            registry: &registry,
+            base_url: "/",
+            highlighter: None,
+            project_root,
+            files: &DiskFiles,
+            locale,
+            version,
+            profile: None,
+            plugins: &plugins,
+            variables: &variables,
+            anchors: anchors.entry((version, locale)).or_default(),
+        };
+        let find_anchor = |page: &Path, anchor: &str| ctx.anchors.find(page, anchor, &ctx);
+
         check_broken_wikilinks(
             &source,
             &page.source_path,
-            page.locale.as_deref(),
+            locale,
             inventory,
+            &find_anchor,
             &mut diags,
         );
+        check_markdown_links(&source, page, &ctx, &find_anchor, &mut diags);
+        check_includes(&source, project_root, config, page, inventory, &mut diags);
         check_unclosed_directives(&source, &page.source_path, &mut diags);
+        check_directive_attributes(&source, &page.source_path, &registry, &mut diags);
         check_frontmatter(&source, &page.source_path, &mut diags);
     }
 
@@ -73,6 +120,7 @@ fn check_broken_wikilinks(
     source_path: &Path,
     locale: Option<&str>,
     inventory: &PageInventory,
+    find_anchor: &FindAnchor,
     diags: &mut Vec<Diagnostic>,
 ) {
     let mut remaining = source;
@@ -95,25 +143,52 @@ fn check_broken_wikilinks(
             };
 
             let target = target.trim();
-            let resolved = match locale {
-                Some(loc) => inventory
-                    .resolve_link_in_locale(target, loc)
-                    .or_else(|| inventory.resolve_link(target)),
-                None => inventory.resolve_link(target),
+            let pos = offset + start;
+            let line_start = source[..pos].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = source[pos..].find('\n').map_or(source.len(), |i| pos + i);
+            // `![[page]]` lines are includes, checked separately
+            let is_embed = includes::is_embed(&source[line_start..line_end]);
+            let (slug, anchor) = match target.split_once('#') {
+                Some((slug, anchor)) => (slug.trim(), anchor.trim()),
+                None => (target, ""),
             };
-            if !target.is_empty() && resolved.is_none() {
-                // Calculate line number
-                let pos = offset + start;
-                let line = source[..pos].matches('\n').count() + 1;
-                diags.push(Diagnostic {
-                    check: "broken-wiki-link",
-                    category: "content",
-                    severity: Severity::Warning,
-                    message: format!("Broken link [[{target}]]"),
-                    file: Some(source_path.to_path_buf()),
-                    line: Some(line),
-                    fix: None,
-                });
+            // `[[#anchor]]` points at this page
+            let linked = if slug.is_empty() {
+                Some(source_path)
+            } else {
+                match locale {
+                    Some(loc) => inventory
+                        .resolve_link_in_locale(slug, loc)
+                        .or_else(|| inventory.resolve_link(slug)),
+                    None => inventory.resolve_link(slug),
+                }
+                .map(|page| page.source_path.as_path())
+            };
+            let line = source[..pos].matches('\n').count() + 1;
+            if !target.is_empty() && !is_embed {
+                match linked {
+                    None => diags.push(Diagnostic {
+                        check: "broken-wiki-link",
+                        category: "content",
+                        severity: Severity::Warning,
+                        message: format!("Broken link [[{target}]]"),
+                        file: Some(source_path.to_path_buf()),
+                        line: Some(line),
+                        fix: None,
+                    }),
+                    Some(page) if !anchor.is_empty() && find_anchor(page, anchor).is_none() => {
+                        diags.push(Diagnostic {
+                            check: "broken-anchor",
+                            category: "content",
+                            severity: Severity::Warning,
+                            message: format!("Broken anchor [[{target}]]"),
+                            file: Some(source_path.to_path_buf()),
+                            line: Some(line),
+                            fix: None,
+                        })
+                    }
+                    Some(_) => {}
+                }
             }
 
             offset += start + 2 + end + 2;
@@ -124,6 +199,60 @@ fn check_broken_wikilinks(
     }
 }
 
+/// Expand and render the page's Markdown and report links to `.md` files that
+/// aren't pages, or to anchors those pages don't have, the way the build does.
+fn check_markdown_links(
+    source: &str,
+    page: &PageInfo,
+    ctx: &PipelineContext,
+    find_anchor: &FindAnchor,
+    diags: &mut Vec<Diagnostic>,
+) {
+    // Include and variable warnings are reported by `check_includes`
+    let body = diagnostics::discard(|| {
+        pipeline::expand(frontmatter::strip(source), &page.source_path, ctx)
+    });
+    let (_, warnings) = diagnostics::capture(|| {
+        diagnostics::in_file(&page.source_path, source, || {
+            let html = markdown::render(&body);
+            links::resolve(
+                &html,
+                ctx.inventory,
+                &page.source_path,
+                "/",
+                page.locale.as_deref(),
+                find_anchor,
+            )
+        })
+    });
+    diags.extend(warnings.iter().map(|w| w.to_diagnostic()));
+}
+
+/// Expand the page's includes, snippets and variables the way the build does
+/// and report its warnings.
+fn check_includes(
+    source: &str,
+    project_root: &Path,
+    config: &Config,
+    page: &PageInfo,
+    inventory: &PageInventory,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let includes = Includes {
+        files: &DiskFiles,
+        project_root,
+        inventory,
+        locale: page.locale.as_deref(),
+    };
+    let vars = config.variables_for(page.version.as_deref(), page.locale.as_deref());
+    let (_, warnings) = diagnostics::capture(|| {
+        diagnostics::in_file(&page.source_path, source, || {
+            variables::substitute(&includes.expand(source, &page.source_path), &vars)
+        })
+    });
+    diags.extend(warnings.iter().map(|w| w.to_diagnostic()));
+}
+
 fn check_unclosed_directives(source: &str, source_path: &Path, diags: &mut Vec<Diagnostic>) {
     let lines: Vec<&str> = source.lines().collect();
     let mut stack: Vec<(String, usize, usize)> = Vec::new(); // (name, colons, line_number)
@@ -163,33 +292,79 @@ fn check_unclosed_directives(source: &str, source_path: &Path, diags: &mut Vec<D
     }
 }
 
-fn check_frontmatter(source: &str, source_path: &Path, diags: &mut Vec<Diagnostic>) {
-    let trimmed = source.trim_start();
-    if !trimmed.starts_with("---") {
-        return;
+fn check_directive_attributes(
+    source: &str,
+    source_path: &Path,
+    registry: &ComponentRegistry,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let mut found: Vec<(usize, DirectiveBlock)> = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        if let Some(caps) = OPEN_RE.captures(line) {
+            found.push((
+                i + 1,
+                DirectiveBlock {
+                    name: caps[2].to_string(),
+                    attributes: caps
+                        .get(3)
+                        .map(|m| directives::parse_attributes(m.as_str()))
+                        .unwrap_or_default(),
+                    body: String::new(),
+                },
+            ));
+        }
     }
 
-    let after_open = &trimmed[3..];
-    let rest = after_open
-        .strip_prefix('\n')
-        .or_else(|| after_open.strip_prefix("\r\n"));
-    let Some(rest) = rest else {
-        return;
-    };
+    // Find inline directives the way the build does, skipping code, then
+    // recover their lines from where the markers ended up
+    let mut inline = Vec::new();
+    let marked = directives::process_inline_directives(source, &mut |block| {
+        inline.push(block.clone());
+        INLINE_MARKER.to_string()
+    });
+    let mut inline = inline.into_iter();
+    for (i, (original, line)) in source.lines().zip(marked.lines()).enumerate() {
+        for block in inline.by_ref().take(line.matches(INLINE_MARKER).count()) {
+            // Block openings also look like inline directives; they were found above
+            if !OPEN_RE.is_match(original) {
+                found.push((i + 1, block));
+            }
+        }
+    }
+    found.sort_by_key(|(line, _)| *line);
 
-    let Some(end) = rest.find("\n---") else {
-        return;
-    };
+    for (line, block) in found {
+        let violations = match block.name.as_str() {
+            "only" => schema::validate(conditions::SCHEMA, &block.attributes),
+            _ => registry.validate(&block),
+        };
+        for violation in violations {
+            diags.push(Diagnostic {
+                check: "invalid-directive-attribute",
+                category: "content",
+                severity: Severity::Warning,
+                message: format!(":::{}: {violation}", block.name),
+                file: Some(source_path.to_path_buf()),
+                line: Some(line),
+                fix: None,
+            });
+        }
+    }
+}
 
-    let content = &rest[..end];
-    if let Err(e) = serde_json::from_str::<serde_json::Value>(content) {
+fn check_frontmatter(source: &str, source_path: &Path, diags: &mut Vec<Diagnostic>) {
+    if let Err(e) = frontmatter::parse(source) {
         diags.push(Diagnostic {
             check: "frontmatter-parse-error",
             category: "content",
             severity: Severity::Warning,
-            message: format!("Front-matter JSON parse error: {e}"),
+            message: format!(
+                "Front-matter {} parse error: {} (column {})",
+                e.format, e.message, e.column
+            ),
             file: Some(source_path.to_path_buf()),
-            line: Some(1),
+            line: Some(e.line),
             fix: None,
         });
     }
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use crate::config::Config;
+use crate::doctor::{Diagnostic, Severity};
+use crate::pipeline::frontmatter;
+use crate::project::PageInventory;
+
+/// Check every page's front matter against `[frontmatter.schema]` (only when a
+/// schema is configured). Pages whose front matter does not parse are left to
+/// the content checks.
+pub fn check_frontmatter(
+    project_root: &Path,
+    config: &Config,
+    inventory: &PageInventory,
+) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let schema = &config.frontmatter.schema;
+    if schema.is_empty() {
+        return diags;
+    }
+
+    let content_dir = project_root.join(&config.project.content_dir);
+    for slug in &inventory.ordered {
+        let page = &inventory.pages[slug];
+        let Ok(source) = std::fs::read_to_string(&page.source_path) else {
+            continue;
+        };
+        let Ok(fm) = frontmatter::parse(&source) else {
+            continue;
+        };
+
+        // Rule folders are relative to the content directory, or to the
+        // version directory when versioning is enabled
+        let mut relative = page
+            .source_path
+            .strip_prefix(&content_dir)
+            .unwrap_or(&page.source_path);
+        if let Some(version) = config
+            .version
+            .enabled
+            .iter()
+            .find(|v| relative.starts_with(v.as_str()))
+        {
+            relative = relative.strip_prefix(version).unwrap_or(relative);
+        }
+
+        for violation in frontmatter::check_schema(&source, &fm, schema, relative) {
+            diags.push(Diagnostic {
+                check: "frontmatter-schema",
+                category: "content",
+                severity: Severity::Warning,
+                message: violation.message,
+                file: Some(page.source_path.clone()),
+                line: Some(violation.line),
+                fix: None,
+            });
+        }
+    }
+
+    diags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup(config_toml: &str, pages: &[(&str, &str)]) -> (tempfile::TempDir, Config) {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in pages {
+            let path = dir.path().join("docs").join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        (dir, toml::from_str(config_toml).unwrap())
+    }
+
+    fn run(dir: &Path, config: &Config) -> Vec<Diagnostic> {
+        let inventory = PageInventory::scan(&dir.join("docs"), None, None, None).unwrap();
+        check_frontmatter(dir, config, &inventory)
+    }
+
+    #[test]
+    fn no_schema_returns_empty() {
+        let (dir, config) = setup("", &[("index.md", "# Home")]);
+        assert!(run(dir.path(), &config).is_empty());
+    }
+
+    #[test]
+    fn reports_violations_in_matching_folders() {
+        let (dir, config) = setup(
+            r#"
+[frontmatter.schema.owner]
+required = true
+folders = ["compliance"]
+
+[frontmatter.schema.review_date]
+type = "date"
+"#,
+            &[
+                ("index.md", "# Home"),
+                (
+                    "compliance/policy.md",
+                    "---\ntitle: Policy\nreview_date: next week\n---\n# Policy",
+                ),
+            ],
+        );
+        let diags = run(dir.path(), &config);
+        assert_eq!(diags.len(), 2, "{diags:?}");
+        assert!(diags.iter().all(|d| d.check == "frontmatter-schema"
+            && d.category == "content"
+            && d.severity == Severity::Warning
+            && d.file.as_ref().unwrap().ends_with("compliance/policy.md")));
+        assert_eq!(diags[0].message, "missing required field 'owner'");
+        assert_eq!(diags[0].line, Some(1));
+        assert_eq!(
+            diags[1].message,
+            "field 'review_date' must be a date in YYYY-MM-DD format, found \"next week\""
+        );
+        assert_eq!(diags[1].line, Some(3));
+    }
+
+    #[test]
+    fn folders_are_relative_to_version_directory() {
+        let (dir, config) = setup(
+            r#"
+[version]
+enabled = ["v1"]
+
+[frontmatter.schema.owner]
+required = true
+folders = ["compliance"]
+"#,
+            &[("v1/compliance/policy.md", "# Policy")],
+        );
+        let diags = run(dir.path(), &config);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+    }
+
+    #[test]
+    fn unparseable_front_matter_is_skipped() {
+        let (dir, config) = setup(
+            "[frontmatter.schema.owner]\nrequired = true\n",
+            &[("index.md", "---\n{broken\n---\n# Home")],
+        );
+        assert!(run(dir.path(), &config).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod content;
+pub mod frontmatter;
 pub mod locale;
 pub mod output;
 pub mod project;
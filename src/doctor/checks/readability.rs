@@ -6,6 +6,7 @@ use regex::Regex;
 
 use crate::config::Config;
 use crate::doctor::{Diagnostic, Severity};
+use crate::pipeline::frontmatter;
 use crate::project::PageInventory;
 
 // ---------------------------------------------------------------------------
@@ -56,14 +57,15 @@ static PLACEHOLDER_RE: LazyLock<Regex> =
 // ---------------------------------------------------------------------------
 
 /// Returns the 0-based line index at which the document body starts,
-/// skipping any front matter block (`---` … `---`).
+/// skipping any front matter block (`---` … `---` or `+++` … `+++`).
 fn body_start(lines: &[&str]) -> usize {
-    if lines.first().map(|l| l.trim()) != Some("---") {
-        return 0;
-    }
+    let delimiter = match lines.first().map(|l| l.trim()) {
+        Some(d @ ("---" | "+++")) => d,
+        _ => return 0,
+    };
     let mut i = 1;
     while i < lines.len() {
-        if lines[i].trim() == "---" {
+        if lines[i].trim() == delimiter {
             return i + 1;
         }
         i += 1;
@@ -158,33 +160,12 @@ fn scan_fence_openers<'a>(source: &'a str) -> Vec<(usize, &'a str)> {
     result
 }
 
-/// Returns `true` if the document's JSON front matter contains a non-empty
-/// `"title"` field.
+/// Returns `true` if the document's front matter contains a non-empty `title` field.
 fn front_matter_has_title(source: &str) -> bool {
-    let trimmed = source.trim_start();
-    if !trimmed.starts_with("---") {
-        return false;
-    }
-    let after_open = match trimmed[3..]
-        .strip_prefix('\n')
-        .or_else(|| trimmed[3..].strip_prefix("\r\n"))
-    {
-        Some(s) => s,
-        None => return false,
-    };
-    let end = match after_open.find("\n---") {
-        Some(i) => i,
-        None => return false,
-    };
-    let json_str = &after_open[..end];
-    serde_json::from_str::<serde_json::Value>(json_str)
+    frontmatter::parse(source)
         .ok()
-        .and_then(|v| {
-            v.get("title")
-                .and_then(|t| t.as_str())
-                .map(|s| !s.trim().is_empty())
-        })
-        .unwrap_or(false)
+        .and_then(|fm| fm.title)
+        .is_some_and(|title| !title.trim().is_empty())
 }
 
 /// Returns true when `line` (trimmed) is a CommonMark thematic break.
@@ -1009,6 +990,14 @@ mod tests {
         assert!(!front_matter_has_title("# Just a heading"));
     }
 
+    #[test]
+    fn fm_yaml_and_toml_titles() {
+        assert!(front_matter_has_title("---\ntitle: My Page\n---\nContent"));
+        assert!(front_matter_has_title(
+            "+++\ntitle = \"My Page\"\n+++\nContent"
+        ));
+    }
+
     // --- check_multiple_h1 ---
 
     #[test]
@@ -2,8 +2,10 @@ use std::path::Path;
 
 use crate::config::Config;
 use crate::doctor::{Diagnostic, Fix, Severity};
+use crate::render::templates::DEFAULT_LAYOUT;
+use crate::theme::{self, TEMPLATES_DIR};
 
-/// Check theme: custom CSS existence, layout template validity.
+/// Check theme: custom CSS existence, page template validity.
 pub fn check_theme(project_root: &Path, config: &Config) -> Vec<Diagnostic> {
     let mut diags = Vec::new();
 
@@ -26,37 +28,76 @@ pub fn check_theme(project_root: &Path, config: &Config) -> Vec<Diagnostic> {
         }
     }
 
-    // Check user layout template for Tera errors
-    let user_layout_path = project_root.join("theme/templates/layout.html");
-    if user_layout_path.exists() {
-        let template_content = match std::fs::read_to_string(&user_layout_path) {
-            Ok(content) => content,
+    // Check user templates for Tera errors. They are parsed together, like the
+    // build does, so templates can extend, include or import from each other.
+    let templates_dir = project_root.join(TEMPLATES_DIR);
+    let mut templates = Vec::new();
+    for entry in walkdir::WalkDir::new(&templates_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.into_path();
+        let Ok(relative) = path.strip_prefix(&templates_dir) else {
+            continue;
+        };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        if name.split('/').any(|part| part.starts_with('.')) {
+            continue;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(content) => templates.push((name, content, path)),
             Err(e) => {
                 diags.push(Diagnostic {
                     check: "layout-read-error",
                     category: "theme",
                     severity: Severity::Error,
-                    message: format!("Cannot read layout template: {e}"),
-                    file: Some(user_layout_path),
+                    message: format!("Cannot read template {name}: {e}"),
+                    file: Some(path),
                     line: None,
                     fix: None,
                 });
                 return diags;
             }
-        };
+        }
+    }
+    if templates.is_empty() {
+        return diags;
+    }
 
-        let mut tera = tera::Tera::default();
-        if let Err(e) = tera.add_raw_template("layout.html", &template_content) {
-            diags.push(Diagnostic {
-                check: "layout-tera-error",
-                category: "theme",
-                severity: Severity::Error,
-                message: format!("Layout template has Tera errors: {e}"),
-                file: Some(user_layout_path),
-                line: None,
-                fix: None,
-            });
+    let mut tera = tera::Tera::default();
+    let mut sources: Vec<(&str, &str)> = templates
+        .iter()
+        .map(|(name, content, _)| (name.as_str(), content.as_str()))
+        .collect();
+    let default_layout = theme::default_layout();
+    if !templates.iter().any(|(name, _, _)| name == DEFAULT_LAYOUT) {
+        sources.push((DEFAULT_LAYOUT, &default_layout));
+    }
+    if let Err(e) = tera.add_raw_templates(sources) {
+        // Point at the first template the error names
+        let message = e.to_string();
+        let file = templates
+            .iter()
+            .filter_map(|(name, _, path)| message.find(&format!("'{name}'")).map(|i| (i, path)))
+            .min_by_key(|(i, _)| *i)
+            .map(|(_, path)| path.clone());
+        let mut details = message;
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            details.push_str(&format!(": {cause}"));
+            source = cause.source();
         }
+        diags.push(Diagnostic {
+            check: "layout-tera-error",
+            category: "theme",
+            severity: Severity::Error,
+            message: format!("Theme templates have Tera errors: {details}"),
+            file,
+            line: None,
+            fix: None,
+        });
     }
 
     diags
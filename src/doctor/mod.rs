@@ -200,6 +200,20 @@ pub fn run_checks(project_root: &Path, silent: bool) -> (Vec<Diagnostic>, Summar
         all.extend(content_diags);
     }
 
+    // D1. Front matter schema checks (only when a schema is configured)
+    if let Some(ref inv) = inventory
+        && !config.frontmatter.schema.is_empty()
+    {
+        if !silent {
+            eprintln!("{}", "Checking front matter schema...".bold());
+        }
+        let frontmatter_diags = checks::frontmatter::check_frontmatter(project_root, &config, inv);
+        if !silent {
+            print_check_results(&frontmatter_diags);
+        }
+        all.extend(frontmatter_diags);
+    }
+
     // E. Readability checks
     if let Some(ref inv) = inventory {
         if !silent {
@@ -418,6 +432,39 @@ pub fn format_junit(diagnostics: &[Diagnostic], project_root: &Path) -> String {
     out
 }
 
+/// Format diagnostics as a JSON array, one object per diagnostic.
+pub fn format_json(diagnostics: &[Diagnostic], project_root: &Path) -> String {
+    #[derive(serde::Serialize)]
+    struct JsonDiagnostic<'a> {
+        check: &'a str,
+        category: &'a str,
+        severity: String,
+        message: &'a str,
+        file: Option<String>,
+        line: Option<usize>,
+    }
+
+    let entries: Vec<JsonDiagnostic> = diagnostics
+        .iter()
+        .map(|d| JsonDiagnostic {
+            check: d.check,
+            category: d.category,
+            severity: d.severity.to_string(),
+            message: &d.message,
+            file: d.file.as_ref().map(|f| {
+                f.strip_prefix(project_root)
+                    .unwrap_or(f)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            }),
+            line: d.line,
+        })
+        .collect();
+    let mut out = serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string());
+    out.push('\n');
+    out
+}
+
 fn print_check_results(diagnostics: &[Diagnostic]) {
     if diagnostics.is_empty() {
         eprintln!("  {} All checks passed", "✓".green().bold());
@@ -448,6 +495,59 @@ pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
     let _ = diagnostics;
 }
 
+/// Apply all safe fixes from the diagnostics, returning the number of fixes applied.
+pub fn apply_fixes(diagnostics: &[Diagnostic]) -> usize {
+    let mut fixed = 0;
+
+    let fixable: Vec<_> = diagnostics.iter().filter(|d| d.fix.is_some()).collect();
+    if fixable.is_empty() {
+        return 0;
+    }
+
+    eprintln!();
+    eprintln!("{}", "Applying fixes...".bold());
+
+    for d in fixable {
+        match d.fix.as_ref().unwrap() {
+            Fix::CreateDir(path) => {
+                if let Err(e) = std::fs::create_dir_all(path) {
+                    eprintln!(
+                        "  {} Failed to create directory {}: {}",
+                        "✗".red().bold(),
+                        path.display(),
+                        e
+                    );
+                } else {
+                    eprintln!(
+                        "  {} Created directory {}",
+                        "✓".green().bold(),
+                        path.display()
+                    );
+                    fixed += 1;
+                }
+            }
+            Fix::CreateFile { path, content } => {
+                if let Some(parent) = path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Err(e) = std::fs::write(path, content) {
+                    eprintln!(
+                        "  {} Failed to create {}: {}",
+                        "✗".red().bold(),
+                        path.display(),
+                        e
+                    );
+                } else {
+                    eprintln!("  {} Created {}", "✓".green().bold(), path.display());
+                    fixed += 1;
+                }
+            }
+        }
+    }
+
+    fixed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -557,6 +657,35 @@ mod tests {
         assert!(xml.contains("source=\"docanvil.output.other-check\""));
     }
 
+    // --- format_json ---
+
+    #[test]
+    fn json_empty_diagnostics() {
+        let root = PathBuf::from("/project");
+        assert_eq!(format_json(&[], &root), "[]\n");
+    }
+
+    #[test]
+    fn json_fields_and_relative_path() {
+        let root = PathBuf::from("/project");
+        let diags = vec![make_diag(
+            "broken-wiki-link",
+            "content",
+            Severity::Warning,
+            "Broken link [[x]]",
+            Some(PathBuf::from("/project/docs/page.md")),
+            Some(3),
+        )];
+        let json: serde_json::Value = serde_json::from_str(&format_json(&diags, &root)).unwrap();
+        let d = &json[0];
+        assert_eq!(d["check"], "broken-wiki-link");
+        assert_eq!(d["category"], "content");
+        assert_eq!(d["severity"], "warning");
+        assert_eq!(d["message"], "Broken link [[x]]");
+        assert_eq!(d["file"], "docs/page.md");
+        assert_eq!(d["line"], 3);
+    }
+
     // --- format_junit ---
 
     #[test]
@@ -639,56 +768,3 @@ mod tests {
         );
     }
 }
-
-/// Apply all safe fixes from the diagnostics, returning the number of fixes applied.
-pub fn apply_fixes(diagnostics: &[Diagnostic]) -> usize {
-    let mut fixed = 0;
-
-    let fixable: Vec<_> = diagnostics.iter().filter(|d| d.fix.is_some()).collect();
-    if fixable.is_empty() {
-        return 0;
-    }
-
-    eprintln!();
-    eprintln!("{}", "Applying fixes...".bold());
-
-    for d in fixable {
-        match d.fix.as_ref().unwrap() {
-            Fix::CreateDir(path) => {
-                if let Err(e) = std::fs::create_dir_all(path) {
-                    eprintln!(
-                        "  {} Failed to create directory {}: {}",
-                        "✗".red().bold(),
-                        path.display(),
-                        e
-                    );
-                } else {
-                    eprintln!(
-                        "  {} Created directory {}",
-                        "✓".green().bold(),
-                        path.display()
-                    );
-                    fixed += 1;
-                }
-            }
-            Fix::CreateFile { path, content } => {
-                if let Some(parent) = path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-                if let Err(e) = std::fs::write(path, content) {
-                    eprintln!(
-                        "  {} Failed to create {}: {}",
-                        "✗".red().bold(),
-                        path.display(),
-                        e
-                    );
-                } else {
-                    eprintln!("  {} Created {}", "✓".green().bold(), path.display());
-                    fixed += 1;
-                }
-            }
-        }
-    }
-
-    fixed
-}
@@ -29,6 +29,9 @@ pub enum Error {
     #[error("doctor found {errors} error(s) and {warnings} warning(s)")]
     DoctorFailed { warnings: usize, errors: usize },
 
+    #[error("plugin '{name}' failed: {message}")]
+    Plugin { name: String, message: String },
+
     #[error(
         "Chrome or Chromium is required for PDF export but wasn't found on this machine.\n\
          Install guide: https://www.google.com/chrome/"
@@ -65,6 +68,9 @@ impl Error {
             Error::StrictWarnings(_) => {
                 Some("Fix the warnings above, or build without --strict.".into())
             }
+            Error::Plugin { .. } => Some(
+                "Check the plugin's command in the [[plugins]] section of docanvil.toml, and its stderr output above.".into(),
+            ),
             Error::General(_) | Error::DoctorFailed { .. } | Error::ChromeNotFound => None,
         }
     }
@@ -84,7 +90,7 @@ impl Error {
             Error::ContentDirNotFound(_)
             | Error::StrictWarnings(_)
             | Error::DoctorFailed { .. } => 3,
-            Error::Render(_) | Error::ChromeNotFound => 4,
+            Error::Render(_) | Error::Plugin { .. } | Error::ChromeNotFound => 4,
         }
     }
 }
@@ -135,6 +141,12 @@ mod tests {
     fn exit_code_render() {
         let render = Error::Render("template broke".into());
         assert_eq!(render.exit_code(), 4);
+
+        let plugin = Error::Plugin {
+            name: "lint".into(),
+            message: "exited".into(),
+        };
+        assert_eq!(plugin.exit_code(), 4);
     }
 
     #[test]
@@ -176,6 +188,16 @@ mod tests {
         assert!(hint.contains("template"));
     }
 
+    #[test]
+    fn hint_plugin() {
+        let err = Error::Plugin {
+            name: "lint".into(),
+            message: "exited".into(),
+        };
+        let hint = err.hint().unwrap();
+        assert!(hint.contains("[[plugins]]"));
+    }
+
     #[test]
     fn hint_strict_warnings() {
         let err = Error::StrictWarnings(2);
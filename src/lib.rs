@@ -1,3 +1,4 @@
+pub mod cache;
 pub mod cli;
 pub mod components;
 pub mod config;
@@ -6,10 +7,13 @@ pub mod doctor;
 pub mod error;
 pub mod nav;
 pub mod pipeline;
+pub mod plugins;
 pub mod project;
 pub mod render;
 pub mod search;
 pub mod seo;
 pub mod server;
+pub mod site;
+pub mod source;
 pub mod theme;
 pub mod util;
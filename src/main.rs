@@ -28,8 +28,24 @@ fn main() {
             out,
             clean,
             strict,
+            no_cache,
+            drafts,
+            profile,
+            jobs,
+            format,
+            path,
+        } => docanvil::cli::build::run(
             path,
-        } => docanvil::cli::build::run(path, out, *clean, cli.quiet, *strict),
+            out,
+            *clean,
+            cli.quiet,
+            *strict,
+            *no_cache,
+            *drafts,
+            profile.clone(),
+            *jobs,
+            format,
+        ),
         Command::Export(export_args) => docanvil::cli::export::dispatch(export_args, cli.quiet),
     };
 
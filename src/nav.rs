@@ -1,20 +1,41 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
 use crate::diagnostics;
 use crate::error::Result;
-use crate::project::{NavNode, PageInventory};
-
-#[derive(Deserialize)]
-struct NavFile {
-    nav: Vec<NavEntry>,
+use crate::project::{Badge, NavDecoration, NavNode, PageInventory};
+use crate::render::icons;
+use crate::source::{DiskFiles, FileSource};
+
+/// The contents of a nav file.
+#[derive(Deserialize, Debug, Default)]
+pub struct NavConfig {
+    /// The sidebar (`[[nav]]`). When absent, the sidebar is built from the
+    /// content directory.
+    #[serde(default)]
+    pub nav: Option<Vec<NavEntry>>,
+    /// Named menus (`[[menus.header]]`, `[[menus.footer]]`, ...), rendered for
+    /// templates alongside the sidebar.
+    #[serde(default)]
+    pub menus: BTreeMap<String, Vec<NavEntry>>,
+    /// Sidebars for top-level sections (`[[sidebars.api]]`), replacing the
+    /// main sidebar on pages in that content folder.
+    #[serde(default)]
+    pub sidebars: BTreeMap<String, Vec<NavEntry>>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct NavEntry {
     pub page: Option<String>,
+    /// External URL; the entry links there in a new tab instead of to a page.
+    pub url: Option<String>,
     pub label: Option<String>,
+    /// Name of a built-in icon shown before the label.
+    pub icon: Option<String>,
+    /// Status badge shown after the label.
+    pub badge: Option<Badge>,
     pub separator: Option<SeparatorValue>,
     pub group: Option<Vec<NavGroupItem>>,
     pub autodiscover: Option<String>,
@@ -30,7 +51,13 @@ pub enum SeparatorValue {
 #[derive(Deserialize, Debug)]
 pub struct NavGroupItem {
     pub page: Option<String>,
+    /// External URL; the entry links there in a new tab instead of to a page.
+    pub url: Option<String>,
     pub label: Option<String>,
+    /// Name of a built-in icon shown before the label.
+    pub icon: Option<String>,
+    /// Status badge shown after the label.
+    pub badge: Option<Badge>,
     pub separator: Option<SeparatorValue>,
     pub group: Option<Vec<NavGroupItem>>,
     pub autodiscover: Option<String>,
@@ -39,7 +66,9 @@ pub struct NavGroupItem {
 /// Shared accessors for the identical fields on `NavEntry` and `NavGroupItem`.
 trait NavItem {
     fn page(&self) -> Option<&str>;
+    fn url(&self) -> Option<&str>;
     fn label(&self) -> Option<&str>;
+    fn decoration(&self) -> NavDecoration;
     fn separator(&self) -> Option<&SeparatorValue>;
     fn group(&self) -> Option<&[NavGroupItem]>;
     fn autodiscover(&self) -> Option<&str>;
@@ -49,9 +78,18 @@ impl NavItem for NavEntry {
     fn page(&self) -> Option<&str> {
         self.page.as_deref()
     }
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
     fn label(&self) -> Option<&str> {
         self.label.as_deref()
     }
+    fn decoration(&self) -> NavDecoration {
+        NavDecoration {
+            icon: self.icon.clone(),
+            badge: self.badge,
+        }
+    }
     fn separator(&self) -> Option<&SeparatorValue> {
         self.separator.as_ref()
     }
@@ -67,9 +105,18 @@ impl NavItem for NavGroupItem {
     fn page(&self) -> Option<&str> {
         self.page.as_deref()
     }
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
     fn label(&self) -> Option<&str> {
         self.label.as_deref()
     }
+    fn decoration(&self) -> NavDecoration {
+        NavDecoration {
+            icon: self.icon.clone(),
+            badge: self.badge,
+        }
+    }
     fn separator(&self) -> Option<&SeparatorValue> {
         self.separator.as_ref()
     }
@@ -83,36 +130,12 @@ impl NavItem for NavGroupItem {
 
 /// Read and parse `nav.toml` from the project root, if it exists.
 pub fn load_nav(project_root: &Path) -> Result<Option<Vec<NavEntry>>> {
-    let path = project_root.join("nav.toml");
-    if !path.exists() {
-        return Ok(None);
-    }
-
-    let content = std::fs::read_to_string(&path)?;
-    let nav_file: NavFile =
-        toml::from_str(&content).map_err(|e| crate::error::Error::ConfigParse {
-            path: path.clone(),
-            source: e,
-        })?;
-
-    Ok(Some(nav_file.nav))
+    load_nav_for_target(&DiskFiles, project_root, None, None)
 }
 
 /// Load version-specific nav file: tries `nav.{version}.toml` first, falls back to `nav.toml`.
 pub fn load_nav_for_version(project_root: &Path, version: &str) -> Result<Option<Vec<NavEntry>>> {
-    let version_path = project_root.join(format!("nav.{version}.toml"));
-    if version_path.exists() {
-        let content = std::fs::read_to_string(&version_path)?;
-        let nav_file: NavFile =
-            toml::from_str(&content).map_err(|e| crate::error::Error::ConfigParse {
-                path: version_path.clone(),
-                source: e,
-            })?;
-        return Ok(Some(nav_file.nav));
-    }
-
-    // Fall back to the default nav.toml
-    load_nav(project_root)
+    load_nav_for_target(&DiskFiles, project_root, Some(version), None)
 }
 
 /// Load nav for a specific version and locale combination.
@@ -127,50 +150,100 @@ pub fn load_nav_for_version_and_locale(
     version: &str,
     locale: &str,
 ) -> Result<Option<Vec<NavEntry>>> {
-    let vl_path = project_root.join(format!("nav.{version}.{locale}.toml"));
-    if vl_path.exists() {
-        let content = std::fs::read_to_string(&vl_path)?;
-        let nav_file: NavFile =
-            toml::from_str(&content).map_err(|e| crate::error::Error::ConfigParse {
-                path: vl_path.clone(),
-                source: e,
-            })?;
-        return Ok(Some(nav_file.nav));
-    }
-
-    let v_path = project_root.join(format!("nav.{version}.toml"));
-    if v_path.exists() {
-        let content = std::fs::read_to_string(&v_path)?;
-        let nav_file: NavFile =
-            toml::from_str(&content).map_err(|e| crate::error::Error::ConfigParse {
-                path: v_path.clone(),
-                source: e,
-            })?;
-        return Ok(Some(nav_file.nav));
-    }
-
-    // Fall back to locale-specific or global nav
-    load_nav_for_locale(project_root, locale)
+    load_nav_for_target(&DiskFiles, project_root, Some(version), Some(locale))
 }
 
 /// Load locale-specific nav file: tries `nav.{locale}.toml` first, falls back to `nav.toml`.
 pub fn load_nav_for_locale(project_root: &Path, locale: &str) -> Result<Option<Vec<NavEntry>>> {
-    let locale_path = project_root.join(format!("nav.{locale}.toml"));
-    if locale_path.exists() {
-        let content = std::fs::read_to_string(&locale_path)?;
-        let nav_file: NavFile =
-            toml::from_str(&content).map_err(|e| crate::error::Error::ConfigParse {
-                path: locale_path.clone(),
-                source: e,
-            })?;
-        return Ok(Some(nav_file.nav));
-    }
-
-    // Fall back to the default nav.toml
-    load_nav(project_root)
+    load_nav_for_target(&DiskFiles, project_root, None, Some(locale))
 }
 
-/// Warn about slugs in nav.toml that don't match any page in the inventory.
+/// Load the sidebar entries of the nav file for a version and/or locale from
+/// `files`, using the most specific `nav.*.toml` that exists (see
+/// [`load_nav_for_version_and_locale`]). `None` if there is no nav file or it
+/// has no `[[nav]]` entries.
+pub fn load_nav_for_target(
+    files: &dyn FileSource,
+    project_root: &Path,
+    version: Option<&str>,
+    locale: Option<&str>,
+) -> Result<Option<Vec<NavEntry>>> {
+    Ok(load_nav_config_for_target(files, project_root, version, locale)?.and_then(|c| c.nav))
+}
+
+/// Like [`load_nav_for_target`], but returns the whole nav file, menus and
+/// section sidebars included.
+pub fn load_nav_config_for_target(
+    files: &dyn FileSource,
+    project_root: &Path,
+    version: Option<&str>,
+    locale: Option<&str>,
+) -> Result<Option<NavConfig>> {
+    let Some(path) = find_nav_file(files, project_root, version, locale) else {
+        return Ok(None);
+    };
+    let content = files.read_to_string(&path)?;
+    parse_nav_config(&content, &path).map(Some)
+}
+
+/// Path of the most specific nav file for a version and/or locale, if any exists.
+pub fn find_nav_file(
+    files: &dyn FileSource,
+    project_root: &Path,
+    version: Option<&str>,
+    locale: Option<&str>,
+) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let (Some(version), Some(locale)) = (version, locale) {
+        candidates.push(format!("nav.{version}.{locale}.toml"));
+    }
+    if let Some(version) = version {
+        candidates.push(format!("nav.{version}.toml"));
+    }
+    if let Some(locale) = locale {
+        candidates.push(format!("nav.{locale}.toml"));
+    }
+    candidates.push("nav.toml".to_string());
+
+    candidates
+        .into_iter()
+        .map(|candidate| project_root.join(candidate))
+        .find(|path| files.exists(path))
+}
+
+/// Parse the sidebar entries of a nav file read from `path`.
+pub fn parse_nav(content: &str, path: &Path) -> Result<Vec<NavEntry>> {
+    Ok(parse_nav_config(content, path)?.nav.unwrap_or_default())
+}
+
+/// Parse the contents of a nav file read from `path`.
+pub fn parse_nav_config(content: &str, path: &Path) -> Result<NavConfig> {
+    toml::from_str(content).map_err(|e| crate::error::Error::ConfigParse {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// Validate every list in a nav file — the sidebar, menus and section sidebars —
+/// for the given locale, if any, and warn about sidebars for sections with no pages.
+pub fn validate_config(config: &NavConfig, inventory: &PageInventory, locale: Option<&str>) {
+    let lists = config
+        .nav
+        .iter()
+        .chain(config.menus.values())
+        .chain(config.sidebars.values());
+    for entries in lists {
+        validate_items(entries, inventory, locale);
+    }
+    for section in config.sidebars.keys() {
+        if !inventory.folder_has_pages(section) {
+            diagnostics::warn_nav_sidebar_empty(section);
+        }
+    }
+}
+
+/// Warn about slugs in nav.toml that don't match any page in the inventory,
+/// and about unknown icons.
 pub fn validate(entries: &[NavEntry], inventory: &PageInventory) {
     validate_items(entries, inventory, None);
 }
@@ -187,10 +260,15 @@ fn validate_items<T: NavItem>(items: &[T], inventory: &PageInventory, locale: Op
                 Some(l) => format!("{l}:{slug}"),
                 None => slug.to_string(),
             };
-            if !inventory.pages.contains_key(&key) {
+            if !inventory.pages.contains_key(&key) && !inventory.unpublished.contains(&key) {
                 diagnostics::warn_nav_missing_page(slug);
             }
         }
+        if let Some(icon) = item.decoration().icon
+            && !icons::exists(&icon)
+        {
+            diagnostics::warn_nav_unknown_icon(&icon);
+        }
         if let Some(folder) = item.autodiscover()
             && !inventory.folder_has_pages(folder)
         {
@@ -210,6 +288,18 @@ pub fn nav_tree_from_config(entries: &[NavEntry], inventory: &PageInventory) ->
         .collect()
 }
 
+/// Convert parsed nav entries into a NavNode tree, for a specific locale if given.
+pub fn nav_tree_from_entries(
+    entries: &[NavEntry],
+    inventory: &PageInventory,
+    locale: Option<&str>,
+) -> Vec<NavNode> {
+    entries
+        .iter()
+        .flat_map(|entry| item_to_nodes(entry, inventory, locale))
+        .collect()
+}
+
 /// Convert parsed nav entries into a NavNode tree for a specific locale.
 /// Nav entries use base slugs; inventory lookup uses `{locale}:{slug}` composite keys.
 pub fn nav_tree_from_config_for_locale(
@@ -246,11 +336,17 @@ fn item_to_nodes(
         } else {
             inventory.nav_tree_for_folder(folder, exclude)
         };
-        if let Some(label) = item.label() {
+        // Without a label in nav.toml, a `_folder.toml` label still makes a group
+        let folder_label = inventory
+            .folders
+            .get(folder.trim_end_matches('/'))
+            .and_then(|meta| meta.label.as_deref());
+        if let Some(label) = item.label().or(folder_label) {
             return vec![NavNode::Group {
                 label: label.to_string(),
-                slug: item.page().map(String::from),
+                slug: published_page(item, inventory, locale),
                 children: discovered,
+                decoration: item.decoration(),
             }];
         }
         return discovered;
@@ -259,7 +355,7 @@ fn item_to_nodes(
     // Group entry
     if let Some(group_items) = item.group() {
         let label = item.label().unwrap_or_default().to_string();
-        let slug = item.page().map(String::from);
+        let slug = published_page(item, inventory, locale);
         let children: Vec<NavNode> = group_items
             .iter()
             .flat_map(|child| item_to_nodes(child, inventory, locale))
@@ -268,11 +364,21 @@ fn item_to_nodes(
             label,
             slug,
             children,
+            decoration: item.decoration(),
+        }];
+    }
+
+    // External link entry
+    if let Some(url) = item.url() {
+        return vec![NavNode::Link {
+            label: item.label().unwrap_or(url).to_string(),
+            url: url.to_string(),
+            decoration: item.decoration(),
         }];
     }
 
-    // Page entry
-    if let Some(slug) = item.page() {
+    // Page entry, dropped while the page is unpublished
+    if let Some(slug) = published_page(item, inventory, locale).as_deref() {
         let label = item
             .label()
             .map(String::from)
@@ -280,13 +386,29 @@ fn item_to_nodes(
         return vec![NavNode::Page {
             label,
             slug: slug.to_string(),
+            decoration: item.decoration(),
         }];
     }
 
     vec![]
 }
 
-/// Resolve a label for a page slug — use the page title from inventory, or derive from slug.
+/// The entry's page, unless it is a draft or scheduled page left out of the build.
+fn published_page(
+    item: &dyn NavItem,
+    inventory: &PageInventory,
+    locale: Option<&str>,
+) -> Option<String> {
+    let slug = item.page()?;
+    let key = match locale {
+        Some(l) => format!("{l}:{slug}"),
+        None => slug.to_string(),
+    };
+    (!inventory.unpublished.contains(&key)).then(|| slug.to_string())
+}
+
+/// Resolve a label for a page slug — use the page's nav label or title from inventory,
+/// or derive from slug.
 fn resolve_label(slug: &str, inventory: &PageInventory, locale: Option<&str>) -> String {
     let key = match locale {
         Some(l) => format!("{l}:{slug}"),
@@ -295,7 +417,7 @@ fn resolve_label(slug: &str, inventory: &PageInventory, locale: Option<&str>) ->
     inventory
         .pages
         .get(&key)
-        .map(|p| p.title.clone())
+        .map(|p| p.nav_label.clone().unwrap_or_else(|| p.title.clone()))
         .unwrap_or_else(|| crate::project::title_from_slug(slug))
 }
 
@@ -334,40 +456,37 @@ group = [
   { page = "advanced/deployment" },
 ]
 "#;
-        let nav_file: NavFile = toml::from_str(toml_str).unwrap();
-        assert_eq!(nav_file.nav.len(), 6);
+        let nav = parse_nav(toml_str, Path::new("nav.toml")).unwrap();
+        assert_eq!(nav.len(), 6);
 
         // First entry: page
-        assert_eq!(nav_file.nav[0].page.as_deref(), Some("index"));
+        assert_eq!(nav[0].page.as_deref(), Some("index"));
 
         // Second entry: labeled separator
         assert!(matches!(
-            &nav_file.nav[1].separator,
+            &nav[1].separator,
             Some(SeparatorValue::Labeled(s)) if s == "Guides"
         ));
 
         // Third entry: page with label override
-        assert_eq!(
-            nav_file.nav[2].page.as_deref(),
-            Some("guides/getting-started")
-        );
-        assert_eq!(nav_file.nav[2].label.as_deref(), Some("Installation"));
+        assert_eq!(nav[2].page.as_deref(), Some("guides/getting-started"));
+        assert_eq!(nav[2].label.as_deref(), Some("Installation"));
 
         // Fourth entry: unlabeled separator
         assert!(matches!(
-            &nav_file.nav[3].separator,
+            &nav[3].separator,
             Some(SeparatorValue::Unlabeled(true))
         ));
 
         // Fifth entry: group without header page
-        assert_eq!(nav_file.nav[4].label.as_deref(), Some("API Reference"));
-        assert!(nav_file.nav[4].group.is_some());
-        assert!(nav_file.nav[4].page.is_none());
+        assert_eq!(nav[4].label.as_deref(), Some("API Reference"));
+        assert!(nav[4].group.is_some());
+        assert!(nav[4].page.is_none());
 
         // Sixth entry: group with header page
-        assert_eq!(nav_file.nav[5].label.as_deref(), Some("Advanced"));
-        assert_eq!(nav_file.nav[5].page.as_deref(), Some("advanced/index"));
-        assert!(nav_file.nav[5].group.is_some());
+        assert_eq!(nav[5].label.as_deref(), Some("Advanced"));
+        assert_eq!(nav[5].page.as_deref(), Some("advanced/index"));
+        assert!(nav[5].group.is_some());
     }
 
     #[test]
@@ -411,21 +530,30 @@ page = "guide"
         let entries = vec![
             NavEntry {
                 page: Some("guide".to_string()),
+                url: None,
                 label: Some("My Guide".to_string()),
+                icon: None,
+                badge: None,
                 separator: None,
                 group: None,
                 autodiscover: None,
             },
             NavEntry {
                 page: None,
+                url: None,
                 label: None,
+                icon: None,
+                badge: None,
                 separator: Some(SeparatorValue::Labeled("Section".to_string())),
                 group: None,
                 autodiscover: None,
             },
             NavEntry {
                 page: Some("index".to_string()),
+                url: None,
                 label: None,
+                icon: None,
+                badge: None,
                 separator: None,
                 group: None,
                 autodiscover: None,
@@ -436,11 +564,125 @@ page = "guide"
         assert_eq!(tree.len(), 3);
 
         assert!(
-            matches!(&tree[0], NavNode::Page { label, slug } if label == "My Guide" && slug == "guide")
+            matches!(&tree[0], NavNode::Page { label, slug, .. } if label == "My Guide" && slug == "guide")
         );
         assert!(matches!(&tree[1], NavNode::Separator { label: Some(l) } if l == "Section"));
         assert!(
-            matches!(&tree[2], NavNode::Page { label, slug } if label == "Home" && slug == "index")
+            matches!(&tree[2], NavNode::Page { label, slug, .. } if label == "Home" && slug == "index")
+        );
+    }
+
+    #[test]
+    fn links_icons_and_badges() {
+        let dir = tempfile::tempdir().unwrap();
+        let inventory = PageInventory::scan(dir.path(), None, None, None).unwrap();
+        let entries = parse_nav(
+            r#"
+[[nav]]
+label = "API Console"
+url = "https://example.com/console"
+icon = "terminal"
+
+[[nav]]
+label = "Experimental"
+badge = "beta"
+group = [
+  { url = "https://example.com/status" },
+]
+"#,
+            Path::new("nav.toml"),
+        )
+        .unwrap();
+
+        let tree = nav_tree_from_config(&entries, &inventory);
+        assert!(matches!(
+            &tree[0],
+            NavNode::Link { label, url, decoration }
+                if label == "API Console"
+                    && url == "https://example.com/console"
+                    && decoration.icon.as_deref() == Some("terminal")
+        ));
+        let NavNode::Group {
+            children,
+            decoration,
+            ..
+        } = &tree[1]
+        else {
+            panic!("expected a group");
+        };
+        assert_eq!(decoration.badge, Some(Badge::Beta));
+        // Links without a label show their URL
+        assert!(
+            matches!(&children[0], NavNode::Link { label, .. } if label == "https://example.com/status")
+        );
+
+        let err = parse_nav(
+            "[[nav]]\npage = \"a\"\nbadge = \"alpha\"\n",
+            Path::new("nav.toml"),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn menus_and_section_sidebars() {
+        let config = parse_nav_config(
+            r#"
+[[menus.header]]
+page = "guides/index"
+label = "Guides"
+
+[[menus.footer]]
+label = "GitHub"
+url = "https://github.com/example"
+
+[[sidebars.api]]
+autodiscover = "api"
+"#,
+            Path::new("nav.toml"),
+        )
+        .unwrap();
+
+        // Without [[nav]] the sidebar is discovered from the content directory
+        assert!(config.nav.is_none());
+        assert_eq!(
+            config.menus.keys().collect::<Vec<_>>(),
+            vec!["footer", "header"]
+        );
+        assert_eq!(
+            config.menus["header"][0].page.as_deref(),
+            Some("guides/index")
+        );
+        assert_eq!(
+            config.sidebars["api"][0].autodiscover.as_deref(),
+            Some("api")
+        );
+    }
+
+    #[test]
+    fn autodiscover_uses_folder_label() {
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("guides")).unwrap();
+        fs::write(docs.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(
+            docs.join("guides/_folder.toml"),
+            "label = \"User Guides\"\n",
+        )
+        .unwrap();
+
+        let inventory = PageInventory::scan(&docs, None, None, None).unwrap();
+        let entries = parse_nav(
+            "[[nav]]\nautodiscover = \"guides\"\n",
+            Path::new("nav.toml"),
+        )
+        .unwrap();
+
+        let tree = nav_tree_from_config(&entries, &inventory);
+        assert_eq!(tree.len(), 1);
+        assert!(
+            matches!(&tree[0], NavNode::Group { label, children, .. } if label == "User Guides" && children.len() == 1)
         );
     }
 }
@@ -0,0 +1,75 @@
+//! Checking `#anchor` links: the element IDs of each page that links point
+//! at, worked out once per version and locale.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::cache::Dependency;
+use crate::diagnostics;
+use crate::pipeline::{self, PipelineContext, frontmatter, includes};
+
+/// Element IDs of pages, by source file. Shared between the threads rendering
+/// a version and locale.
+#[derive(Default)]
+pub struct Anchors {
+    pages: Mutex<HashMap<PathBuf, Arc<PageIds>>>,
+}
+
+struct PageIds {
+    /// `None` when the page's source can't be read, so its anchors can't be checked.
+    ids: Option<HashSet<String>>,
+    /// The page's source and the files it includes.
+    dependencies: Vec<Dependency>,
+}
+
+impl Anchors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ID on the page built from `source_file` that `anchor` points at —
+    /// `anchor` itself, or its text slugified like heading IDs — or `None` if
+    /// the page has no such element.
+    ///
+    /// The page's files become dependencies of the page being rendered (see
+    /// [`includes::track`]), so incremental builds check the link again when
+    /// they change.
+    pub fn find(&self, source_file: &Path, anchor: &str, ctx: &PipelineContext) -> Option<String> {
+        let page = self.page(source_file, ctx);
+        for dependency in &page.dependencies {
+            includes::depend(dependency.clone());
+        }
+
+        let Some(ids) = &page.ids else {
+            return Some(anchor.to_string());
+        };
+        if ids.contains(anchor) {
+            return Some(anchor.to_string());
+        }
+        let slug = slug::slugify(anchor);
+        ids.contains(&slug).then_some(slug)
+    }
+
+    fn page(&self, source_file: &Path, ctx: &PipelineContext) -> Arc<PageIds> {
+        if let Some(page) = self.pages.lock().unwrap().get(source_file) {
+            return Arc::clone(page);
+        }
+
+        // Warnings belong to the linked page's own render, not this one
+        let (ids, dependencies) = includes::track(|| {
+            let contents = ctx.files.read_to_string(source_file).ok()?;
+            includes::record(source_file, &contents);
+            let source = frontmatter::strip(&contents);
+            Some(diagnostics::discard(|| {
+                pipeline::element_ids(source, source_file, ctx)
+            }))
+        });
+        let page = Arc::new(PageIds { ids, dependencies });
+        self.pages
+            .lock()
+            .unwrap()
+            .insert(source_file.to_path_buf(), Arc::clone(&page));
+        page
+    }
+}
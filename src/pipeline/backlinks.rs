@@ -0,0 +1,97 @@
+//! Backlinks: the pages linking to each page with wiki-links or `.md` links,
+//! worked out before a version and locale is rendered so every page can list
+//! the pages that reference it.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics;
+use crate::pipeline::{self, PipelineContext};
+use crate::util;
+
+thread_local! {
+    /// When set, pages linked to on this thread are recorded here.
+    static LINKED: RefCell<Option<Vec<PathBuf>>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, returning the source files of the pages it linked to on the
+/// current thread alongside its result.
+pub fn track<R>(f: impl FnOnce() -> R) -> (R, Vec<PathBuf>) {
+    let previous = LINKED.with(|l| l.replace(Some(Vec::new())));
+    let result = f();
+    let linked = LINKED.with(|l| l.replace(previous)).unwrap_or_default();
+    (result, linked)
+}
+
+/// Record a link to the page built from `source_file`.
+pub fn record(source_file: &Path) {
+    LINKED.with(|l| {
+        if let Some(linked) = l.borrow_mut().as_mut()
+            && !linked.iter().any(|path| path == source_file)
+        {
+            linked.push(source_file.to_path_buf());
+        }
+    });
+}
+
+/// The pages linking to each page of a version and locale.
+#[derive(Debug, Default)]
+pub struct Backlinks {
+    /// Inventory keys of the linking pages, by the linked page's source file.
+    pages: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Backlinks {
+    /// Find the pages each of the pages at `keys`, with their Markdown in
+    /// `sources`, links to, on up to `jobs` threads.
+    pub fn collect(
+        keys: &[String],
+        sources: &HashMap<String, String>,
+        ctx: &PipelineContext,
+        jobs: usize,
+    ) -> Self {
+        let linked = util::parallel_map(keys, jobs, |key| {
+            let source_file = &ctx.inventory.pages[key].source_path;
+            // Warnings belong to the page's own render
+            diagnostics::discard(|| pipeline::linked_pages(&sources[key], source_file, ctx))
+        });
+
+        let mut pages: HashMap<PathBuf, Vec<String>> = HashMap::new();
+        for (key, linked) in keys.iter().zip(linked) {
+            let source_file = &ctx.inventory.pages[key].source_path;
+            for target in linked.into_iter().filter(|t| t != source_file) {
+                pages.entry(target).or_default().push(key.clone());
+            }
+        }
+        Self { pages }
+    }
+
+    /// Inventory keys of the pages linking to the page built from
+    /// `source_file`, in the order they were collected.
+    pub fn to(&self, source_file: &Path) -> &[String] {
+        self.pages.get(source_file).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_linked_pages_once() {
+        record(Path::new("untracked.md"));
+        let ((), linked) = track(|| {
+            record(Path::new("docs/setup.md"));
+            record(Path::new("docs/index.md"));
+            record(Path::new("docs/setup.md"));
+        });
+        assert_eq!(
+            linked,
+            [
+                PathBuf::from("docs/setup.md"),
+                PathBuf::from("docs/index.md")
+            ]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Conditional content: a `:::only{version="v2" locale="fr" profile="internal"}`
+//! block is kept when every condition matches the page being built and left
+//! out otherwise, so one source file can diverge between builds.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::components::schema::{self, AttributeSpec};
+use crate::diagnostics;
+use crate::pipeline::directives;
+
+static ONLY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(:{3,})\s*only\s*(\{.*\})?\s*$").unwrap());
+
+/// Attributes `:::only` accepts.
+pub const SCHEMA: &[AttributeSpec] = &[
+    AttributeSpec::new("version"),
+    AttributeSpec::new("locale"),
+    AttributeSpec::new("profile"),
+];
+
+/// What a page is being built for.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Conditions<'a> {
+    pub version: Option<&'a str>,
+    pub locale: Option<&'a str>,
+    /// The build profile passed to `docanvil build --profile`.
+    pub profile: Option<&'a str>,
+}
+
+impl Conditions<'_> {
+    /// Pre-comrak pass: keep the bodies of `:::only` blocks whose conditions
+    /// match and drop the rest. Blocks nest; like other directives, an outer
+    /// block needs more colons than the directives inside it.
+    ///
+    /// Blocks in fenced code are left as they are, and so is a block with no
+    /// closing fence.
+    pub fn apply(&self, source: &str) -> String {
+        if !source.contains(":::") {
+            return source.to_string();
+        }
+        let lines: Vec<&str> = source.lines().collect();
+        let mut kept = Vec::with_capacity(lines.len());
+        self.filter(&lines, &mut kept);
+
+        let mut output = kept.join("\n");
+        if source.ends_with('\n') {
+            output.push('\n');
+        }
+        output
+    }
+
+    fn filter<'s>(&self, lines: &[&'s str], kept: &mut Vec<&'s str>) {
+        let mut fence: Option<(char, usize)> = None;
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            i += 1;
+
+            if directives::in_fence(&mut fence, line.trim()) {
+                kept.push(line);
+                continue;
+            }
+
+            let Some(caps) = ONLY_RE.captures(line) else {
+                kept.push(line);
+                continue;
+            };
+            // The closing fence, skipping `:::` lines in code inside the block
+            let colons = caps[1].len();
+            let mut body_fence = None;
+            let Some(len) = lines[i..].iter().position(|l| {
+                let t = l.trim();
+                !directives::in_fence(&mut body_fence, t)
+                    && t.len() == colons
+                    && t.chars().all(|c| c == ':')
+            }) else {
+                kept.push(line);
+                continue;
+            };
+            let body = &lines[i..i + len];
+            i += len + 1;
+
+            let attributes = caps
+                .get(2)
+                .map(|m| directives::parse_attributes(m.as_str()))
+                .unwrap_or_default();
+            for violation in schema::validate(SCHEMA, &attributes) {
+                diagnostics::warn_invalid_directive_attribute("only", &violation);
+            }
+            let matches = matches(attributes.get("version"), self.version)
+                && matches(attributes.get("locale"), self.locale)
+                && matches(attributes.get("profile"), self.profile);
+            if matches {
+                self.filter(body, kept);
+            }
+        }
+    }
+}
+
+/// Whether `actual` satisfies a condition: a comma-separated list of values,
+/// any of which may match, and `!value`s, none of which may. No condition
+/// always matches.
+fn matches(condition: Option<&String>, actual: Option<&str>) -> bool {
+    let Some(condition) = condition else {
+        return true;
+    };
+    let (excluded, included): (Vec<&str>, Vec<&str>) = condition
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .partition(|v| v.starts_with('!'));
+    let is = |value: &str| actual == Some(value);
+    (included.is_empty() || included.iter().any(|v| is(v))) && !excluded.iter().any(|v| is(&v[1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET: Conditions = Conditions {
+        version: Some("v2"),
+        locale: Some("fr"),
+        profile: None,
+    };
+
+    #[test]
+    fn keeps_matching_blocks() {
+        let input = "A\n:::only{version=\"v2\"}\nB\n:::\n:::only{version=\"v1\" locale=\"fr\"}\nC\n:::\nD\n";
+        assert_eq!(TARGET.apply(input), "A\nB\nD\n");
+    }
+
+    #[test]
+    fn value_lists_and_negation() {
+        let input = ":::only{version=\"v1, v2\"}\nA\n:::\n:::only{locale=\"!fr\"}\nB\n:::\n:::only{profile=\"!internal\"}\nC\n:::";
+        assert_eq!(TARGET.apply(input), "A\nC");
+
+        let internal = Conditions {
+            profile: Some("internal"),
+            ..TARGET
+        };
+        assert_eq!(
+            internal.apply(":::only{profile=\"internal\"}\nA\n:::\n"),
+            "A\n"
+        );
+        assert_eq!(
+            TARGET.apply("X\n:::only{profile=\"internal\"}\nA\n:::\n"),
+            "X\n"
+        );
+    }
+
+    #[test]
+    fn nested_blocks_and_directives() {
+        let input = "::::only{locale=\"fr\"}\n:::note\nNote\n:::\n:::only{version=\"v1\"}\nOld\n:::\n::::\n";
+        assert_eq!(TARGET.apply(input), ":::note\nNote\n:::\n");
+    }
+
+    #[test]
+    fn leaves_code_and_unclosed_blocks() {
+        let input = "```\n:::only{version=\"v1\"}\nA\n:::\n```\n:::only{version=\"v1\"}\nB\n";
+        assert_eq!(TARGET.apply(input), input);
+    }
+
+    #[test]
+    fn skips_code_when_finding_the_closing_fence() {
+        let input = ":::only{version=\"v1\"}\n````md\n:::note\nA\n:::\n```\n:::\n````\nB\n:::\nC\n";
+        assert_eq!(TARGET.apply(input), "C\n");
+    }
+
+    #[test]
+    fn warns_about_unknown_attributes() {
+        let (result, warnings) =
+            diagnostics::capture(|| TARGET.apply(":::only{edition=\"pro\"}\nA\n:::\n"));
+        assert_eq!(result, "A\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "invalid-directive-attribute");
+    }
+}
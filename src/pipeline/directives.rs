@@ -18,6 +18,26 @@ static ATTR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(\w[\w-]*)="([^
 static INLINE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r":::([\w][\w-]*)\{([^}]*)\}").unwrap());
 
+/// Whether the trimmed line opens, is inside or closes a fenced code block.
+/// `fence` tracks the open fence's character and length: it only closes on a
+/// line of at least as many of the same character and nothing else.
+pub fn in_fence(fence: &mut Option<(char, usize)>, trimmed: &str) -> bool {
+    if let Some((fence_char, fence_len)) = *fence {
+        let count = trimmed.chars().take_while(|&c| c == fence_char).count();
+        if count >= fence_len && trimmed.chars().skip(count).all(char::is_whitespace) {
+            *fence = None;
+        }
+        return true;
+    }
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+        let fence_char = trimmed.chars().next().unwrap();
+        let count = trimmed.chars().take_while(|&c| c == fence_char).count();
+        *fence = Some((fence_char, count));
+        return true;
+    }
+    false
+}
+
 /// Pre-comrak pass: parse `:::directive{attrs}` blocks and replace them with
 /// HTML placeholder comments that will survive Markdown rendering.
 /// The returned string has directives replaced with rendered component HTML.
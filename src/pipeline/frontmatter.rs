@@ -1,41 +1,637 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::config::{FieldRule, FieldType};
+use crate::diagnostics;
+use crate::project::Listing;
+
+static POSITION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r" at line \d+ column \d+").unwrap());
 
 /// Parsed front matter metadata from a Markdown file.
-#[derive(Debug, Default, Clone, Deserialize)]
-#[serde(default)]
+#[derive(Debug, Clone)]
 pub struct FrontMatter {
     pub title: Option<String>,
     pub description: Option<String>,
     pub author: Option<String>,
     pub date: Option<String>,
     pub slug: Option<String>,
+    /// Template in `theme/templates/` that renders the page, e.g. `landing`.
+    pub layout: Option<String>,
+    /// Keeps the page out of production builds.
+    pub draft: bool,
+    /// Keeps the page out of production builds until this date or time,
+    /// e.g. `2025-03-01` or `2025-03-01T09:00:00Z`.
+    pub publish_after: Option<String>,
+    /// Lists the page in the search index (`search: false` leaves it out).
+    pub search: bool,
+    /// Lists the page in `sitemap.xml` (`sitemap: false` leaves it out).
+    pub sitemap: bool,
+    /// Lists the page in auto-generated navigation (`nav: false` leaves it out).
+    pub nav: bool,
+    /// Label in auto-generated navigation, instead of the title.
+    pub nav_label: Option<String>,
+    /// Position among its siblings in auto-generated navigation; lower comes
+    /// first. `weight` is accepted as an alias.
+    pub nav_order: Option<i64>,
+    /// Asks search engines not to index the page with a robots meta tag, and
+    /// leaves it out of `sitemap.xml`.
+    pub noindex: bool,
+    /// Every other key, available to templates as `page.meta`.
+    pub extra: Map<String, Value>,
+}
+
+impl Default for FrontMatter {
+    fn default() -> Self {
+        Self {
+            title: None,
+            description: None,
+            author: None,
+            date: None,
+            slug: None,
+            layout: None,
+            draft: false,
+            publish_after: None,
+            search: true,
+            sitemap: true,
+            nav: true,
+            nav_label: None,
+            nav_order: None,
+            noindex: false,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl FrontMatter {
+    /// Take the known fields out of `fields`, keeping the rest as `extra`.
+    /// On error, returns the offending key and a message.
+    fn from_fields(mut fields: Map<String, Value>) -> Result<Self, (&'static str, String)> {
+        let title = take_string(&mut fields, "title")?;
+        let description = take_string(&mut fields, "description")?;
+        let author = take_string(&mut fields, "author")?;
+        let date = take_string(&mut fields, "date")?;
+        let slug = take_string(&mut fields, "slug")?;
+        let layout = take_string(&mut fields, "layout")?;
+        let draft = take_bool(&mut fields, "draft", false)?;
+        let publish_after = take_string(&mut fields, "publish_after")?;
+        if let Some(ref date) = publish_after
+            && unix_time(date).is_none()
+        {
+            return Err((
+                "publish_after",
+                format!(
+                    "publish_after: expected a date like 2025-03-01 or 2025-03-01T09:00:00Z, found \"{date}\""
+                ),
+            ));
+        }
+        let search = take_bool(&mut fields, "search", true)?;
+        let sitemap = take_bool(&mut fields, "sitemap", true)?;
+        let nav = take_bool(&mut fields, "nav", true)?;
+        let nav_label = take_string(&mut fields, "nav_label")?;
+        let weight = take_integer(&mut fields, "weight")?;
+        let nav_order = take_integer(&mut fields, "nav_order")?.or(weight);
+        let noindex = take_bool(&mut fields, "noindex", false)?;
+        Ok(Self {
+            title,
+            description,
+            author,
+            date,
+            slug,
+            layout,
+            draft,
+            publish_after,
+            search,
+            sitemap,
+            nav,
+            nav_label,
+            nav_order,
+            noindex,
+            extra: fields,
+        })
+    }
+}
+
+impl FrontMatter {
+    /// The value of a field, built-in or custom. `null` counts as unset.
+    pub fn field(&self, name: &str) -> Option<Value> {
+        let known = match name {
+            "title" => &self.title,
+            "description" => &self.description,
+            "author" => &self.author,
+            "date" => &self.date,
+            "slug" => &self.slug,
+            "layout" => &self.layout,
+            "nav_label" => &self.nav_label,
+            "nav_order" | "weight" => return self.nav_order.map(Value::from),
+            "publish_after" => &self.publish_after,
+            "draft" => return self.draft.then_some(Value::Bool(true)),
+            "noindex" => return self.noindex.then_some(Value::Bool(true)),
+            "search" => return (!self.search).then_some(Value::Bool(false)),
+            "sitemap" => return (!self.sitemap).then_some(Value::Bool(false)),
+            "nav" => return (!self.nav).then_some(Value::Bool(false)),
+            _ => return self.extra.get(name).filter(|v| !v.is_null()).cloned(),
+        };
+        known.clone().map(Value::String)
+    }
+}
+
+impl FrontMatter {
+    /// Why the page is left out of a production build at `now` (seconds since
+    /// the Unix epoch), or `None` if it is published.
+    pub fn unpublished(&self, now: i64) -> Option<Unpublished> {
+        if self.draft {
+            return Some(Unpublished::Draft);
+        }
+        let date = self.publish_after.as_ref()?;
+        (unix_time(date)? > now).then(|| Unpublished::Scheduled(date.clone()))
+    }
+}
+
+impl FrontMatter {
+    /// Which listings include the page.
+    pub fn listing(&self) -> Listing {
+        Listing {
+            nav: self.nav,
+            search: self.search,
+            sitemap: self.sitemap && !self.noindex,
+        }
+    }
+}
+
+/// Why a page is not published yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unpublished {
+    /// `draft: true`.
+    Draft,
+    /// `publish_after` is in the future.
+    Scheduled(String),
+}
+
+impl fmt::Display for Unpublished {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unpublished::Draft => write!(
+                f,
+                "This page is a draft and is left out of production builds."
+            ),
+            Unpublished::Scheduled(date) => write!(
+                f,
+                "This page is scheduled for {date} and is left out of production builds until then."
+            ),
+        }
+    }
+}
+
+/// Seconds since the Unix epoch for a `publish_after` value: a `YYYY-MM-DD`
+/// date (midnight UTC), optionally followed by `THH:MM[:SS]` and a `Z` or
+/// `±HH:MM` offset. Times without an offset are read as UTC.
+fn unix_time(value: &str) -> Option<i64> {
+    let date = value
+        .get(..10)
+        .filter(|d| is_date(d, DEFAULT_DATE_FORMAT))?;
+    let number = |s: &str| s.parse::<i64>().ok();
+    let (year, month, day) = (
+        number(&date[..4])?,
+        number(&date[5..7])?,
+        number(&date[8..])?,
+    );
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let mut rest = &value[10..];
+    if rest.is_empty() {
+        return Some(days * 86_400);
+    }
+    rest = rest.strip_prefix(['T', 't', ' '])?;
+    let two_digits = |s: &str, max: i64| {
+        s.get(..2)
+            .and_then(number)
+            .filter(|n| (0..=max).contains(n))
+    };
+    let hours = two_digits(rest, 23)?;
+    rest = rest.get(2..)?.strip_prefix(':')?;
+    let minutes = two_digits(rest, 59)?;
+    rest = &rest[2..];
+    let mut seconds = 0;
+    if let Some(after) = rest.strip_prefix(':') {
+        seconds = two_digits(after, 60)?;
+        rest = &after[2..];
+        // Fractional seconds are ignored
+        if let Some(fraction) = rest.strip_prefix('.') {
+            rest = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+        }
+    }
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset = &rest[1..];
+            if offset.len() != 5 || offset.as_bytes()[2] != b':' {
+                return None;
+            }
+            sign * (two_digits(offset, 23)? * 3600 + two_digits(&offset[3..], 59)? * 60)
+        }
+    };
+    Some(days * 86_400 + hours * 3600 + minutes * 60 + seconds - offset)
+}
+
+fn take_string(
+    fields: &mut Map<String, Value>,
+    key: &'static str,
+) -> Result<Option<String>, (&'static str, String)> {
+    match fields.remove(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(other) => Err((key, format!("{key}: expected a string, found {other}"))),
+    }
+}
+
+fn take_integer(
+    fields: &mut Map<String, Value>,
+    key: &'static str,
+) -> Result<Option<i64>, (&'static str, String)> {
+    match fields.remove(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) if n.is_i64() => Ok(n.as_i64()),
+        Some(other) => Err((key, format!("{key}: expected an integer, found {other}"))),
+    }
+}
+
+fn take_bool(
+    fields: &mut Map<String, Value>,
+    key: &'static str,
+    default: bool,
+) -> Result<bool, (&'static str, String)> {
+    match fields.remove(key) {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::Bool(b)) => Ok(b),
+        Some(other) => Err((key, format!("{key}: expected true or false, found {other}"))),
+    }
+}
+
+/// The syntax a front matter block is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `---` delimiters around a JSON object.
+    Json,
+    /// `---` delimiters around YAML.
+    Yaml,
+    /// `+++` delimiters around TOML.
+    Toml,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Json => write!(f, "JSON"),
+            Format::Yaml => write!(f, "YAML"),
+            Format::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+/// A front matter block found at the start of a Markdown file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub format: Format,
+    /// The text between the delimiters.
+    pub content: &'a str,
+    /// 1-based line of the file that `content` starts on.
+    pub line: usize,
+    /// Everything after the closing delimiter line.
+    pub body: &'a str,
 }
 
-/// Extract JSON front matter from a Markdown source string.
+/// Front matter that could not be parsed. `line` and `column` are 1-based
+/// positions in the whole file, not just the block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub format: Format,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} front matter: {} (line {}, column {})",
+            self.format, self.message, self.line, self.column
+        )
+    }
+}
+
+/// Find the front matter block at the start of `source`: `---` … `---` for
+/// JSON or YAML (JSON when the content starts with `{`), or `+++` … `+++` for TOML.
 ///
-/// Expects the standard `---` delimiters at the start of the file.
-/// Returns `FrontMatter::default()` if no front matter is found or parsing fails.
-pub fn extract(source: &str) -> FrontMatter {
+/// Returns `None` if the file has no front matter or it is never closed.
+pub fn find(source: &str) -> Option<Block<'_>> {
     let trimmed = source.trim_start();
-    if !trimmed.starts_with("---") {
-        return FrontMatter::default();
-    }
+    let skipped_lines = source[..source.len() - trimmed.len()].matches('\n').count();
 
-    // Find the closing delimiter after the opening `---`
+    let delimiter = if trimmed.starts_with("---") {
+        "---"
+    } else if trimmed.starts_with("+++") {
+        "+++"
+    } else {
+        return None;
+    };
+
+    // The opening delimiter must be alone on its line
     let after_open = &trimmed[3..];
     let rest = after_open
         .strip_prefix('\n')
-        .or_else(|| after_open.strip_prefix("\r\n"));
-    let Some(rest) = rest else {
-        return FrontMatter::default();
+        .or_else(|| after_open.strip_prefix("\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let content = &rest[..offset];
+            let format = match delimiter {
+                "+++" => Format::Toml,
+                _ if content.trim_start().starts_with('{') => Format::Json,
+                _ => Format::Yaml,
+            };
+            return Some(Block {
+                format,
+                content,
+                line: skipped_lines + 2,
+                body: &rest[offset + line.len()..],
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// `source` without its front matter block, if it has one.
+pub fn strip(source: &str) -> &str {
+    find(source).map_or(source, |block| block.body)
+}
+
+/// Parse the front matter of a Markdown source string.
+///
+/// Returns `FrontMatter::default()` if there is no front matter.
+pub fn parse(source: &str) -> Result<FrontMatter, ParseError> {
+    let Some(block) = find(source) else {
+        return Ok(FrontMatter::default());
+    };
+    if block.content.trim().is_empty() {
+        return Ok(FrontMatter::default());
+    }
+
+    let error = |message: String, line: usize, column: usize| ParseError {
+        format: block.format,
+        message,
+        line: block.line + line.max(1) - 1,
+        column: column.max(1),
     };
 
-    let Some(end) = rest.find("\n---") else {
-        return FrontMatter::default();
+    // Each parser reports a 1-based line and column within the block
+    let value = match block.format {
+        Format::Json => serde_json::from_str(block.content)
+            .map_err(|e| error(strip_position(&e.to_string()), e.line(), e.column()))?,
+        Format::Yaml => serde_yaml_ng::from_str(block.content).map_err(|e| {
+            let (line, column) = e
+                .location()
+                .map(|l| (l.line(), l.column()))
+                .unwrap_or((1, 1));
+            error(strip_position(&e.to_string()), line, column)
+        })?,
+        Format::Toml => match block.content.parse::<toml::Table>() {
+            Ok(table) => toml_to_json(toml::Value::Table(table)),
+            Err(e) => {
+                let start = e.span().map(|s| s.start).unwrap_or(0);
+                let before = &block.content[..start];
+                let line = before.matches('\n').count() + 1;
+                let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+                return Err(error(e.message().to_string(), line, column));
+            }
+        },
     };
 
-    let content = &rest[..end];
-    serde_json::from_str(content).unwrap_or_default()
+    let fields = match value {
+        Value::Object(fields) => fields,
+        // A YAML block holding only comments
+        Value::Null => return Ok(FrontMatter::default()),
+        other => {
+            return Err(error(
+                format!("expected a set of fields, found {other}"),
+                1,
+                1,
+            ));
+        }
+    };
+    FrontMatter::from_fields(fields)
+        .map_err(|(key, message)| error(message, key_line(block.content, key).unwrap_or(1), 1))
+}
+
+/// Extract front matter from a Markdown source string, falling back to
+/// `FrontMatter::default()` if there is none. A block that fails to parse is
+/// reported as a build warning against `file`.
+pub fn extract(source: &str, file: &Path) -> FrontMatter {
+    parse(source).unwrap_or_else(|e| {
+        diagnostics::warn_frontmatter_parse_error(file, &e);
+        FrontMatter::default()
+    })
+}
+
+/// A front matter field that breaks a `[frontmatter.schema]` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub field: String,
+    pub message: String,
+    /// 1-based line of the file: the field's line, or the start of the front
+    /// matter for a missing field.
+    pub line: usize,
+}
+
+/// Check the front matter of `source` against `schema`, returning violations
+/// in field name order. `path` is the page's path relative to the content
+/// directory, used to pick the rules whose `folders` apply.
+pub fn check_schema(
+    source: &str,
+    fm: &FrontMatter,
+    schema: &BTreeMap<String, FieldRule>,
+    path: &Path,
+) -> Vec<SchemaViolation> {
+    let block = find(source);
+    let mut violations = Vec::new();
+
+    for (name, rule) in schema {
+        if !rule.applies_to(path) {
+            continue;
+        }
+        let Some(value) = fm.field(name) else {
+            if rule.required {
+                violations.push(SchemaViolation {
+                    field: name.clone(),
+                    message: format!("missing required field '{name}'"),
+                    // The opening delimiter, or the top of a file without front matter
+                    line: block.map(|b| b.line - 1).unwrap_or(1),
+                });
+            }
+            continue;
+        };
+        if let Some(message) = check_value(name, rule, &value) {
+            let line = field_line(source, name).unwrap_or(1);
+            violations.push(SchemaViolation {
+                field: name.clone(),
+                message,
+                line,
+            });
+        }
+    }
+    violations
+}
+
+/// Check a field's value against its rule, returning the problem if any.
+fn check_value(name: &str, rule: &FieldRule, value: &Value) -> Option<String> {
+    if let Some(kind) = rule.kind {
+        let format = rule.format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT);
+        let ok = match kind {
+            FieldType::String => value.is_string(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Number => value.is_number(),
+            FieldType::Boolean => value.is_boolean(),
+            FieldType::Date => value.as_str().is_some_and(|s| is_date(s, format)),
+            FieldType::List => value.is_array(),
+        };
+        if !ok {
+            return Some(match kind {
+                FieldType::Date => {
+                    format!("field '{name}' must be a date in {format} format, found {value}")
+                }
+                _ => format!("field '{name}' must be {kind}, found {value}"),
+            });
+        }
+    }
+
+    if !rule.allowed.is_empty() {
+        // Every item of a list must be allowed
+        let items = match value {
+            Value::Array(items) => items.as_slice(),
+            _ => std::slice::from_ref(value),
+        };
+        let allowed = |item: &Value| {
+            let text = match item {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            rule.allowed.contains(&text)
+        };
+        if let Some(item) = items.iter().find(|item| !allowed(item)) {
+            return Some(format!(
+                "field '{name}' must be one of {}, found {item}",
+                rule.allowed.join(", ")
+            ));
+        }
+    }
+    None
+}
+
+/// The date layout used when a `date` rule sets no `format`.
+pub const DEFAULT_DATE_FORMAT: &str = "YYYY-MM-DD";
+
+/// Whether `value` is a date laid out as `format`, where `YYYY`, `MM` and `DD`
+/// stand for the year, month and day and any other character must match as is.
+fn is_date(value: &str, format: &str) -> bool {
+    let mut value = value;
+    let mut format = format;
+    while !format.is_empty() {
+        let (token, range) = match format {
+            f if f.starts_with("YYYY") => ("YYYY", 0..=9999),
+            f if f.starts_with("MM") => ("MM", 1..=12),
+            f if f.starts_with("DD") => ("DD", 1..=31),
+            f => {
+                let c = f.chars().next().unwrap();
+                match value.strip_prefix(c) {
+                    Some(rest) => value = rest,
+                    None => return false,
+                }
+                format = &format[c.len_utf8()..];
+                continue;
+            }
+        };
+        let Some(digits) = value.get(..token.len()) else {
+            return false;
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit())
+            || !range.contains(&digits.parse::<u32>().unwrap_or(0))
+        {
+            return false;
+        }
+        value = &value[token.len()..];
+        format = &format[token.len()..];
+    }
+    value.is_empty()
+}
+
+/// Drop the "at line X column Y" positions parsers add to messages, which are
+/// relative to the block; [`ParseError`] carries the position in the whole file.
+fn strip_position(message: &str) -> String {
+    POSITION_RE.replace_all(message, "").into_owned()
+}
+
+/// The 1-based line of `source` where the front matter sets `key`, if it does.
+pub fn field_line(source: &str, key: &str) -> Option<usize> {
+    let block = find(source)?;
+    key_line(block.content, key).map(|line| block.line + line - 1)
+}
+
+/// The 1-based line of `content` where `key` is set, in any of the formats.
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    let quoted = format!("\"{key}\"");
+    content
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            let rest = line
+                .strip_prefix(quoted.as_str())
+                .or_else(|| line.strip_prefix(key));
+            rest.is_some_and(|rest| rest.trim_start().starts_with([':', '=']))
+        })
+        .map(|i| i + 1)
+}
+
+/// Convert TOML to JSON values, writing datetimes as strings (`date = 2024-01-15`
+/// becomes `"2024-01-15"`).
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
 }
 
 #[cfg(test)]
@@ -45,7 +641,7 @@ mod tests {
     #[test]
     fn full_front_matter() {
         let source = "---\n{\"title\": \"Getting Started\", \"description\": \"Learn how to set up DocAnvil\", \"author\": \"Jane Doe\", \"date\": \"2024-01-15\"}\n---\n\n# Hello";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert_eq!(fm.title.as_deref(), Some("Getting Started"));
         assert_eq!(
             fm.description.as_deref(),
@@ -58,7 +654,7 @@ mod tests {
     #[test]
     fn partial_front_matter() {
         let source = "---\n{\"title\": \"My Page\"}\n---\n\nContent here";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert_eq!(fm.title.as_deref(), Some("My Page"));
         assert!(fm.description.is_none());
         assert!(fm.author.is_none());
@@ -68,7 +664,7 @@ mod tests {
     #[test]
     fn no_front_matter() {
         let source = "# Just a heading\n\nSome content.";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert!(fm.title.is_none());
         assert!(fm.description.is_none());
     }
@@ -76,7 +672,7 @@ mod tests {
     #[test]
     fn empty_front_matter() {
         let source = "---\n{}\n---\n\nContent";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert!(fm.title.is_none());
         assert!(fm.description.is_none());
     }
@@ -84,22 +680,38 @@ mod tests {
     #[test]
     fn invalid_json() {
         let source = "---\n{not valid json\n---\n\nContent";
-        let fm = extract(source);
-        assert!(fm.title.is_none());
+        let err = parse(source).unwrap_err();
+        assert_eq!(err.format, Format::Json);
+        assert_eq!((err.line, err.column), (2, 2));
+        assert!(err.message.contains("key must be a string"), "{err}");
     }
 
     #[test]
-    fn unknown_fields_ignored() {
+    fn unknown_fields_kept_as_extra() {
         let source = "---\n{\"title\": \"My Page\", \"custom_field\": \"some value\", \"tags\": [\"a\", \"b\", \"c\"]}\n---\n\nContent";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert_eq!(fm.title.as_deref(), Some("My Page"));
+        assert_eq!(fm.extra["custom_field"], "some value");
+        assert_eq!(fm.extra["tags"], serde_json::json!(["a", "b", "c"]));
+        assert!(!fm.extra.contains_key("title"));
+    }
+
+    #[test]
+    fn extra_fields_in_every_format() {
+        let yaml = "---\nowner: docs-team\nreview:\n  due: 2025-03-01\n---\n";
+        let toml = "+++\nowner = \"docs-team\"\n[review]\ndue = 2025-03-01\n+++\n";
+        for source in [yaml, toml] {
+            let fm = parse(source).unwrap();
+            assert_eq!(fm.extra["owner"], "docs-team");
+            assert_eq!(fm.extra["review"]["due"], "2025-03-01");
+        }
     }
 
     #[test]
     fn explicit_slug_field() {
         let source =
             "---\n{\"title\": \"My Page\", \"slug\": \"custom-slug\"}\n---\n\nContent here";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert_eq!(fm.title.as_deref(), Some("My Page"));
         assert_eq!(fm.slug.as_deref(), Some("custom-slug"));
     }
@@ -107,7 +719,7 @@ mod tests {
     #[test]
     fn slug_without_title() {
         let source = "---\n{\"slug\": \"override-slug\"}\n---\n\nContent";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         assert!(fm.title.is_none());
         assert_eq!(fm.slug.as_deref(), Some("override-slug"));
     }
@@ -115,8 +727,254 @@ mod tests {
     #[test]
     fn no_closing_delimiter() {
         let source = "---\n{\"title\": \"Broken\"}\n\nContent without closing delimiter";
-        let fm = extract(source);
+        let fm = parse(source).unwrap();
         // No closing `---`, so no valid front matter
         assert!(fm.title.is_none());
     }
+
+    #[test]
+    fn yaml_front_matter() {
+        let source = "---\ntitle: Getting Started\ndescription: \"Set up: quickly\"\ndate: 2024-01-15\ntags: [a, b]\n---\n\n# Hello";
+        let fm = parse(source).unwrap();
+        assert_eq!(fm.title.as_deref(), Some("Getting Started"));
+        assert_eq!(fm.description.as_deref(), Some("Set up: quickly"));
+        assert_eq!(fm.date.as_deref(), Some("2024-01-15"));
+    }
+
+    #[test]
+    fn toml_front_matter() {
+        let source =
+            "+++\ntitle = \"Getting Started\"\nslug = \"start\"\ndate = 2024-01-15\n+++\n\n# Hello";
+        let fm = parse(source).unwrap();
+        assert_eq!(fm.title.as_deref(), Some("Getting Started"));
+        assert_eq!(fm.slug.as_deref(), Some("start"));
+        assert_eq!(fm.date.as_deref(), Some("2024-01-15"));
+    }
+
+    #[test]
+    fn detects_format() {
+        assert_eq!(find("---\n{}\n---\n").unwrap().format, Format::Json);
+        assert_eq!(find("---\na: 1\n---\n").unwrap().format, Format::Yaml);
+        assert_eq!(find("+++\na = 1\n+++\n").unwrap().format, Format::Toml);
+    }
+
+    #[test]
+    fn strip_front_matter() {
+        assert_eq!(strip("---\ntitle: A\n---\n\n# Hello\n"), "\n# Hello\n");
+        assert_eq!(strip("+++\na = 1\n+++\r\nBody"), "Body");
+        assert_eq!(strip("# No front matter"), "# No front matter");
+    }
+
+    #[test]
+    fn invalid_yaml_reports_position() {
+        let source = "\n---\ntitle: ok\nauthor: [unclosed\n---\n";
+        let err = parse(source).unwrap_err();
+        assert_eq!(err.format, Format::Yaml);
+        // Blank first line, then the delimiter: the block starts on line 3
+        assert_eq!(err.line, 5, "{err}");
+        assert_eq!(
+            err.message,
+            "did not find expected ',' or ']', while parsing a flow sequence"
+        );
+    }
+
+    #[test]
+    fn invalid_toml_reports_position() {
+        let source = "+++\ntitle = \"ok\"\nauthor = \n+++\n";
+        let err = parse(source).unwrap_err();
+        assert_eq!(err.format, Format::Toml);
+        assert_eq!((err.line, err.column), (3, 10));
+    }
+
+    fn schema(toml: &str) -> BTreeMap<String, FieldRule> {
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            schema: BTreeMap<String, FieldRule>,
+        }
+        toml::from_str::<Wrapper>(toml).unwrap().schema
+    }
+
+    fn violations(source: &str, schema: &BTreeMap<String, FieldRule>, path: &str) -> Vec<String> {
+        let fm = parse(source).unwrap();
+        check_schema(source, &fm, schema, Path::new(path))
+            .into_iter()
+            .map(|v| format!("{}: {}", v.line, v.message))
+            .collect()
+    }
+
+    #[test]
+    fn schema_reports_missing_required_fields() {
+        let schema = schema(
+            "[schema.owner]\nrequired = true\n[schema.title]\nrequired = true\n[schema.tags]\n",
+        );
+        assert_eq!(
+            violations("---\ntitle: Policy\n---\n# Policy", &schema, "policy.md"),
+            ["1: missing required field 'owner'"]
+        );
+        assert_eq!(
+            violations("# Policy", &schema, "policy.md"),
+            [
+                "1: missing required field 'owner'",
+                "1: missing required field 'title'"
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_checks_types() {
+        let schema = schema(
+            "[schema.owner]\ntype = \"string\"\n[schema.rating]\ntype = \"integer\"\n\
+             [schema.tags]\ntype = \"list\"\n[schema.featured]\ntype = \"boolean\"\n",
+        );
+        let source = "---\nowner: 42\nrating: 1.5\ntags: [a]\nfeatured: yes\n---\n";
+        assert_eq!(
+            violations(source, &schema, "page.md"),
+            [
+                "5: field 'featured' must be true or false, found \"yes\"",
+                "2: field 'owner' must be a string, found 42",
+                "3: field 'rating' must be an integer, found 1.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_checks_enums() {
+        let schema = schema("[schema.status]\nenum = [\"draft\", \"approved\"]\n");
+        assert!(violations("---\nstatus: approved\n---\n", &schema, "a.md").is_empty());
+        assert!(violations("---\nstatus: [draft, approved]\n---\n", &schema, "a.md").is_empty());
+        assert_eq!(
+            violations("+++\nstatus = \"wip\"\n+++\n", &schema, "a.md"),
+            ["2: field 'status' must be one of draft, approved, found \"wip\""]
+        );
+    }
+
+    #[test]
+    fn schema_checks_date_formats() {
+        let schema = schema(
+            "[schema.review_date]\ntype = \"date\"\n\
+             [schema.published]\ntype = \"date\"\nformat = \"DD/MM/YYYY\"\n",
+        );
+        let valid = "+++\nreview_date = 2025-03-01\npublished = \"01/03/2025\"\n+++\n";
+        assert!(violations(valid, &schema, "a.md").is_empty());
+        let invalid = "---\nreview_date: 2025-13-01\npublished: 2025-03-01\n---\n";
+        assert_eq!(
+            violations(invalid, &schema, "a.md"),
+            [
+                "3: field 'published' must be a date in DD/MM/YYYY format, found \"2025-03-01\"",
+                "2: field 'review_date' must be a date in YYYY-MM-DD format, found \"2025-13-01\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_rules_apply_to_their_folders() {
+        let schema = schema("[schema.owner]\nrequired = true\nfolders = [\"compliance\"]\n");
+        assert!(violations("# Setup", &schema, "guides/setup.md").is_empty());
+        assert_eq!(
+            violations("# Policy", &schema, "compliance/policy.md"),
+            ["1: missing required field 'owner'"]
+        );
+    }
+
+    #[test]
+    fn date_layouts() {
+        assert!(is_date("2025-03-01", "YYYY-MM-DD"));
+        assert!(is_date("2025.03", "YYYY.MM"));
+        assert!(!is_date("2025-3-01", "YYYY-MM-DD"));
+        assert!(!is_date("2025-03-32", "YYYY-MM-DD"));
+        assert!(!is_date("2025-03-01T10:00", "YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn layout_field() {
+        let source = "---\ntitle: Home\nlayout: landing\n---\n";
+        let fm = parse(source).unwrap();
+        assert_eq!(fm.layout.as_deref(), Some("landing"));
+        assert!(!fm.extra.contains_key("layout"));
+        assert_eq!(field_line(source, "layout"), Some(3));
+        assert_eq!(field_line("# No front matter", "layout"), None);
+    }
+
+    #[test]
+    fn publish_times() {
+        assert_eq!(unix_time("1970-01-01"), Some(0));
+        assert_eq!(unix_time("2000-03-01"), Some(951_868_800));
+        assert_eq!(unix_time("2024-02-29T12:30:15Z"), Some(1_709_209_815));
+        assert_eq!(unix_time("2024-02-29T12:30:15.250Z"), Some(1_709_209_815));
+        assert_eq!(unix_time("2024-02-29T14:30:15+02:00"), Some(1_709_209_815));
+        assert_eq!(unix_time("2024-02-29 12:30"), Some(1_709_209_800));
+        assert_eq!(unix_time("2024-02-29T12"), None);
+        assert_eq!(unix_time("next week"), None);
+    }
+
+    #[test]
+    fn draft_and_scheduled_pages() {
+        let now = unix_time("2025-06-01").unwrap();
+        let published = parse("---\ntitle: Live\npublish_after: 2025-05-31\n---\n").unwrap();
+        assert_eq!(published.unpublished(now), None);
+
+        let draft = parse("---\ndraft: true\n---\n").unwrap();
+        assert_eq!(draft.unpublished(now), Some(Unpublished::Draft));
+        assert!(!draft.extra.contains_key("draft"));
+
+        let scheduled = parse("+++\npublish_after = 2025-06-01T09:00:00Z\n+++\n").unwrap();
+        assert_eq!(
+            scheduled.unpublished(now),
+            Some(Unpublished::Scheduled("2025-06-01T09:00:00Z".into()))
+        );
+        assert_eq!(scheduled.unpublished(now + 86_400), None);
+    }
+
+    #[test]
+    fn invalid_publishing_fields() {
+        let err = parse("---\ndraft: yes please\n---\n").unwrap_err();
+        assert_eq!(
+            err.message,
+            "draft: expected true or false, found \"yes please\""
+        );
+        let err = parse("---\ntitle: x\npublish_after: soon\n---\n").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(
+            err.message.starts_with("publish_after: expected a date"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn listing_flags() {
+        let listed = parse("---\ntitle: x\n---\n").unwrap();
+        assert_eq!(listed.listing(), Listing::default());
+        assert!(!listed.noindex);
+
+        let hidden = parse("---\nnav: false\nsearch: false\nnoindex: true\n---\n").unwrap();
+        let listing = hidden.listing();
+        assert!(!listing.nav && !listing.search && !listing.sitemap);
+        assert!(hidden.noindex);
+        assert_eq!(hidden.field("nav"), Some(Value::Bool(false)));
+
+        let err = parse("---\nsitemap: \"no\"\n---\n").unwrap_err();
+        assert_eq!(err.message, "sitemap: expected true or false, found \"no\"");
+    }
+
+    #[test]
+    fn nav_label_and_order() {
+        let fm = parse("---\nnav_label: Setup\nnav_order: 3\n---\n").unwrap();
+        assert_eq!(fm.nav_label.as_deref(), Some("Setup"));
+        assert_eq!(fm.nav_order, Some(3));
+
+        let fm = parse("+++\nweight = -1\n+++\n").unwrap();
+        assert_eq!(fm.nav_order, Some(-1));
+        assert!(fm.extra.is_empty());
+
+        let err = parse("---\nnav_order: 1.5\n---\n").unwrap_err();
+        assert_eq!(err.message, "nav_order: expected an integer, found 1.5");
+    }
+
+    #[test]
+    fn wrong_type_is_an_error() {
+        let source = "---\ndescription: ok\ntitle: [a, b]\n---\n";
+        let err = parse(source).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "title: expected a string, found [\"a\",\"b\"]");
+    }
 }
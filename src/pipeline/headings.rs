@@ -12,7 +12,7 @@ use std::sync::LazyLock;
 ///   `### My Great Heading\n\n{#custom-id}`
 pub fn extract_custom_heading_ids(source: &str) -> String {
     static HEADING_ID_RE: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?m)^(#{1,6}\s+.*?)\s+\{#([\w-]+)\}\s*$").unwrap());
+        LazyLock::new(|| Regex::new(r"(?m)^(#{1,6}\s+.*?)\s+\{#([\w-]+)\}[ \t]*$").unwrap());
 
     HEADING_ID_RE
         .replace_all(source, "$1\n\n{#$2}")
@@ -164,7 +164,8 @@ mod tests {
     fn multiple_headings_with_custom_ids() {
         let source = "## First {#one}\n\nSome text\n\n### Second {#two}";
         let result = extract_custom_heading_ids(source);
-        assert!(result.contains("## First\n\n{#one}"));
+        // The blank line after a heading stays, so `{#one}` is its own paragraph
+        assert!(result.contains("## First\n\n{#one}\n\nSome text"));
         assert!(result.contains("### Second\n\n{#two}"));
     }
 
@@ -3,6 +3,8 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
+use crate::source::FileSource;
+
 static IMG_SRC_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"<img\b([^>]*)\bsrc\s*=\s*"([^"]*)"([^>]*)>"#).unwrap());
 
@@ -10,7 +12,12 @@ static IMG_SRC_RE: LazyLock<Regex> =
 ///
 /// Skips absolute paths (`/`), URLs (`http://`, `https://`), and data URIs (`data:`).
 /// For relative paths, checks if the file exists at project root; if not, tries under `assets/`.
-pub fn rewrite_image_paths(html: &str, base_url: &str, project_root: &Path) -> String {
+pub fn rewrite_image_paths(
+    html: &str,
+    base_url: &str,
+    project_root: &Path,
+    files: &dyn FileSource,
+) -> String {
     IMG_SRC_RE
         .replace_all(html, |caps: &regex::Captures| {
             let before = &caps[1];
@@ -26,12 +33,12 @@ pub fn rewrite_image_paths(html: &str, base_url: &str, project_root: &Path) -> S
             }
 
             // Check if the path exists directly relative to project root
-            let resolved = if project_root.join(src).exists() {
+            let resolved = if files.exists(&project_root.join(src)) {
                 Some(src.to_string())
             } else {
                 // Try under assets/ as a fallback
                 let assets_path = format!("assets/{src}");
-                if project_root.join(&assets_path).exists() {
+                if files.exists(&project_root.join(&assets_path)) {
                     Some(assets_path)
                 } else {
                     None
@@ -54,6 +61,7 @@ pub fn rewrite_image_paths(html: &str, base_url: &str, project_root: &Path) -> S
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source::DiskFiles;
     use std::fs;
 
     #[test]
@@ -64,7 +72,7 @@ mod tests {
         fs::write(assets.join("diagram.png"), b"fake").unwrap();
 
         let html = r#"<p><img src="assets/diagram.png" alt="diagram"></p>"#;
-        let result = rewrite_image_paths(html, "/docs/", dir.path());
+        let result = rewrite_image_paths(html, "/docs/", dir.path(), &DiskFiles);
         assert_eq!(
             result,
             r#"<p><img src="/docs/assets/diagram.png" alt="diagram"></p>"#
@@ -79,7 +87,7 @@ mod tests {
         fs::write(assets.join("photo.jpg"), b"fake").unwrap();
 
         let html = r#"<img src="photo.jpg" alt="photo">"#;
-        let result = rewrite_image_paths(html, "/", dir.path());
+        let result = rewrite_image_paths(html, "/", dir.path(), &DiskFiles);
         assert_eq!(result, r#"<img src="/assets/photo.jpg" alt="photo">"#);
     }
 
@@ -87,7 +95,7 @@ mod tests {
     fn skips_absolute_paths() {
         let dir = tempfile::tempdir().unwrap();
         let html = r#"<img src="/absolute/image.png" alt="abs">"#;
-        let result = rewrite_image_paths(html, "/docs/", dir.path());
+        let result = rewrite_image_paths(html, "/docs/", dir.path(), &DiskFiles);
         assert_eq!(result, html);
     }
 
@@ -95,7 +103,7 @@ mod tests {
     fn skips_http_urls() {
         let dir = tempfile::tempdir().unwrap();
         let html = r#"<img src="https://example.com/img.png" alt="ext">"#;
-        let result = rewrite_image_paths(html, "/docs/", dir.path());
+        let result = rewrite_image_paths(html, "/docs/", dir.path(), &DiskFiles);
         assert_eq!(result, html);
     }
 
@@ -103,7 +111,7 @@ mod tests {
     fn skips_data_uris() {
         let dir = tempfile::tempdir().unwrap();
         let html = r#"<img src="data:image/png;base64,abc" alt="data">"#;
-        let result = rewrite_image_paths(html, "/docs/", dir.path());
+        let result = rewrite_image_paths(html, "/docs/", dir.path(), &DiskFiles);
         assert_eq!(result, html);
     }
 
@@ -111,7 +119,7 @@ mod tests {
     fn rewrites_missing_file_with_base_url() {
         let dir = tempfile::tempdir().unwrap();
         let html = r#"<img src="missing.png" alt="gone">"#;
-        let result = rewrite_image_paths(html, "/sub/", dir.path());
+        let result = rewrite_image_paths(html, "/sub/", dir.path(), &DiskFiles);
         assert_eq!(result, r#"<img src="/sub/missing.png" alt="gone">"#);
     }
 
@@ -123,7 +131,7 @@ mod tests {
         fs::write(assets.join("logo.svg"), b"fake").unwrap();
 
         let html = r#"<img src="assets/logo.svg" alt="logo">"#;
-        let result = rewrite_image_paths(html, "/", dir.path());
+        let result = rewrite_image_paths(html, "/", dir.path(), &DiskFiles);
         assert_eq!(result, r#"<img src="/assets/logo.svg" alt="logo">"#);
     }
 
@@ -136,10 +144,18 @@ mod tests {
         fs::write(assets.join("b.png"), b"fake").unwrap();
 
         let html = r#"<img src="assets/a.png" alt="a"><img src="assets/b.png" alt="b">"#;
-        let result = rewrite_image_paths(html, "/base/", dir.path());
+        let result = rewrite_image_paths(html, "/base/", dir.path(), &DiskFiles);
         assert_eq!(
             result,
             r#"<img src="/base/assets/a.png" alt="a"><img src="/base/assets/b.png" alt="b">"#
         );
     }
+
+    #[test]
+    fn resolves_against_memory_files() {
+        let files = crate::source::MemoryFiles::new().with("assets/logo.svg", "<svg/>");
+        let html = r#"<img src="logo.svg" alt="logo">"#;
+        let result = rewrite_image_paths(html, "/", Path::new(""), &files);
+        assert_eq!(result, r#"<img src="/assets/logo.svg" alt="logo">"#);
+    }
 }
@@ -0,0 +1,405 @@
+//! Transclusion: a line holding `:::include{file="shared/prereqs.md"}`,
+//! `:::include{page="guides/setup#prerequisites"}` or `![[guides/setup#prerequisites]]`
+//! is replaced with the Markdown it points at, and a `:::snippet{...}` line with
+//! the code it quotes (see [`snippets`](super::snippets)), before any other
+//! stage sees the page.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::cache::Dependency;
+use crate::diagnostics;
+use crate::pipeline::snippets::Snippet;
+use crate::pipeline::{directives, frontmatter};
+use crate::project::PageInventory;
+use crate::source::FileSource;
+use crate::util;
+
+static INCLUDE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*):::include(\{[^}]*\})\s*$").unwrap());
+
+static EMBED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)!\[\[([^\]|]+)\]\]\s*$").unwrap());
+
+static HEADING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*?)(?:\s+\{#([\w-]+)\})?(?:\s+#+)?\s*$").unwrap());
+
+thread_local! {
+    /// When set, files included on this thread are recorded here.
+    static INCLUDED: RefCell<Option<Vec<Dependency>>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, returning the files it included, quoted or linked to by `#anchor`
+/// on the current thread alongside its result, so incremental builds
+/// re-render a page when one of them changes.
+pub fn track<R>(f: impl FnOnce() -> R) -> (R, Vec<Dependency>) {
+    let previous = INCLUDED.with(|i| i.replace(Some(Vec::new())));
+    let result = f();
+    let included = INCLUDED.with(|i| i.replace(previous)).unwrap_or_default();
+    (result, included)
+}
+
+/// Record `path`, read with `contents`, as a file the page being tracked depends on.
+pub fn record(path: &Path, contents: &str) {
+    depend(Dependency::new(path, contents));
+}
+
+/// Record a file the page being tracked depends on.
+pub fn depend(dependency: Dependency) {
+    INCLUDED.with(|i| {
+        if let Some(included) = i.borrow_mut().as_mut()
+            && !included.contains(&dependency)
+        {
+            included.push(dependency);
+        }
+    });
+}
+
+/// Whether `line` is a `![[page]]` embed rather than a wiki-link.
+pub fn is_embed(line: &str) -> bool {
+    EMBED_RE.is_match(line)
+}
+
+/// Where includes are looked up: `file` paths are relative to the project
+/// root, pages are found by slug like wiki-links.
+pub struct Includes<'a> {
+    pub files: &'a dyn FileSource,
+    pub project_root: &'a Path,
+    pub inventory: &'a PageInventory,
+    /// When set, pages are included from this locale only.
+    pub locale: Option<&'a str>,
+}
+
+/// An include as written: what it points at and, after `#`, which section.
+struct Include<'s> {
+    indent: &'s str,
+    /// The target as written, for warnings.
+    target: String,
+    kind: Kind,
+    section: Option<String>,
+}
+
+enum Kind {
+    File(String),
+    Page(String),
+}
+
+impl Includes<'_> {
+    /// Replace the includes in `source`, the contents of `source_file`, with the
+    /// Markdown they point at, recursively. Front matter of included files is
+    /// dropped. Missing targets and include cycles are reported as warnings
+    /// and the include is left out.
+    pub fn expand(&self, source: &str, source_file: &Path) -> String {
+        self.expand_in(source, &mut vec![source_file.to_path_buf()])
+    }
+
+    fn expand_in(&self, source: &str, chain: &mut Vec<PathBuf>) -> String {
+        let mut output = String::with_capacity(source.len());
+        let mut fence: Option<(char, usize)> = None;
+
+        for line in source.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let trimmed = content.trim();
+
+            // Includes inside fenced code blocks are left alone
+            if directives::in_fence(&mut fence, trimmed) {
+                output.push_str(line);
+                continue;
+            }
+
+            if let Some((indent, snippet)) = Snippet::parse(content) {
+                output.push_str(&self.quote(&snippet, indent));
+                continue;
+            }
+            let Some(include) = parse_include(content) else {
+                output.push_str(line);
+                continue;
+            };
+            let Some((path, contents)) = self.read(&include.kind) else {
+                diagnostics::warn_broken_include(&include.target);
+                continue;
+            };
+            if chain.contains(&path) {
+                diagnostics::warn_include_cycle(&include.target);
+                continue;
+            }
+            record(&path, &contents);
+
+            let body = frontmatter::strip(&contents);
+            let body = match &include.section {
+                Some(anchor) => match section(body, anchor) {
+                    Some(section) => section,
+                    None => {
+                        diagnostics::warn_broken_include(&include.target);
+                        continue;
+                    }
+                },
+                None => body,
+            };
+
+            // Warnings from nested includes point at the file they're written in
+            chain.push(path.clone());
+            let expanded = diagnostics::in_file(&path, &contents, || self.expand_in(body, chain));
+            chain.pop();
+
+            // Indent included lines like the include, so it can sit in a list item
+            for included in expanded.lines() {
+                if !included.is_empty() {
+                    output.push_str(include.indent);
+                }
+                output.push_str(included);
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    /// A snippet's code as a fenced code block, or nothing if it can't be found.
+    fn quote(&self, snippet: &Snippet, indent: &str) -> String {
+        if snippet.file.is_empty() {
+            diagnostics::warn_broken_snippet(":::snippet", "no file given");
+            return String::new();
+        }
+        let path = self.resolve(&snippet.file);
+        let Ok(contents) = self.files.read_to_string(&path) else {
+            diagnostics::warn_broken_snippet(&snippet.file, "file not found");
+            return String::new();
+        };
+        record(&path, &contents);
+        match snippet.extract(&contents) {
+            Ok(code) => snippet.code_block(&code, indent),
+            Err(reason) => {
+                diagnostics::warn_broken_snippet(&snippet.file, &reason);
+                String::new()
+            }
+        }
+    }
+
+    /// `relative` joined to the project root, with `.` and `..` resolved, so
+    /// files outside the project (`../src/lib.rs`) can be read too.
+    fn resolve(&self, relative: &str) -> PathBuf {
+        util::normalize_path(&self.project_root.join(relative))
+    }
+
+    /// The path and contents of an include's target, if it exists.
+    fn read(&self, kind: &Kind) -> Option<(PathBuf, String)> {
+        let path = match kind {
+            Kind::File(file) => self.resolve(file),
+            Kind::Page(slug) => {
+                let page = match self.locale {
+                    Some(locale) => self.inventory.resolve_link_in_locale(slug, locale),
+                    None => self.inventory.resolve_link(slug),
+                }?;
+                page.source_path.clone()
+            }
+        };
+        let contents = self.files.read_to_string(&path).ok()?;
+        Some((path, contents))
+    }
+}
+
+fn parse_include(line: &str) -> Option<Include<'_>> {
+    let (caps, is_file, target) = if let Some(caps) = INCLUDE_RE.captures(line) {
+        let attributes = directives::parse_attributes(&caps[2]);
+        match (attributes.get("file"), attributes.get("page")) {
+            (Some(file), _) => (caps, true, file.clone()),
+            (None, Some(page)) => (caps, false, page.clone()),
+            // Nothing to include; reported as broken
+            (None, None) => (caps, true, line.trim().to_string()),
+        }
+    } else {
+        let caps = EMBED_RE.captures(line)?;
+        let target = caps[2].trim().to_string();
+        (caps, false, target)
+    };
+    let indent = caps.get(1).map_or("", |m| m.as_str());
+
+    let (path, section) = match target.split_once('#') {
+        Some((path, section)) => (path.to_string(), Some(section.to_string())),
+        None => (target.clone(), None),
+    };
+    let kind = if is_file {
+        Kind::File(path)
+    } else {
+        Kind::Page(path)
+    };
+    Some(Include {
+        indent,
+        target,
+        kind,
+        section,
+    })
+}
+
+/// The section of `source` under the heading matching `anchor` — by its
+/// `{#id}`, or by its text slugified like heading IDs — up to the next
+/// heading of the same or a higher level. The heading itself is included.
+fn section<'a>(source: &'a str, anchor: &str) -> Option<&'a str> {
+    let wanted = slug::slugify(anchor);
+    let mut start: Option<(usize, usize)> = None;
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        if directives::in_fence(&mut fence, line.trim()) {
+            // Headings inside code blocks don't start or end sections
+        } else if let Some(caps) = HEADING_RE.captures(line.trim_end()) {
+            let level = caps[1].len();
+            match start {
+                Some((begin, start_level)) if level <= start_level => {
+                    return Some(&source[begin..offset]);
+                }
+                None => {
+                    let matches = match caps.get(3) {
+                        Some(id) => id.as_str() == anchor,
+                        None => slug::slugify(&caps[2]) == wanted,
+                    };
+                    if matches {
+                        start = Some((offset, level));
+                    }
+                }
+                _ => {}
+            }
+        }
+        offset += line.len();
+    }
+
+    start.map(|(begin, _)| &source[begin..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MemoryFiles;
+
+    fn expand(files: &MemoryFiles, source: &str) -> (String, Vec<diagnostics::Warning>) {
+        let inventory =
+            PageInventory::scan_files(files, Path::new("/p/docs"), None, None, None).unwrap();
+        let includes = Includes {
+            files,
+            project_root: Path::new("/p"),
+            inventory: &inventory,
+            locale: None,
+        };
+        diagnostics::capture(|| includes.expand(source, Path::new("/p/docs/index.md")))
+    }
+
+    #[test]
+    fn includes_files_and_pages() {
+        let files = MemoryFiles::new()
+            .with("/p/shared/prereqs.md", "---\ntitle: Ignored\n---\nYou need Rust.\n")
+            .with(
+                "/p/docs/guides/setup.md",
+                "# Setup\n\nIntro\n\n## Install\n\nRun it.\n\n### Details\n\nMore.\n\n## Next\n\nDone.\n",
+            );
+        let (output, warnings) = expand(
+            &files,
+            "# Home\n\n:::include{file=\"shared/prereqs.md\"}\n\n![[guides/setup#install]]\n\n- Item\n  :::include{page=\"guides/setup#next\"}\n",
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            output,
+            "# Home\n\nYou need Rust.\n\n## Install\n\nRun it.\n\n### Details\n\nMore.\n\n\n- Item\n  ## Next\n\n  Done.\n"
+        );
+    }
+
+    #[test]
+    fn records_included_files() {
+        let files = MemoryFiles::new()
+            .with("/p/shared/a.md", "A\n")
+            .with("/p/docs/index.md", "# Home\n");
+        let ((_, _), included) = track(|| {
+            expand(
+                &files,
+                ":::include{file=\"shared/a.md\"}\n:::include{file=\"shared/a.md\"}\n",
+            )
+        });
+        assert_eq!(
+            included,
+            vec![Dependency::new(Path::new("/p/shared/a.md"), "A\n")]
+        );
+    }
+
+    #[test]
+    fn missing_targets_are_reported() {
+        let files = MemoryFiles::new().with("/p/docs/faq.md", "# FAQ\n");
+        let (output, warnings) = expand(
+            &files,
+            "Text\n:::include{file=\"shared/missing.md\"}\n![[nowhere]]\n![[faq#no-such-heading]]\n",
+        );
+        assert_eq!(output, "Text\n");
+        let checks: Vec<_> = warnings.iter().map(|w| w.check).collect();
+        assert_eq!(checks, ["broken-include"; 3]);
+        assert_eq!(warnings[0].message, "broken include shared/missing.md");
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let files = MemoryFiles::new()
+            .with("/p/shared/a.md", "A\n:::include{file=\"shared/b.md\"}\n")
+            .with("/p/shared/b.md", "B\n:::include{file=\"shared/a.md\"}\n");
+        let (output, warnings) = expand(&files, ":::include{file=\"shared/a.md\"}\n");
+        assert_eq!(output, "A\nB\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "include-cycle");
+        assert_eq!(
+            warnings[0].file.as_deref(),
+            Some(Path::new("/p/shared/b.md"))
+        );
+        assert_eq!(warnings[0].line, Some(2));
+    }
+
+    #[test]
+    fn quotes_snippets() {
+        let files = MemoryFiles::new().with(
+            "/src/lib.rs",
+            "fn a() {\n    // region: body\n    let x = 1;\n    // endregion\n}\n",
+        );
+        let ((output, warnings), included) = track(|| {
+            expand(
+                &files,
+                "- Step\n  :::snippet{file=\"../src/lib.rs\" region=\"body\" lang=\"rust\"}\n:::snippet{file=\"../src/lib.rs\" region=\"gone\"}\n",
+            )
+        });
+        assert_eq!(output, "- Step\n  ```rust\n  let x = 1;\n  ```\n");
+        assert_eq!(included.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "broken-snippet");
+        assert_eq!(
+            warnings[0].message,
+            "broken snippet ../src/lib.rs: region 'gone' not found"
+        );
+    }
+
+    #[test]
+    fn includes_in_code_are_left_alone() {
+        let files = MemoryFiles::new().with("/p/shared/a.md", "A\n");
+        let source = "```md\n:::include{file=\"shared/a.md\"}\n![[index]]\n```\n";
+        let (output, warnings) = expand(&files, source);
+        assert_eq!(output, source);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn sections_by_custom_id() {
+        let source = "# A\n\n## First {#one}\n\nBody\n\n# B\n";
+        assert_eq!(section(source, "one"), Some("## First {#one}\n\nBody\n\n"));
+        assert_eq!(section(source, "First"), None);
+        assert_eq!(section(source, "b"), Some("# B\n"));
+    }
+
+    #[test]
+    fn sections_skip_headings_in_nested_fences() {
+        let source =
+            "## Setup\n\n````md\n```rust\n# Not a heading\n```\n````\n\nAfter\n\n## Next\n";
+        assert_eq!(
+            section(source, "setup"),
+            Some("## Setup\n\n````md\n```rust\n# Not a heading\n```\n````\n\nAfter\n\n")
+        );
+        assert_eq!(section(source, "not-a-heading"), None);
+    }
+}
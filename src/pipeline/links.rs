@@ -0,0 +1,247 @@
+//! Standard Markdown links to other pages' source files:
+//! `[Setup](../guides/setup.md#install)` points at the built page instead.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::diagnostics;
+use crate::pipeline::backlinks;
+use crate::pipeline::wikilinks::FindAnchor;
+use crate::project::{PageInfo, PageInventory};
+use crate::util;
+
+static HREF_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<a href="([^"]*)""#).unwrap());
+
+/// Post-comrak pass: rewrite links to `.md` files, relative to `source_file`,
+/// to the output path of the page built from that file. Links to files that
+/// aren't pages are left as they are and reported as broken.
+///
+/// When `locale` is provided, a link to another locale's file goes to the
+/// same page in `locale` if there is one. A `#fragment` must be an anchor
+/// `find_anchor` finds on that page, or the link is reported as broken.
+pub fn resolve(
+    html: &str,
+    inventory: &PageInventory,
+    source_file: &Path,
+    base_url: &str,
+    locale: Option<&str>,
+    find_anchor: &FindAnchor,
+) -> String {
+    if !html.contains(".md") {
+        return html.to_string();
+    }
+    HREF_RE
+        .replace_all(html, |caps: &regex::Captures| {
+            let href = &caps[1];
+            let Some((path, fragment)) = split_md_link(href) else {
+                return caps[0].to_string();
+            };
+            match find_page(inventory, source_file, &path, locale) {
+                Some(page) => {
+                    backlinks::record(&page.source_path);
+                    let fragment = match fragment.strip_prefix('#') {
+                        Some(anchor) if !anchor.is_empty() => {
+                            match find_anchor(&page.source_path, &percent_decode(anchor)) {
+                                Some(id) => format!("#{id}"),
+                                None => {
+                                    diagnostics::warn_broken_anchor(source_file, href);
+                                    fragment.to_string()
+                                }
+                            }
+                        }
+                        _ => fragment.to_string(),
+                    };
+                    format!(
+                        "<a href=\"{base_url}{}{fragment}\"",
+                        page.output_path.display()
+                    )
+                }
+                None => {
+                    diagnostics::warn_broken_markdown_link(source_file, &path);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .into_owned()
+}
+
+/// Split a relative link to a `.md` file into its decoded path and its
+/// `#fragment`, if any. Absolute URLs and paths are not page links.
+fn split_md_link(href: &str) -> Option<(String, &str)> {
+    if href.starts_with('/') || href.starts_with('#') || has_scheme(href) {
+        return None;
+    }
+    let end = href.find(['?', '#']).unwrap_or(href.len());
+    let (path, rest) = href.split_at(end);
+    if !path.to_ascii_lowercase().ends_with(".md") {
+        return None;
+    }
+    let fragment = rest.find('#').map_or("", |i| &rest[i..]);
+    Some((percent_decode(path), fragment))
+}
+
+/// Whether `href` starts with a URL scheme such as `https:` or `mailto:`.
+fn has_scheme(href: &str) -> bool {
+    href.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Decode `%XX` escapes, which comrak adds for spaces and other characters.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The page built from the file at `path`, relative to `source_file`.
+fn find_page<'a>(
+    inventory: &'a PageInventory,
+    source_file: &Path,
+    path: &str,
+    locale: Option<&str>,
+) -> Option<&'a PageInfo> {
+    let dir = source_file.parent().unwrap_or(Path::new(""));
+    let target = util::normalize_path(&dir.join(path));
+    let page = inventory.pages.values().find(|p| {
+        p.source_path.file_name() == target.file_name()
+            && util::normalize_path(&p.source_path) == target
+    })?;
+
+    // `setup.md` linked from `index.fr.md` goes to the French setup page
+    match locale {
+        Some(locale) if page.locale.as_deref() != Some(locale) => inventory
+            .pages
+            .get(&format!("{locale}:{}", page.slug))
+            .or(Some(page)),
+        _ => Some(page),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn inventory(locales: Option<&[String]>) -> (tempfile::TempDir, PageInventory) {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("guides")).unwrap();
+        fs::write(docs.join("index.md"), "# Home").unwrap();
+        fs::write(docs.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(docs.join("guides/setup.fr.md"), "# Installation").unwrap();
+        fs::write(docs.join("guides/my page.md"), "# Mine").unwrap();
+        let inv = PageInventory::scan(&docs, locales, Some("en"), None).unwrap();
+        (dir, inv)
+    }
+
+    fn any_anchor(_: &Path, anchor: &str) -> Option<String> {
+        Some(anchor.to_string())
+    }
+
+    #[test]
+    fn rewrites_relative_md_links() {
+        let (dir, inv) = inventory(None);
+        let index = dir.path().join("docs/index.md");
+        let html = "<a href=\"guides/setup.md#install\">Setup</a> <a href=\"./guides/my%20page.md\">Mine</a>";
+        let result = resolve(html, &inv, &index, "/docs/", None, &any_anchor);
+        assert_eq!(
+            result,
+            "<a href=\"/docs/guides/setup.html#install\">Setup</a> <a href=\"/docs/guides/my page.html\">Mine</a>"
+        );
+
+        let setup = dir.path().join("docs/guides/setup.md");
+        let result = resolve(
+            "<a href=\"../index.md\">Home</a>",
+            &inv,
+            &setup,
+            "/",
+            None,
+            &any_anchor,
+        );
+        assert_eq!(result, "<a href=\"/index.html\">Home</a>");
+    }
+
+    #[test]
+    fn leaves_other_links() {
+        let (dir, inv) = inventory(None);
+        let index = dir.path().join("docs/index.md");
+        let html = "<a href=\"https://example.com/README.md\">a</a> <a href=\"/raw/notes.md\">b</a> \
+                    <a href=\"#setup.md\">c</a> <a href=\"guide.html\">d</a>";
+        let (result, warnings) =
+            diagnostics::capture(|| resolve(html, &inv, &index, "/", None, &any_anchor));
+        assert_eq!(result, html);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn broken_links_warn() {
+        let (dir, inv) = inventory(None);
+        let index = dir.path().join("docs/index.md");
+        let html = "<a href=\"guides/missing.md\">Missing</a>";
+        let (result, warnings) =
+            diagnostics::capture(|| resolve(html, &inv, &index, "/", None, &any_anchor));
+        assert_eq!(result, html);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "broken-markdown-link");
+    }
+
+    #[test]
+    fn checks_anchors() {
+        let (dir, inv) = inventory(None);
+        let index = dir.path().join("docs/index.md");
+        let find = |page: &Path, anchor: &str| {
+            let id = slug::slugify(anchor);
+            (page.ends_with("setup.md") && id == "first-steps").then_some(id)
+        };
+        let html = "<a href=\"guides/setup.md#First%20Steps\">a</a> <a href=\"guides/setup.md#gone\">b</a>";
+        let (result, warnings) =
+            diagnostics::capture(|| resolve(html, &inv, &index, "/", None, &find));
+        assert_eq!(
+            result,
+            "<a href=\"/guides/setup.html#first-steps\">a</a> <a href=\"/guides/setup.html#gone\">b</a>"
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "broken-anchor");
+    }
+
+    #[test]
+    fn stays_in_locale() {
+        let locales = ["en".to_string(), "fr".to_string()];
+        let (dir, inv) = inventory(Some(&locales));
+        let index = dir.path().join("docs/index.md");
+        let html = "<a href=\"guides/setup.md\">Setup</a>";
+        assert_eq!(
+            resolve(html, &inv, &index, "/", Some("fr"), &any_anchor),
+            "<a href=\"/fr/guides/setup.html\">Setup</a>"
+        );
+        // Falls back to the linked file's own locale
+        let html = "<a href=\"index.md\">Home</a>";
+        assert_eq!(
+            resolve(html, &inv, &index, "/", Some("fr"), &any_anchor),
+            "<a href=\"/en/index.html\">Home</a>"
+        );
+    }
+}
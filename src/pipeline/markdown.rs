@@ -19,7 +19,11 @@ pub fn comrak_options() -> Options<'static> {
 
 /// Render Markdown source to HTML using comrak with GFM extensions.
 pub fn render(source: &str) -> String {
-    let options = comrak_options();
+    let mut options = comrak_options();
+    // TOML front matter uses `+++` delimiters
+    if source.starts_with("+++") {
+        options.extension.front_matter_delimiter = Some("+++".to_string());
+    }
     markdown_to_html(source, &options)
 }
 
@@ -77,6 +81,14 @@ mod tests {
         assert!(html.contains("Content here"));
     }
 
+    #[test]
+    fn toml_front_matter_stripped() {
+        let md = "+++\ntitle = \"Test\"\n+++\n\nContent here";
+        let html = render(md);
+        assert!(!html.contains("title ="));
+        assert!(html.contains("Content here"));
+    }
+
     #[test]
     fn superscript() {
         let html = render("X^2^");
@@ -1,40 +1,81 @@
+pub mod anchors;
 pub mod attributes;
+pub mod backlinks;
+pub mod conditions;
 pub mod directives;
 pub mod frontmatter;
 pub mod headings;
 pub mod images;
+pub mod includes;
+pub mod links;
 pub mod markdown;
 pub mod popovers;
+pub mod snippets;
 pub mod syntax;
+pub mod variables;
 pub mod wikilinks;
 
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
 
 use crate::components::ComponentRegistry;
+use crate::diagnostics;
 use crate::error::Result;
+use crate::plugins::Plugins;
 use crate::project::PageInventory;
+use crate::source::FileSource;
 
+use self::anchors::Anchors;
 use self::syntax::SyntaxHighlighter;
 
-/// Full pipeline: directives → popovers → markdown → syntax highlight → wiki-links → attributes → heading IDs.
-/// When `locale` is provided, wiki-links resolve within that locale only.
-#[allow(clippy::too_many_arguments)]
-pub fn process(
-    source: &str,
-    inventory: &PageInventory,
-    source_file: &Path,
-    registry: &ComponentRegistry,
-    base_url: &str,
-    highlighter: Option<&SyntaxHighlighter>,
-    project_root: &Path,
-    locale: Option<&str>,
-) -> Result<String> {
-    // 1. Pre-comrak: process block directives (:::name{attrs} ... :::)
-    let source = directives::process_directives(source, &mut |block| registry.render_block(block));
-
-    // 1b. Pre-comrak: process inline directives (:::name{attrs} with no body)
-    let source =
-        directives::process_inline_directives(&source, &mut |block| registry.render_block(block));
+/// Everything the pipeline needs besides the page source.
+pub struct PipelineContext<'a> {
+    pub inventory: &'a PageInventory,
+    pub registry: &'a ComponentRegistry,
+    pub base_url: &'a str,
+    pub highlighter: Option<&'a SyntaxHighlighter>,
+    pub project_root: &'a Path,
+    /// Where image paths are checked for existence and included files are read.
+    pub files: &'a dyn FileSource,
+    /// When set, wiki-links resolve within this locale only.
+    pub locale: Option<&'a str>,
+    /// The version being built, for `:::only` blocks.
+    pub version: Option<&'a str>,
+    /// The build profile, for `:::only` blocks.
+    pub profile: Option<&'a str>,
+    /// `[[plugins]]` whose Markdown and HTML hooks run before and after the stages below.
+    pub plugins: &'a Plugins,
+    /// `[variables]` for this page's version and locale.
+    pub variables: &'a HashMap<String, String>,
+    /// Element IDs of the pages links point at, shared by every page of a
+    /// version and locale.
+    pub anchors: &'a Anchors,
+}
+
+/// Full pipeline: plugin Markdown hooks → includes and snippets → conditional blocks → variables → directives → popovers → markdown → syntax highlight → wiki-links and `.md` links → attributes → heading IDs → images → plugin HTML hooks.
+///
+/// Warnings raised along the way point at `source_file`.
+pub fn process(source: &str, source_file: &Path, ctx: &PipelineContext) -> Result<String> {
+    diagnostics::in_file(source_file, source, || run_stages(source, source_file, ctx))
+}
+
+fn run_stages(source: &str, source_file: &Path, ctx: &PipelineContext) -> Result<String> {
+    let registry = ctx.registry;
+
+    // 0. Let plugins transform the raw Markdown
+    let source = ctx
+        .plugins
+        .transform_markdown(source, source_file, ctx.locale)?;
+
+    // 0b–0d. Includes, conditional blocks and variables
+    let source = expand(&source, source_file, ctx);
+    let source = source.as_str();
+
+    // 1–1b. Pre-comrak: process block and inline directives
+    let source = render_directives(source, registry)?;
 
     // 2. Pre-comrak: convert ^[content] to popover spans
     let source = popovers::process_popovers(&source);
@@ -46,13 +87,31 @@ pub fn process(
     let html = markdown::render(&source);
 
     // 4. Syntax-highlight code blocks (if enabled)
-    let html = match highlighter {
+    let html = match ctx.highlighter {
         Some(h) => syntax::highlight_code_blocks(&html, h),
         None => html,
     };
 
-    // 5. Resolve wiki-links
-    let html = wikilinks::resolve(&html, inventory, source_file, base_url, locale);
+    // 5. Resolve wiki-links, checking #anchors against the target page
+    let find_anchor = |page: &Path, anchor: &str| ctx.anchors.find(page, anchor, ctx);
+    let html = wikilinks::resolve(
+        &html,
+        ctx.inventory,
+        source_file,
+        ctx.base_url,
+        ctx.locale,
+        &find_anchor,
+    );
+
+    // 5b. Point Markdown links to .md files at the pages built from them
+    let html = links::resolve(
+        &html,
+        ctx.inventory,
+        source_file,
+        ctx.base_url,
+        ctx.locale,
+        &find_anchor,
+    );
 
     // 6. Post-comrak: inject inline attributes ({.class})
     let html = attributes::inject_attributes(&html);
@@ -61,7 +120,109 @@ pub fn process(
     let html = headings::inject_heading_ids(&html);
 
     // 8. Rewrite relative image paths with base_url
-    let html = images::rewrite_image_paths(&html, base_url, project_root);
+    let html = images::rewrite_image_paths(&html, ctx.base_url, ctx.project_root, ctx.files);
+
+    // 9. Let plugins transform the finished HTML
+    ctx.plugins.transform_html(&html, source_file, ctx.locale)
+}
+
+/// Stages 1–1b: render block directives (`:::name{attrs} ... :::`), then
+/// inline ones (`:::name{attrs}` with no body). The first component to fail
+/// fails the page.
+fn render_directives(source: &str, registry: &ComponentRegistry) -> Result<String> {
+    let mut failure = None;
+    let mut render = |block: &directives::DirectiveBlock| {
+        registry.render_block(block).unwrap_or_else(|e| {
+            failure.get_or_insert(e);
+            String::new()
+        })
+    };
+    let source = directives::process_directives(source, &mut render);
+    let source = directives::process_inline_directives(&source, &mut render);
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(source),
+    }
+}
+
+/// Stages 0b–0d: inline includes, page sections and code snippets, keep the
+/// `:::only` blocks meant for this version, locale and profile, and substitute
+/// `{{ variable }}` placeholders.
+pub fn expand(source: &str, source_file: &Path, ctx: &PipelineContext) -> String {
+    let source = includes::Includes {
+        files: ctx.files,
+        project_root: ctx.project_root,
+        inventory: ctx.inventory,
+        locale: ctx.locale,
+    }
+    .expand(source, source_file);
 
-    Ok(html)
+    let source = conditions::Conditions {
+        version: ctx.version,
+        locale: ctx.locale,
+        profile: ctx.profile,
+    }
+    .apply(&source);
+
+    variables::substitute(&source, ctx.variables)
+}
+
+/// The element IDs the page built from `source` will have — heading IDs,
+/// `{#id}` attributes and IDs in the output of components and plugin hooks —
+/// for checking links to `#anchors` on it.
+///
+/// Runs only the stages that affect IDs, so it's cheaper than [`process`]
+/// and never follows the page's own links. A stage that fails is skipped;
+/// the page's own render reports the failure.
+pub fn element_ids(source: &str, source_file: &Path, ctx: &PipelineContext) -> HashSet<String> {
+    static ID_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\bid="([^"]+)""#).unwrap());
+
+    let source = ctx
+        .plugins
+        .transform_markdown(source, source_file, ctx.locale)
+        .unwrap_or_else(|_| source.to_string());
+    let source = expand(&source, source_file, ctx);
+    let source = render_directives(&source, ctx.registry).unwrap_or(source);
+    let source = headings::extract_custom_heading_ids(&source);
+    let html = markdown::render(&source);
+    let html = attributes::inject_attributes(&html);
+    let html = headings::inject_heading_ids(&html);
+    let html = ctx
+        .plugins
+        .transform_html(&html, source_file, ctx.locale)
+        .unwrap_or(html);
+    ID_RE
+        .captures_iter(&html)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// The source files of the pages `source` links to with wiki-links and `.md`
+/// links, for [`backlinks`].
+///
+/// Like [`element_ids`], runs only the stages links pass through, and doesn't
+/// check their anchors.
+pub fn linked_pages(source: &str, source_file: &Path, ctx: &PipelineContext) -> Vec<PathBuf> {
+    let source = expand(source, source_file, ctx);
+    let html = markdown::render(&source);
+    let any_anchor = |_: &Path, anchor: &str| Some(anchor.to_string());
+    let (_, linked) = backlinks::track(|| {
+        let html = wikilinks::resolve(
+            &html,
+            ctx.inventory,
+            source_file,
+            ctx.base_url,
+            ctx.locale,
+            &any_anchor,
+        );
+        links::resolve(
+            &html,
+            ctx.inventory,
+            source_file,
+            ctx.base_url,
+            ctx.locale,
+            &any_anchor,
+        )
+    });
+    linked
 }
@@ -1,21 +1,34 @@
 use regex::Regex;
+use std::cell::Cell;
 use std::sync::LazyLock;
-use std::sync::atomic::{AtomicUsize, Ordering};
 
 static POPOVER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\^\[([^\]]+)\]").unwrap());
 
-static POPOVER_ID: AtomicUsize = AtomicUsize::new(0);
+thread_local! {
+    /// Per-thread so pages rendered in parallel get the same IDs as a serial build.
+    static POPOVER_ID: Cell<usize> = const { Cell::new(0) };
+}
 
 use crate::util::html_escape;
 
-/// Reset the popover ID counter to zero (call between builds).
+/// Reset the popover ID counter to zero on the current thread.
+///
+/// The build calls this before each page, so IDs are unique within a page and
+/// independent of render order. The PDF export renders every page into one
+/// document without resetting, so IDs stay unique across it.
 pub fn reset_popover_ids() {
-    POPOVER_ID.store(0, Ordering::Relaxed);
+    POPOVER_ID.set(0);
 }
 
 /// Pre-comrak pass: convert `^[content]` to inline popover HTML spans.
 /// Skips fenced code blocks (``` lines) and inline code spans (backtick-wrapped).
 pub fn process_popovers(source: &str) -> String {
+    replace_outside_code(source, replace_popovers_in_text)
+}
+
+/// Apply `replace` to the text of `source` outside fenced code blocks and
+/// inline code spans, which are copied as they are.
+pub fn replace_outside_code(source: &str, mut replace: impl FnMut(&str) -> String) -> String {
     let mut output = String::with_capacity(source.len());
     let mut in_code_block = false;
 
@@ -36,7 +49,7 @@ pub fn process_popovers(source: &str) -> String {
             continue;
         }
 
-        output.push_str(&replace_popovers_in_line(line));
+        output.push_str(&replace_in_line(line, &mut replace));
     }
 
     // Preserve trailing newline if the source had one
@@ -47,8 +60,8 @@ pub fn process_popovers(source: &str) -> String {
     output
 }
 
-/// Replace `^[content]` in a single line, skipping anything inside backtick spans.
-fn replace_popovers_in_line(line: &str) -> String {
+/// Apply `replace` to a single line, skipping anything inside backtick spans.
+fn replace_in_line(line: &str, replace: &mut impl FnMut(&str) -> String) -> String {
     // Split the line into segments: inside backticks (skip) and outside (process).
     let mut result = String::with_capacity(line.len());
     let mut remaining = line;
@@ -58,7 +71,7 @@ fn replace_popovers_in_line(line: &str) -> String {
         if let Some(tick_pos) = remaining.find('`') {
             // Process the part before the backtick
             let before = &remaining[..tick_pos];
-            result.push_str(&replace_popovers_in_text(before));
+            result.push_str(&replace(before));
 
             // Find the closing backtick
             let after_tick = &remaining[tick_pos + 1..];
@@ -73,7 +86,7 @@ fn replace_popovers_in_line(line: &str) -> String {
             }
         } else {
             // No backticks left — process the remainder
-            result.push_str(&replace_popovers_in_text(remaining));
+            result.push_str(&replace(remaining));
             return result;
         }
     }
@@ -86,7 +99,7 @@ fn replace_popovers_in_text(text: &str) -> String {
     POPOVER_RE
         .replace_all(text, |caps: &regex::Captures| {
             let content = html_escape(&caps[1]);
-            let id = POPOVER_ID.fetch_add(1, Ordering::Relaxed);
+            let id = POPOVER_ID.replace(POPOVER_ID.get() + 1);
             format!(
                 "<span class=\"popover-trigger\" tabindex=\"0\" aria-describedby=\"popover-{id}\">\
                  <span class=\"popover-indicator\"></span>\
@@ -164,6 +177,16 @@ mod tests {
         assert!(id_count >= 4);
     }
 
+    #[test]
+    fn reset_restarts_ids() {
+        reset_popover_ids();
+        let first = process_popovers("A^[one]");
+        reset_popover_ids();
+        let second = process_popovers("A^[one]");
+        assert_eq!(first, second);
+        assert!(first.contains("id=\"popover-0\""));
+    }
+
     #[test]
     fn preserves_trailing_newline() {
         let input = "hello^[world]\n";
@@ -0,0 +1,270 @@
+//! Code snippets: a line holding
+//! `:::snippet{file="../src/lib.rs" region="setup" lang="rust"}` quotes part of
+//! a source file as a fenced code block, so the docs show the code as it is in
+//! the repository. Snippets are expanded alongside includes (see
+//! [`Includes`](super::includes::Includes)) and highlighted like any other code block.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::pipeline::directives;
+
+static SNIPPET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*):::snippet(\{[^}]*\})\s*$").unwrap());
+
+/// `// region: name` / `// endregion` markers, after any common line or block
+/// comment opener. `#region name` (C#, VS Code) works too.
+static REGION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?://+|#|--|;+|/\*+|<!--|\{/\*)\s*#?(end)?region\b:?\s*([\w.-]*)").unwrap()
+});
+
+/// A `:::snippet` as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Path of the source file, relative to the project root.
+    pub file: String,
+    /// Quote only the lines between this region's markers.
+    pub region: Option<String>,
+    /// Quote only these lines (`"12-20"`, `"12-"` or `"12"`), counted within
+    /// the region if there is one.
+    pub lines: Option<String>,
+    /// Language of the code block. Defaults to the file's extension.
+    pub lang: Option<String>,
+}
+
+impl Snippet {
+    /// Parse a `:::snippet{...}` line, returning its indentation and the snippet.
+    pub fn parse(line: &str) -> Option<(&str, Snippet)> {
+        let caps = SNIPPET_RE.captures(line)?;
+        let mut attributes = directives::parse_attributes(&caps[2]);
+        let snippet = Snippet {
+            file: attributes.remove("file").unwrap_or_default(),
+            region: attributes.remove("region"),
+            lines: attributes.remove("lines"),
+            lang: attributes.remove("lang"),
+        };
+        Some((caps.get(1).map_or("", |m| m.as_str()), snippet))
+    }
+
+    /// The quoted code, dedented and without region markers, or why it can't
+    /// be found in `contents`.
+    pub fn extract(&self, contents: &str) -> Result<String, String> {
+        let mut lines: Vec<&str> = match &self.region {
+            Some(region) => region_lines(contents, region)?,
+            None => contents.lines().collect(),
+        };
+        if let Some(range) = &self.lines {
+            let (start, end) = parse_range(range)?;
+            let end = end.unwrap_or(lines.len());
+            if start > end || end > lines.len() {
+                return Err(format!(
+                    "lines {range} are out of range ({} lines)",
+                    lines.len()
+                ));
+            }
+            lines = lines[start - 1..end].to_vec();
+        }
+        lines.retain(|line| !REGION_RE.is_match(line));
+
+        // Drop surrounding blank lines
+        while lines.first().is_some_and(|l| l.trim().is_empty()) {
+            lines.remove(0);
+        }
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        Ok(dedent(&lines))
+    }
+
+    /// `code` as a fenced code block, with every line indented by `indent`.
+    pub fn code_block(&self, code: &str, indent: &str) -> String {
+        let lang = self.lang.clone().unwrap_or_else(|| {
+            Path::new(&self.file)
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        // The fence must be longer than any run of backticks in the code
+        let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+
+        let mut block = format!("{indent}{fence}{lang}\n");
+        for line in code.lines() {
+            if !line.is_empty() {
+                block.push_str(indent);
+            }
+            block.push_str(line);
+            block.push('\n');
+        }
+        block.push_str(&format!("{indent}{fence}\n"));
+        block
+    }
+}
+
+/// The lines between `region: name` and its `endregion`, skipping the
+/// markers of regions nested inside it.
+fn region_lines<'a>(contents: &'a str, name: &str) -> Result<Vec<&'a str>, String> {
+    let mut found: Option<Vec<&str>> = None;
+    let mut depth = 0;
+
+    for line in contents.lines() {
+        let marker = REGION_RE
+            .captures(line)
+            .map(|caps| (caps.get(1).is_some(), caps[2].to_string()));
+        let Some(lines) = &mut found else {
+            if marker.is_some_and(|(end, region)| !end && region == name) {
+                found = Some(Vec::new());
+            }
+            continue;
+        };
+        match marker {
+            Some((false, _)) => depth += 1,
+            Some((true, _)) if depth == 0 => return Ok(std::mem::take(lines)),
+            Some((true, _)) => depth -= 1,
+            None => lines.push(line),
+        }
+    }
+
+    match found {
+        Some(_) => Err(format!("region '{name}' is never closed")),
+        None => Err(format!("region '{name}' not found")),
+    }
+}
+
+/// Parse a 1-based, inclusive line range: `"12-20"`, `"12-"` (to the end) or `"12"`.
+fn parse_range(range: &str) -> Result<(usize, Option<usize>), String> {
+    let invalid = || format!("invalid line range '{range}'");
+    let number = |s: &str| s.trim().parse::<usize>().ok().filter(|&n| n > 0);
+    match range.split_once('-') {
+        Some((start, "")) => Ok((number(start).ok_or_else(invalid)?, None)),
+        Some((start, end)) => Ok((
+            number(start).ok_or_else(invalid)?,
+            Some(number(end).ok_or_else(invalid)?),
+        )),
+        None => {
+            let line = number(range).ok_or_else(invalid)?;
+            Ok((line, Some(line)))
+        }
+    }
+}
+
+/// Remove the indentation every non-blank line shares.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut code = String::new();
+    for line in lines {
+        code.push_str(line.get(indent..).unwrap_or("").trim_end());
+        code.push('\n');
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+use std::io;
+
+fn main() {
+    // region: setup
+    let config = Config::load()?;
+    // region: inner
+    config.validate()?;
+    // endregion
+    // endregion: setup
+    run(config);
+}
+";
+
+    fn snippet(attrs: &str) -> Snippet {
+        Snippet::parse(&format!(":::snippet{{{attrs}}}")).unwrap().1
+    }
+
+    #[test]
+    fn parses_attributes() {
+        let (indent, s) =
+            Snippet::parse("  :::snippet{file=\"../src/lib.rs\" region=\"setup\" lang=\"rust\"}")
+                .unwrap();
+        assert_eq!(indent, "  ");
+        assert_eq!(s.file, "../src/lib.rs");
+        assert_eq!(s.region.as_deref(), Some("setup"));
+        assert_eq!(s.lang.as_deref(), Some("rust"));
+        assert!(Snippet::parse("Text :::snippet{file=\"a.rs\"}").is_none());
+    }
+
+    #[test]
+    fn extracts_regions() {
+        let code = snippet("file=\"main.rs\" region=\"setup\"")
+            .extract(SOURCE)
+            .unwrap();
+        assert_eq!(code, "let config = Config::load()?;\nconfig.validate()?;\n");
+
+        let code = snippet("file=\"main.rs\" region=\"inner\"")
+            .extract(SOURCE)
+            .unwrap();
+        assert_eq!(code, "config.validate()?;\n");
+
+        let err = snippet("file=\"main.rs\" region=\"missing\"").extract(SOURCE);
+        assert_eq!(err, Err("region 'missing' not found".to_string()));
+        let err = snippet("file=\"main.rs\" region=\"open\"").extract("// region: open\nx\n");
+        assert_eq!(err, Err("region 'open' is never closed".to_string()));
+    }
+
+    #[test]
+    fn extracts_line_ranges() {
+        let code = snippet("file=\"main.rs\" lines=\"3-4\"")
+            .extract(SOURCE)
+            .unwrap();
+        // Region markers are dropped and the rest dedented
+        assert_eq!(code, "fn main() {\n");
+        assert_eq!(
+            snippet("file=\"main.rs\" lines=\"10-\"").extract(SOURCE),
+            Ok("    run(config);\n}\n".to_string())
+        );
+        assert_eq!(
+            snippet("file=\"main.rs\" region=\"setup\" lines=\"1\"").extract(SOURCE),
+            Ok("let config = Config::load()?;\n".to_string())
+        );
+        assert_eq!(
+            snippet("file=\"main.rs\" lines=\"20-30\"").extract(SOURCE),
+            Err("lines 20-30 are out of range (11 lines)".to_string())
+        );
+        assert_eq!(
+            snippet("file=\"main.rs\" lines=\"x\"").extract(SOURCE),
+            Err("invalid line range 'x'".to_string())
+        );
+    }
+
+    #[test]
+    fn other_comment_styles() {
+        let source = "# region: py\nimport os\n# endregion\n<!-- #region html -->\n<p>Hi</p>\n<!-- #endregion -->\n";
+        assert_eq!(
+            snippet("file=\"a.py\" region=\"py\"").extract(source),
+            Ok("import os\n".to_string())
+        );
+        assert_eq!(
+            snippet("file=\"a.html\" region=\"html\"").extract(source),
+            Ok("<p>Hi</p>\n".to_string())
+        );
+    }
+
+    #[test]
+    fn code_blocks() {
+        let s = snippet("file=\"../src/lib.rs\"");
+        assert_eq!(s.code_block("fn a() {}\n", ""), "```rs\nfn a() {}\n```\n");
+
+        let s = snippet("file=\"README.md\" lang=\"markdown\"");
+        assert_eq!(
+            s.code_block("```sh\nls\n\n```\n", "  "),
+            "  ````markdown\n  ```sh\n  ls\n\n  ```\n  ````\n"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Site-wide variables: `{{ product_name }}` in a page is replaced with the
+//! matching value from `[variables]` in `docanvil.toml`, after any version or
+//! locale overrides (see [`Config::variables_for`](crate::config::Config::variables_for)).
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::diagnostics;
+use crate::pipeline::popovers;
+
+static VARIABLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][\w-]*)\s*\}\}").unwrap());
+
+/// Pre-comrak pass: replace `{{ name }}` placeholders with their values.
+///
+/// Placeholders in fenced code blocks and inline code spans are left as they
+/// are, as are undefined ones, which are reported as warnings.
+pub fn substitute(source: &str, variables: &HashMap<String, String>) -> String {
+    if !source.contains("{{") {
+        return source.to_string();
+    }
+    popovers::replace_outside_code(source, |text| {
+        VARIABLE_RE
+            .replace_all(text, |caps: &regex::Captures| {
+                match variables.get(&caps[1]) {
+                    Some(value) => value.clone(),
+                    None => {
+                        diagnostics::warn_undefined_variable(&caps[1], &caps[0]);
+                        caps[0].to_string()
+                    }
+                }
+            })
+            .into_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<String, String> {
+        HashMap::from([
+            ("product_name".to_string(), "Anvil".to_string()),
+            ("release".to_string(), "2.0".to_string()),
+        ])
+    }
+
+    #[test]
+    fn replaces_placeholders() {
+        let result = substitute(
+            "# {{ product_name }} {{release}}\n\nUse {{ product_name }}.\n",
+            &vars(),
+        );
+        assert_eq!(result, "# Anvil 2.0\n\nUse Anvil.\n");
+    }
+
+    #[test]
+    fn skips_code() {
+        let input = "`{{ product_name }}` and {{ product_name }}\n```\n{{ release }}\n```\n";
+        assert_eq!(
+            substitute(input, &vars()),
+            "`{{ product_name }}` and Anvil\n```\n{{ release }}\n```\n"
+        );
+    }
+
+    #[test]
+    fn leaves_undefined_and_template_expressions() {
+        let (result, warnings) =
+            diagnostics::capture(|| substitute("{{ missing }} {{ page.meta.owner }}", &vars()));
+        assert_eq!(result, "{{ missing }} {{ page.meta.owner }}");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].check, "undefined-variable");
+    }
+}
@@ -1,17 +1,26 @@
 use std::path::Path;
 
 use crate::diagnostics;
+use crate::pipeline::backlinks;
 use crate::project::PageInventory;
 
+/// Finds the ID an `#anchor` points at on the page built from a source file.
+pub type FindAnchor<'a> = dyn Fn(&Path, &str) -> Option<String> + 'a;
+
 /// Process wiki-links in rendered HTML.
 /// Replaces `[[target]]` and `[[target|display text]]` with proper HTML links.
 /// When `locale` is provided, links resolve within that locale only.
+///
+/// `[[target#anchor]]` links to an element on the target page and
+/// `[[#anchor]]` to one on this page; anchors `find_anchor` can't find are
+/// reported as broken.
 pub fn resolve(
     html: &str,
     inventory: &PageInventory,
     source_file: &Path,
     base_url: &str,
     locale: Option<&str>,
+    find_anchor: &FindAnchor,
 ) -> String {
     let mut result = String::with_capacity(html.len());
     let mut remaining = html;
@@ -30,13 +39,37 @@ pub fn resolve(
 
             let target = target.trim();
             let display = display.trim();
+            let (slug, anchor) = match target.split_once('#') {
+                Some((slug, anchor)) => (slug.trim(), Some(anchor.trim())),
+                None => (target, None),
+            };
+            let fragment = |page: &Path| match anchor {
+                Some(anchor) if !anchor.is_empty() => {
+                    let id = find_anchor(page, anchor).unwrap_or_else(|| {
+                        diagnostics::warn_broken_anchor(source_file, target);
+                        anchor.to_string()
+                    });
+                    format!("#{id}")
+                }
+                _ => String::new(),
+            };
 
             let resolved = match locale {
-                Some(l) => inventory.resolve_link_in_locale(target, l),
-                None => inventory.resolve_link(target),
+                _ if slug.is_empty() => None,
+                Some(l) => inventory.resolve_link_in_locale(slug, l),
+                None => inventory.resolve_link(slug),
             };
-            if let Some(page) = resolved {
-                let href = format!("{}{}", base_url, page.output_path.display());
+            if slug.is_empty() {
+                let href = fragment(source_file);
+                result.push_str(&format!("<a href=\"{href}\">{display}</a>"));
+            } else if let Some(page) = resolved {
+                backlinks::record(&page.source_path);
+                let href = format!(
+                    "{}{}{}",
+                    base_url,
+                    page.output_path.display(),
+                    fragment(&page.source_path)
+                );
                 result.push_str(&format!("<a href=\"{href}\">{display}</a>"));
             } else {
                 diagnostics::warn_broken_link(source_file, target);
@@ -68,6 +101,10 @@ mod tests {
     use crate::project::PageInventory;
     use std::fs;
 
+    fn no_anchors(_: &Path, _: &str) -> Option<String> {
+        None
+    }
+
     fn test_inventory() -> (tempfile::TempDir, PageInventory) {
         let dir = tempfile::tempdir().unwrap();
         let docs = dir.path().join("docs");
@@ -82,7 +119,7 @@ mod tests {
     fn resolve_simple_link() {
         let (_dir, inv) = test_inventory();
         let html = "<p>See [[setup]] for details.</p>";
-        let result = resolve(html, &inv, Path::new("test.md"), "/", None);
+        let result = resolve(html, &inv, Path::new("test.md"), "/", None, &no_anchors);
         assert!(result.contains("<a href=\"/setup.html\">setup</a>"));
     }
 
@@ -90,7 +127,7 @@ mod tests {
     fn resolve_link_with_display_text() {
         let (_dir, inv) = test_inventory();
         let html = "<p>See [[setup|the setup guide]] for details.</p>";
-        let result = resolve(html, &inv, Path::new("test.md"), "/", None);
+        let result = resolve(html, &inv, Path::new("test.md"), "/", None, &no_anchors);
         assert!(result.contains("<a href=\"/setup.html\">the setup guide</a>"));
     }
 
@@ -98,18 +135,35 @@ mod tests {
     fn broken_link_gets_class() {
         let (_dir, inv) = test_inventory();
         let html = "<p>See [[nonexistent]] page.</p>";
-        let result = resolve(html, &inv, Path::new("test.md"), "/", None);
+        let result = resolve(html, &inv, Path::new("test.md"), "/", None, &no_anchors);
         assert!(result.contains("class=\"broken-link popover-trigger\""));
         assert!(result.contains("popover-error"));
         assert!(result.contains("<code>nonexistent</code>"));
         assert!(result.contains("Page not found"));
     }
 
+    #[test]
+    fn anchors() {
+        let (_dir, inv) = test_inventory();
+        let find = |page: &Path, anchor: &str| {
+            let id = slug::slugify(anchor);
+            (page.ends_with("setup.md") && id == "install").then_some(id)
+        };
+        let html = "<p>[[setup#Install]] [[setup#missing|gone]] [[#top]]</p>";
+        let (result, warnings) =
+            diagnostics::capture(|| resolve(html, &inv, Path::new("index.md"), "/", None, &find));
+        assert!(result.contains("<a href=\"/setup.html#install\">setup#Install</a>"));
+        assert!(result.contains("<a href=\"/setup.html#missing\">gone</a>"));
+        assert!(result.contains("<a href=\"#top\">#top</a>"));
+        let checks: Vec<_> = warnings.iter().map(|w| w.check).collect();
+        assert_eq!(checks, ["broken-anchor", "broken-anchor"]);
+    }
+
     #[test]
     fn unclosed_brackets_preserved() {
         let (_dir, inv) = test_inventory();
         let html = "<p>This [[ is unclosed.</p>";
-        let result = resolve(html, &inv, Path::new("test.md"), "/", None);
+        let result = resolve(html, &inv, Path::new("test.md"), "/", None, &no_anchors);
         assert!(result.contains("[["));
     }
 }
@@ -0,0 +1,306 @@
+//! External plugins declared in `[[plugins]]` sections of `docanvil.toml`.
+//!
+//! A plugin is any executable that reads JSON requests from stdin and writes
+//! JSON responses to stdout, one object per line. It is started the first time
+//! it is needed and runs until the build finishes. Every request has a `hook`
+//! field:
+//!
+//! | `hook` | Sent | Other fields | Response `content` |
+//! |--------|------|--------------|--------------------|
+//! | `markdown` | per page, before any other processing | `file`, `locale`, `content` | transformed Markdown |
+//! | `html` | per page, after rendering the body | `file`, `locale`, `content` | transformed HTML |
+//! | `component` | per `:::name` directive the plugin registered | `name`, `attributes`, `body_raw`, `body_html` | the component's HTML |
+//! | `post-build` | once, after the site is written to disk | `output_dir` | ignored |
+//!
+//! A response may also carry `warnings` (reported as build warnings) or an
+//! `error` (fails the build). Omitting `content` from a transform leaves the
+//! page unchanged.
+
+mod process;
+pub mod wasm;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::components::{Component, ComponentContext, ComponentRegistry};
+use crate::config::{PluginConfig, PluginHook};
+use crate::diagnostics;
+use crate::error::{Error, Result};
+
+pub use self::process::{ProcessPlugin, Request, Response};
+
+/// The plugins configured for a build.
+#[derive(Default)]
+pub struct Plugins {
+    plugins: Vec<Arc<ProcessPlugin>>,
+}
+
+impl Plugins {
+    pub fn new(configs: &[PluginConfig], project_root: &Path) -> Self {
+        Self {
+            plugins: configs
+                .iter()
+                .map(|config| Arc::new(ProcessPlugin::new(config.clone(), project_root)))
+                .collect(),
+        }
+    }
+
+    /// Register a component for every directive name a plugin handles.
+    pub fn register_components(&self, registry: &mut ComponentRegistry) {
+        for plugin in &self.plugins {
+            for name in &plugin.config.components {
+                registry.register(Box::new(PluginComponent {
+                    name: name.clone(),
+                    plugin: Arc::clone(plugin),
+                }));
+            }
+        }
+    }
+
+    /// Pass page Markdown through every plugin with the `markdown` hook, in order.
+    pub fn transform_markdown(
+        &self,
+        source: &str,
+        file: &Path,
+        locale: Option<&str>,
+    ) -> Result<String> {
+        self.transform(PluginHook::Markdown, source, file, locale)
+    }
+
+    /// Pass a page's HTML body through every plugin with the `html` hook, in order.
+    pub fn transform_html(&self, html: &str, file: &Path, locale: Option<&str>) -> Result<String> {
+        self.transform(PluginHook::Html, html, file, locale)
+    }
+
+    /// Notify every plugin with the `post-build` hook that the site was written.
+    pub fn post_build(&self, output_dir: &Path) -> Result<()> {
+        for plugin in self.with_hook(PluginHook::PostBuild) {
+            let response = plugin.call(&Request::PostBuild { output_dir })?;
+            report_warnings(&plugin.config.name, &response);
+        }
+        Ok(())
+    }
+
+    fn transform(
+        &self,
+        hook: PluginHook,
+        input: &str,
+        file: &Path,
+        locale: Option<&str>,
+    ) -> Result<String> {
+        let mut content = input.to_string();
+        for plugin in self.with_hook(hook) {
+            let request = match hook {
+                PluginHook::Markdown => Request::Markdown {
+                    file,
+                    locale,
+                    content: &content,
+                },
+                _ => Request::Html {
+                    file,
+                    locale,
+                    content: &content,
+                },
+            };
+            let response = plugin.call(&request)?;
+            report_warnings(&plugin.config.name, &response);
+            if let Some(transformed) = response.content {
+                content = transformed;
+            }
+        }
+        Ok(content)
+    }
+
+    fn with_hook(&self, hook: PluginHook) -> impl Iterator<Item = &ProcessPlugin> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.as_ref())
+            .filter(move |plugin| plugin.config.hooks.contains(&hook))
+    }
+}
+
+fn report_warnings(plugin: &str, response: &Response) {
+    for warning in &response.warnings {
+        diagnostics::warn_plugin(plugin, warning);
+    }
+}
+
+/// A `:::name` directive rendered by a plugin.
+struct PluginComponent {
+    name: String,
+    plugin: Arc<ProcessPlugin>,
+}
+
+impl Component for PluginComponent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, ctx: &ComponentContext) -> Result<String> {
+        let response = self.plugin.call(&Request::Component {
+            name: &self.name,
+            attributes: &ctx.attributes,
+            body_raw: &ctx.body_raw,
+            body_html: &ctx.body_html,
+        })?;
+        report_warnings(&self.plugin.config.name, &response);
+        response.content.ok_or_else(|| Error::Plugin {
+            name: self.plugin.config.name.clone(),
+            message: format!("no content returned for :::{}", self.name),
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Write an executable shell script plugin into `dir`.
+    fn script(dir: &Path, name: &str, body: &str) -> PluginConfig {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{body}")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        PluginConfig {
+            name: name.to_string(),
+            command: format!("./{name}"),
+            args: Vec::new(),
+            hooks: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn transforms_run_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = script(
+            dir.path(),
+            "first.sh",
+            "while read line; do echo '{\"content\": \"first\"}'; done\n",
+        );
+        first.hooks = vec![PluginHook::Markdown];
+        let mut second = script(
+            dir.path(),
+            "second.sh",
+            "while read line; do echo '{\"content\": \"second\"}'; done\n",
+        );
+        second.hooks = vec![PluginHook::Markdown, PluginHook::Html];
+
+        let plugins = Plugins::new(&[first, second], dir.path());
+        let file = Path::new("docs/index.md");
+        assert_eq!(
+            plugins.transform_markdown("# Hi", file, None).unwrap(),
+            "second"
+        );
+        // Second request to the same, still-running process
+        assert_eq!(
+            plugins.transform_html("<p>x</p>", file, None).unwrap(),
+            "second"
+        );
+    }
+
+    #[test]
+    fn missing_content_keeps_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lint = script(
+            dir.path(),
+            "lint.sh",
+            "while read line; do echo '{}'; done\n",
+        );
+        lint.hooks = vec![PluginHook::Html];
+
+        let plugins = Plugins::new(&[lint], dir.path());
+        let html = plugins
+            .transform_html("<p>x</p>", Path::new("a.md"), None)
+            .unwrap();
+        assert_eq!(html, "<p>x</p>");
+    }
+
+    #[test]
+    fn error_response_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bad = script(
+            dir.path(),
+            "bad.sh",
+            "read line; echo '{\"error\": \"nope\"}'\n",
+        );
+        bad.hooks = vec![PluginHook::Markdown];
+
+        let plugins = Plugins::new(&[bad], dir.path());
+        let err = plugins
+            .transform_markdown("x", Path::new("a.md"), None)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "plugin 'bad.sh' failed: nope");
+    }
+
+    #[test]
+    fn exiting_plugin_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut quits = script(dir.path(), "quits.sh", "exit 0\n");
+        quits.hooks = vec![PluginHook::PostBuild];
+
+        let plugins = Plugins::new(&[quits], dir.path());
+        let err = plugins.post_build(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("exited without responding"));
+    }
+
+    #[test]
+    fn plugin_exiting_before_reading_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut quits = script(dir.path(), "quits.sh", "exit 0\n");
+        quits.hooks = vec![PluginHook::Markdown];
+
+        // More than a pipe buffer, so the write can only end with a broken pipe
+        let source = "x".repeat(1 << 20);
+        let plugins = Plugins::new(&[quits], dir.path());
+        for _ in 0..2 {
+            let err = plugins
+                .transform_markdown(&source, Path::new("a.md"), None)
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "plugin 'quits.sh' failed: exited without responding"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_command_fails() {
+        let config = PluginConfig {
+            name: "ghost".into(),
+            command: "./does-not-exist".into(),
+            args: Vec::new(),
+            hooks: vec![PluginHook::Markdown],
+            components: Vec::new(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let plugins = Plugins::new(&[config], dir.path());
+        let err = plugins
+            .transform_markdown("x", Path::new("a.md"), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("could not start"));
+    }
+
+    #[test]
+    fn registers_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut comp = script(
+            dir.path(),
+            "comp.sh",
+            "while read line; do echo '{\"content\": \"<aside>plugin</aside>\"}'; done\n",
+        );
+        comp.components = vec!["aside".into()];
+
+        let plugins = Plugins::new(&[comp], dir.path());
+        let mut registry = ComponentRegistry::with_builtins();
+        plugins.register_components(&mut registry);
+
+        let source = ":::aside\nbody\n:::\n";
+        let html = crate::pipeline::directives::process_directives(source, &mut |block| {
+            registry.render_block(block).unwrap()
+        });
+        assert!(html.contains("<aside>plugin</aside>"));
+    }
+}
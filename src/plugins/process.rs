@@ -0,0 +1,161 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PluginConfig;
+use crate::error::{Error, Result};
+
+/// A message sent to a plugin, one JSON object per line.
+#[derive(Debug, Serialize)]
+#[serde(tag = "hook", rename_all = "kebab-case")]
+pub enum Request<'a> {
+    Markdown {
+        file: &'a Path,
+        locale: Option<&'a str>,
+        content: &'a str,
+    },
+    Html {
+        file: &'a Path,
+        locale: Option<&'a str>,
+        content: &'a str,
+    },
+    Component {
+        name: &'a str,
+        attributes: &'a std::collections::HashMap<String, String>,
+        body_raw: &'a str,
+        body_html: &'a str,
+    },
+    PostBuild {
+        output_dir: &'a Path,
+    },
+}
+
+/// A plugin's reply to a [`Request`], one JSON object per line.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Response {
+    /// Transformed Markdown or HTML, or a component's HTML. `None` leaves a
+    /// transform's input unchanged.
+    pub content: Option<String>,
+    /// Fails the build with this message.
+    pub error: Option<String>,
+    /// Reported as build warnings.
+    pub warnings: Vec<String>,
+}
+
+/// An external plugin process, started on first use and kept running for the
+/// rest of the build. Requests are serialised: one request, one response line.
+pub struct ProcessPlugin {
+    pub config: PluginConfig,
+    root: PathBuf,
+    running: Mutex<Option<Running>>,
+}
+
+struct Running {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Running {
+    /// Close the plugin's stdin so it can exit, then wait for it, so no
+    /// zombie process is left behind.
+    fn reap(self) {
+        let Running {
+            mut child, stdin, ..
+        } = self;
+        drop(stdin);
+        let _ = child.wait();
+    }
+}
+
+impl ProcessPlugin {
+    pub fn new(config: PluginConfig, project_root: &Path) -> Self {
+        Self {
+            config,
+            root: project_root.to_path_buf(),
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Send a request and wait for the plugin's response.
+    pub fn call(&self, request: &Request) -> Result<Response> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.is_none() {
+            *running = Some(self.spawn()?);
+        }
+        let process = running.as_mut().expect("plugin process was just started");
+
+        let line = serde_json::to_string(request).map_err(|e| self.error(e))?;
+        if let Err(e) = writeln!(process.stdin, "{line}").and_then(|_| process.stdin.flush()) {
+            if e.kind() == std::io::ErrorKind::BrokenPipe {
+                // The process exited before reading the request.
+                running.take().expect("plugin process is running").reap();
+                return Err(self.error("exited without responding"));
+            }
+            return Err(self.error(format!("could not write request: {e}")));
+        }
+
+        let mut reply = String::new();
+        let read = process
+            .stdout
+            .read_line(&mut reply)
+            .map_err(|e| self.error(format!("could not read response: {e}")))?;
+        if read == 0 {
+            // The process is gone; start a fresh one if there is another request.
+            running.take().expect("plugin process is running").reap();
+            return Err(self.error("exited without responding"));
+        }
+
+        let response: Response = serde_json::from_str(&reply)
+            .map_err(|e| self.error(format!("invalid response: {e}")))?;
+        match response.error {
+            Some(message) => Err(self.error(message)),
+            None => Ok(response),
+        }
+    }
+
+    fn spawn(&self) -> Result<Running> {
+        // Relative paths like `plugins/lint.py` resolve against the project root;
+        // bare names are looked up on PATH.
+        let command = if self.config.command.contains('/') {
+            self.root.join(&self.config.command)
+        } else {
+            PathBuf::from(&self.config.command)
+        };
+        let mut child = Command::new(&command)
+            .args(&self.config.args)
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| self.error(format!("could not start {}: {e}", command.display())))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(Running {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn error(&self, message: impl ToString) -> Error {
+        Error::Plugin {
+            name: self.config.name.clone(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Drop for ProcessPlugin {
+    fn drop(&mut self) {
+        let running = self.running.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Some(process) = running.take() {
+            process.reap();
+        }
+    }
+}
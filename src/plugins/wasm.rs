@@ -0,0 +1,522 @@
+//! WebAssembly components loaded from the project's `plugins/` directory.
+//!
+//! Each `plugins/*.wasm` module renders one `:::name` directive inside a
+//! sandbox: it gets no filesystem, network, clock or environment access, a
+//! bounded amount of memory and a bounded number of instructions per render.
+//!
+//! A module implements ABI version 1 by exporting:
+//!
+//! | Export | Signature | Purpose |
+//! |--------|-----------|---------|
+//! | `memory` | memory | Linear memory used to exchange strings |
+//! | `docanvil_abi_version` | `() -> i32` | Must return `1` |
+//! | `docanvil_alloc` | `(len: i32) -> i32` | Allocate `len` bytes for input |
+//! | `docanvil_name` | `() -> i64` | Directive name, as a packed string |
+//! | `docanvil_render` | `(ptr: i32, len: i32) -> i64` | Render a directive, as a packed string |
+//!
+//! A packed string is `(ptr << 32) | len` pointing at UTF-8 bytes in `memory`.
+//! `docanvil_render` receives the same JSON `component` request as process
+//! plugins and returns the same JSON response (see [`crate::plugins`]).
+//!
+//! Modules built for `wasm32-wasip1` may import a small subset of
+//! `wasi_snapshot_preview1`: writes to stdout and stderr go to docanvil's
+//! stderr, and everything else reports no data or no access. Reactor modules'
+//! `_initialize` export is called before use.
+
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use wasmi::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+use crate::components::{Component, ComponentContext};
+use crate::error::{Error, Result};
+use crate::source::FileSource;
+
+use super::{Request, Response, report_warnings};
+
+/// Directory, relative to the project root, that modules are loaded from.
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// The ABI version modules must report from `docanvil_abi_version`.
+pub const ABI_VERSION: i32 = 1;
+
+/// Instructions a module may execute per call before it is stopped.
+const FUEL: u64 = 1_000_000_000;
+
+/// Maximum linear memory per module instance, in bytes.
+const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+const WASI: &str = "wasi_snapshot_preview1";
+const ERRNO_SUCCESS: i32 = 0;
+const ERRNO_BADF: i32 = 8;
+
+/// List the `.wasm` modules directly inside `dir`, in path order.
+///
+/// Subdirectories are ignored so a plugin's source and build artefacts can
+/// live next to the compiled module.
+pub fn list_modules(files: &dyn FileSource, dir: &Path) -> Vec<PathBuf> {
+    files
+        .walk(dir)
+        .into_iter()
+        .filter(|path| path.parent() == Some(dir))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect()
+}
+
+/// Load every `.wasm` module directly inside `dir`.
+pub fn load_dir(files: &dyn FileSource, dir: &Path) -> Result<Vec<WasmComponent>> {
+    let engine = engine();
+    list_modules(files, dir)
+        .into_iter()
+        .map(|path| {
+            let bytes = files.read(&path)?;
+            WasmComponent::load(&engine, &path, &bytes)
+        })
+        .collect()
+}
+
+fn engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+/// A `:::name` directive rendered by a WebAssembly module.
+///
+/// Every render runs in a fresh instance, so modules cannot keep state between
+/// pages and can render on several threads at once.
+pub struct WasmComponent {
+    name: String,
+    /// File name of the module, used in warnings and errors.
+    plugin: String,
+    module: Module,
+    /// Instructions allowed per call.
+    fuel: u64,
+}
+
+impl WasmComponent {
+    /// Compile a module and read its directive name.
+    pub fn load(engine: &Engine, path: &Path, bytes: &[u8]) -> Result<Self> {
+        let plugin = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let error = |e: wasmi::Error| Error::Plugin {
+            name: plugin.clone(),
+            message: e.to_string(),
+        };
+
+        let module = Module::new(engine, bytes).map_err(error)?;
+        let mut component = Self {
+            name: String::new(),
+            plugin: plugin.clone(),
+            module,
+            fuel: FUEL,
+        };
+        component.name = component.call_name().map_err(error)?;
+        Ok(component)
+    }
+
+    fn call_name(&self) -> std::result::Result<String, wasmi::Error> {
+        let (mut store, instance) = self.instantiate()?;
+        let version = instance
+            .get_typed_func::<(), i32>(&store, "docanvil_abi_version")?
+            .call(&mut store, ())?;
+        if version != ABI_VERSION {
+            return Err(wasmi::Error::new(format!(
+                "unsupported ABI version {version} (expected {ABI_VERSION})"
+            )));
+        }
+        let packed = instance
+            .get_typed_func::<(), i64>(&store, "docanvil_name")?
+            .call(&mut store, ())?;
+        let bytes = read_packed(&store, &instance, packed)?;
+        String::from_utf8(bytes).map_err(|_| wasmi::Error::new("name is not valid UTF-8"))
+    }
+
+    fn call_render(&self, input: &[u8]) -> std::result::Result<Vec<u8>, wasmi::Error> {
+        let (mut store, instance) = self.instantiate()?;
+        let len = i32::try_from(input.len()).map_err(|_| wasmi::Error::new("input too large"))?;
+        let ptr = instance
+            .get_typed_func::<i32, i32>(&store, "docanvil_alloc")?
+            .call(&mut store, len)?;
+        memory(&store, &instance)?.write(&mut store, ptr as u32 as usize, input)?;
+        let packed = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "docanvil_render")?
+            .call(&mut store, (ptr, len))?;
+        read_packed(&store, &instance, packed)
+    }
+
+    fn instantiate(&self) -> std::result::Result<(Store<StoreLimits>, Instance), wasmi::Error> {
+        let engine = self.module.engine();
+        let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+        let mut store = Store::new(engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.fuel)?;
+
+        let instance = wasi_linker(engine)?.instantiate_and_start(&mut store, &self.module)?;
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&store, "_initialize") {
+            initialize.call(&mut store, ())?;
+        }
+        Ok((store, instance))
+    }
+}
+
+impl Component for WasmComponent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, ctx: &ComponentContext) -> Result<String> {
+        let error = |message: String| Error::Plugin {
+            name: self.plugin.clone(),
+            message,
+        };
+        let request = serde_json::to_vec(&Request::Component {
+            name: &self.name,
+            attributes: &ctx.attributes,
+            body_raw: &ctx.body_raw,
+            body_html: &ctx.body_html,
+        })
+        .map_err(|e| error(e.to_string()))?;
+
+        let output = self
+            .call_render(&request)
+            .map_err(|e| error(e.to_string()))?;
+        let response: Response =
+            serde_json::from_slice(&output).map_err(|e| error(format!("invalid response: {e}")))?;
+        if let Some(message) = response.error {
+            return Err(error(message));
+        }
+        report_warnings(&self.plugin, &response);
+        response
+            .content
+            .ok_or_else(|| error(format!("no content returned for :::{}", self.name)))
+    }
+}
+
+fn memory(
+    store: &Store<StoreLimits>,
+    instance: &Instance,
+) -> std::result::Result<Memory, wasmi::Error> {
+    instance
+        .get_memory(store, "memory")
+        .ok_or_else(|| wasmi::Error::new("module does not export its memory"))
+}
+
+/// Read the string a packed `(ptr << 32) | len` value points at. The range is
+/// checked against the module's memory before anything is allocated for it.
+fn read_packed(
+    store: &Store<StoreLimits>,
+    instance: &Instance,
+    packed: i64,
+) -> std::result::Result<Vec<u8>, wasmi::Error> {
+    let packed = packed as u64;
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    let memory = memory(store, instance)?;
+    let range = checked_range(memory.data_size(store), ptr, len).ok_or_else(|| {
+        wasmi::Error::new(format!("returned {len} bytes at {ptr}, outside its memory"))
+    })?;
+    Ok(memory.data(store)[range].to_vec())
+}
+
+/// The range of `len` bytes at `ptr` in a module's memory of `size` bytes, or
+/// `None` if it doesn't fit. Guest-supplied ranges are checked with this
+/// before anything is read, written or allocated for them.
+fn checked_range(size: usize, ptr: usize, len: usize) -> Option<Range<usize>> {
+    let end = ptr.checked_add(len)?;
+    (end <= size).then_some(ptr..end)
+}
+
+/// A linker providing the sandboxed `wasi_snapshot_preview1` subset.
+fn wasi_linker(engine: &Engine) -> std::result::Result<Linker<StoreLimits>, wasmi::Error> {
+    type Ctx<'a> = Caller<'a, StoreLimits>;
+
+    fn caller_memory(caller: &Ctx) -> std::result::Result<Memory, wasmi::Error> {
+        caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .ok_or_else(|| wasmi::Error::new("module does not export its memory"))
+    }
+
+    /// The range of `len` bytes at the guest pointer `ptr`, trapping if it
+    /// doesn't fit in the module's memory.
+    fn guest_range(
+        caller: &Ctx,
+        memory: Memory,
+        ptr: i32,
+        len: usize,
+    ) -> std::result::Result<Range<usize>, wasmi::Error> {
+        let ptr = ptr as u32 as usize;
+        checked_range(memory.data_size(caller), ptr, len).ok_or_else(|| {
+            wasmi::Error::new(format!("accessed {len} bytes at {ptr}, outside its memory"))
+        })
+    }
+
+    fn write_zeros(
+        caller: &mut Ctx,
+        ptrs: &[(i32, usize)],
+    ) -> std::result::Result<i32, wasmi::Error> {
+        let memory = caller_memory(caller)?;
+        for &(ptr, len) in ptrs {
+            let range = guest_range(caller, memory, ptr, len)?;
+            memory.data_mut(&mut *caller)[range].fill(0);
+        }
+        Ok(ERRNO_SUCCESS)
+    }
+
+    let mut linker = Linker::new(engine);
+
+    // Output: stdout and stderr are forwarded to docanvil's stderr
+    linker.func_wrap(
+        WASI,
+        "fd_write",
+        |mut caller: Ctx, fd: i32, iovs: i32, iovs_len: i32, nwritten: i32| {
+            if fd != 1 && fd != 2 {
+                return Ok(ERRNO_BADF);
+            }
+            let memory = caller_memory(&caller)?;
+            // Each iovec is a (base, len) pair of little-endian u32s
+            let iovs_len = (iovs_len as u32 as usize)
+                .checked_mul(8)
+                .ok_or_else(|| wasmi::Error::new("too many iovecs"))?;
+            let iovs = guest_range(&caller, memory, iovs, iovs_len)?;
+            let nwritten = guest_range(&caller, memory, nwritten, 4)?;
+
+            // Written straight from the module's memory, so nothing is
+            // allocated for the sizes it asks for
+            let data = memory.data(&caller);
+            let mut stderr = std::io::stderr().lock();
+            let mut written = 0u32;
+            for iov in data[iovs].chunks_exact(8) {
+                let base = u32::from_le_bytes(iov[..4].try_into().unwrap()) as usize;
+                let len = u32::from_le_bytes(iov[4..].try_into().unwrap());
+                let range = checked_range(data.len(), base, len as usize).ok_or_else(|| {
+                    wasmi::Error::new(format!("wrote {len} bytes at {base}, outside its memory"))
+                })?;
+                let _ = stderr.write_all(&data[range]);
+                written = written.saturating_add(len);
+            }
+            memory.data_mut(&mut caller)[nwritten].copy_from_slice(&written.to_le_bytes());
+            Ok(ERRNO_SUCCESS)
+        },
+    )?;
+
+    // Files: there are no pre-opened directories and no other descriptors
+    linker.func_wrap(WASI, "fd_read", |_: i32, _: i32, _: i32, _: i32| ERRNO_BADF)?;
+    linker.func_wrap(WASI, "fd_close", |_: i32| ERRNO_BADF)?;
+    linker.func_wrap(WASI, "fd_seek", |_: i32, _: i64, _: i32, _: i32| ERRNO_BADF)?;
+    linker.func_wrap(WASI, "fd_fdstat_get", |_: i32, _: i32| ERRNO_BADF)?;
+    linker.func_wrap(WASI, "fd_prestat_get", |_: i32, _: i32| ERRNO_BADF)?;
+    linker.func_wrap(WASI, "fd_prestat_dir_name", |_: i32, _: i32, _: i32| {
+        ERRNO_BADF
+    })?;
+
+    // Environment and arguments: always empty
+    for name in ["environ_sizes_get", "args_sizes_get"] {
+        linker.func_wrap(WASI, name, |mut caller: Ctx, count: i32, size: i32| {
+            write_zeros(&mut caller, &[(count, 4), (size, 4)])
+        })?;
+    }
+    linker.func_wrap(WASI, "environ_get", |_: i32, _: i32| ERRNO_SUCCESS)?;
+    linker.func_wrap(WASI, "args_get", |_: i32, _: i32| ERRNO_SUCCESS)?;
+
+    // Clock and randomness: fixed, so output is reproducible
+    linker.func_wrap(
+        WASI,
+        "clock_time_get",
+        |mut caller: Ctx, _: i32, _: i64, time: i32| write_zeros(&mut caller, &[(time, 8)]),
+    )?;
+    linker.func_wrap(WASI, "random_get", |mut caller: Ctx, buf: i32, len: i32| {
+        write_zeros(&mut caller, &[(buf, len as u32 as usize)])
+    })?;
+
+    linker.func_wrap(WASI, "sched_yield", || ERRNO_SUCCESS)?;
+    linker.func_wrap(
+        WASI,
+        "proc_exit",
+        |code: i32| -> std::result::Result<(), wasmi::Error> { Err(wasmi::Error::i32_exit(code)) },
+    )?;
+
+    Ok(linker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::ComponentRegistry;
+    use crate::source::MemoryFiles;
+    use std::collections::HashMap;
+
+    /// A module rendering `:::shout` as a fixed response, with `render` as the
+    /// body of `docanvil_render`.
+    fn module(render: &str) -> String {
+        let response = r#"{"content":"<b>wasm</b>","warnings":["from wasm"]}"#;
+        format!(
+            r#"(module
+                (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 1024))
+                (data (i32.const 0) "shout")
+                (data (i32.const 16) "{escaped}")
+                (func (export "docanvil_abi_version") (result i32) (i32.const 1))
+                (func (export "docanvil_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $heap))
+                    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "docanvil_name") (result i64) (i64.const 5))
+                (func (export "docanvil_render") (param i32 i32) (result i64) {render}))"#,
+            escaped = response.replace('"', "\\\""),
+            render = render.replace("LEN", &response.len().to_string()),
+        )
+    }
+
+    /// Render body returning the fixed response at offset 16.
+    const RESPOND: &str = "(i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const LEN))";
+
+    fn context() -> ComponentContext {
+        ComponentContext {
+            attributes: HashMap::new(),
+            body_raw: String::new(),
+            body_html: String::new(),
+        }
+    }
+
+    #[test]
+    fn loads_and_renders() {
+        let files = MemoryFiles::new().with("plugins/shout.wasm", module(RESPOND));
+        let components = load_dir(&files, Path::new("plugins")).unwrap();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].name(), "shout");
+
+        let (html, warnings) = crate::diagnostics::capture(|| components[0].render(&context()));
+        assert_eq!(html.unwrap(), "<b>wasm</b>");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "plugin 'shout.wasm': from wasm");
+    }
+
+    #[test]
+    fn registry_loads_plugins_dir() {
+        let files = MemoryFiles::new()
+            .with("plugins/shout.wasm", module(RESPOND))
+            .with("plugins/lint.py", "print()")
+            .with("plugins/src/target/other.wasm", "not wasm");
+        let mut registry = ComponentRegistry::with_builtins();
+        registry
+            .register_wasm_plugins(&files, Path::new("plugins"))
+            .unwrap();
+
+        let block = crate::pipeline::directives::DirectiveBlock {
+            name: "shout".into(),
+            attributes: HashMap::new(),
+            body: String::new(),
+        };
+        let (html, _) = crate::diagnostics::capture(|| registry.render_block(&block).unwrap());
+        assert_eq!(html, "<b>wasm</b>");
+    }
+
+    #[test]
+    fn invalid_module_fails_to_load() {
+        let files = MemoryFiles::new().with("plugins/broken.wasm", "not wasm");
+        let err = load_dir(&files, Path::new("plugins")).err().unwrap();
+        assert!(err.to_string().starts_with("plugin 'broken.wasm' failed"));
+    }
+
+    #[test]
+    fn wrong_abi_version_fails_to_load() {
+        let wat = module(RESPOND).replace("(i32.const 1))", "(i32.const 2))");
+        let files = MemoryFiles::new().with("plugins/shout.wasm", wat);
+        let err = load_dir(&files, Path::new("plugins")).err().unwrap();
+        assert!(err.to_string().contains("unsupported ABI version 2"));
+    }
+
+    #[test]
+    fn out_of_range_response_fails() {
+        // 4 GiB - 1 bytes at the end of the module's single 64 KiB page
+        let files = MemoryFiles::new().with(
+            "plugins/shout.wasm",
+            module("(i64.or (i64.shl (i64.const 65535) (i64.const 32)) (i64.const 4294967295))"),
+        );
+        let components = load_dir(&files, Path::new("plugins")).unwrap();
+        let err = components[0].render(&context()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plugin 'shout.wasm' failed: returned 4294967295 bytes at 65535, outside its memory"
+        );
+    }
+
+    #[test]
+    fn out_of_range_host_calls_fail() {
+        let fails = |render: &str| {
+            let wat = module(&format!("{render} {RESPOND}"));
+            let files = MemoryFiles::new().with("plugins/shout.wasm", wat);
+            let components = load_dir(&files, Path::new("plugins")).unwrap();
+            components[0].render(&context()).unwrap_err().to_string()
+        };
+
+        // An iovec of 4 GiB - 1 bytes
+        let err = fails(
+            "(i32.store (i32.const 512) (i32.const 0))
+             (i32.store (i32.const 516) (i32.const -1))
+             (drop (call $fd_write (i32.const 2) (i32.const 512) (i32.const 1) (i32.const 520)))",
+        );
+        assert!(
+            err.contains("wrote 4294967295 bytes at 0, outside its memory"),
+            "{err}"
+        );
+
+        // 2^31 - 1 iovecs
+        let err = fails(
+            "(drop (call $fd_write (i32.const 2) (i32.const 512) (i32.const 2147483647) (i32.const 520)))",
+        );
+        assert!(err.contains("outside its memory"), "{err}");
+
+        let err = fails("(drop (call $random_get (i32.const 512) (i32.const -1)))");
+        assert!(
+            err.contains("accessed 4294967295 bytes at 512, outside its memory"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn writes_and_random_bytes_stay_in_memory() {
+        // An empty write, then 5 bytes zeroed
+        let render = "(i32.store (i32.const 512) (i32.const 0))
+             (i32.store (i32.const 516) (i32.const 0))
+             (i32.store (i32.const 520) (i32.const 7))
+             (drop (call $fd_write (i32.const 2) (i32.const 512) (i32.const 1) (i32.const 520)))
+             (if (i32.ne (i32.load (i32.const 520)) (i32.const 0)) (then unreachable))
+             (drop (call $random_get (i32.const 0) (i32.const 5)))
+             (if (i32.ne (i32.load8_u (i32.const 4)) (i32.const 0)) (then unreachable))";
+        let wat = module(&format!("{render} {RESPOND}"));
+        let files = MemoryFiles::new().with("plugins/shout.wasm", wat);
+        let components = load_dir(&files, Path::new("plugins")).unwrap();
+        let (html, _) = crate::diagnostics::capture(|| components[0].render(&context()));
+        assert_eq!(html.unwrap(), "<b>wasm</b>");
+    }
+
+    #[test]
+    fn runaway_render_is_stopped() {
+        let files = MemoryFiles::new().with(
+            "plugins/shout.wasm",
+            module("(loop $forever (br $forever)) (i64.const 0)"),
+        );
+        let mut components = load_dir(&files, Path::new("plugins")).unwrap();
+        components[0].fuel = 100_000;
+        let err = components[0].render(&context()).unwrap_err();
+        assert!(err.to_string().contains("fuel"), "{err}");
+    }
+
+    #[test]
+    fn out_of_bounds_response_fails() {
+        let files = MemoryFiles::new().with("plugins/shout.wasm", module("(i64.const -1)"));
+        let components = load_dir(&files, Path::new("plugins")).unwrap();
+        assert!(components[0].render(&context()).is_err());
+    }
+}
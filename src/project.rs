@@ -1,9 +1,11 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use walkdir::WalkDir;
+use serde::Deserialize;
 
 use crate::error::Result;
+use crate::render::icons;
+use crate::source::{DiskFiles, FileSource};
 
 /// Metadata for a single documentation page.
 #[derive(Debug, Clone)]
@@ -20,6 +22,58 @@ pub struct PageInfo {
     pub locale: Option<String>,
     /// Version code for this page (e.g. "v2"), if versioning is enabled.
     pub version: Option<String>,
+    /// Which listings include the page, as set in front matter.
+    pub listing: Listing,
+    /// Label in auto-generated navigation, if it differs from the title.
+    pub nav_label: Option<String>,
+    /// Position among its siblings in auto-generated navigation; lower comes first.
+    pub nav_order: Option<i64>,
+}
+
+/// Where a page is listed. A page left out of a listing is still built and
+/// reachable by URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Listing {
+    /// Auto-generated navigation (`nav: false` in front matter).
+    pub nav: bool,
+    /// The search index (`search: false`).
+    pub search: bool,
+    /// `sitemap.xml` (`sitemap: false` or `noindex: true`).
+    pub sitemap: bool,
+}
+
+impl Default for Listing {
+    fn default() -> Self {
+        Self {
+            nav: true,
+            search: true,
+            sitemap: true,
+        }
+    }
+}
+
+/// File in a content folder that sets the folder's label and position in
+/// auto-generated navigation.
+pub const FOLDER_META_FILE: &str = "_folder.toml";
+
+/// Navigation metadata for a content folder, read from its [`FOLDER_META_FILE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+pub struct FolderMeta {
+    /// Group label, instead of one derived from the folder name.
+    pub label: Option<String>,
+    /// Position among its siblings; lower comes first.
+    #[serde(alias = "weight")]
+    pub order: Option<i64>,
+}
+
+impl FolderMeta {
+    /// Parse the contents of a folder metadata file read from `path`.
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        toml::from_str(content).map_err(|e| crate::error::Error::ConfigParse {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
 }
 
 /// Ordered collection of all pages in the docs directory.
@@ -33,6 +87,11 @@ pub struct PageInventory {
     pub slug_aliases: HashMap<String, String>,
     /// Locales discovered during scanning (populated only when i18n is enabled).
     pub discovered_locales: HashSet<String>,
+    /// Keys of draft and scheduled pages left out of the build with [`withhold`](Self::withhold).
+    pub unpublished: HashSet<String>,
+    /// Folder path relative to the content directory (e.g. `guides/advanced`) →
+    /// metadata from its `_folder.toml`.
+    pub folders: HashMap<String, FolderMeta>,
 }
 
 /// A node in the navigation tree.
@@ -41,17 +100,54 @@ pub enum NavNode {
     Page {
         label: String,
         slug: String,
+        decoration: NavDecoration,
     },
     Group {
         label: String,
         slug: Option<String>,
         children: Vec<NavNode>,
+        decoration: NavDecoration,
+    },
+    /// A link to an external URL, opened in a new tab.
+    Link {
+        label: String,
+        url: String,
+        decoration: NavDecoration,
     },
     Separator {
         label: Option<String>,
     },
 }
 
+/// Icon and badge shown around a nav entry's label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NavDecoration {
+    /// Name of a built-in icon, shown before the label.
+    pub icon: Option<String>,
+    /// Status badge, shown after the label.
+    pub badge: Option<Badge>,
+}
+
+/// A status badge on a nav entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Badge {
+    New,
+    Beta,
+    Deprecated,
+}
+
+impl Badge {
+    /// The badge's text, also used in its CSS class (`nav-badge-beta`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Badge::New => "new",
+            Badge::Beta => "beta",
+            Badge::Deprecated => "deprecated",
+        }
+    }
+}
+
 /// Extract locale suffix from a filename stem, if it matches an enabled locale.
 ///
 /// Given `getting-started.en` and enabled `["en", "fr"]`, returns `("getting-started", Some("en"))`.
@@ -80,24 +176,51 @@ impl PageInventory {
         enabled_locales: Option<&[String]>,
         default_locale: Option<&str>,
         version: Option<&str>,
+    ) -> Result<Self> {
+        Self::scan_files(
+            &DiskFiles,
+            content_dir,
+            enabled_locales,
+            default_locale,
+            version,
+        )
+    }
+
+    /// Like [`scan`](Self::scan), but lists Markdown files through `files`.
+    pub fn scan_files(
+        files: &dyn FileSource,
+        content_dir: &Path,
+        enabled_locales: Option<&[String]>,
+        default_locale: Option<&str>,
+        version: Option<&str>,
     ) -> Result<Self> {
         let mut pages = HashMap::new();
         let mut ordered = Vec::new();
         let mut discovered_locales = HashSet::new();
+        let mut folders = HashMap::new();
+
+        // walk() returns paths in sorted order, which keeps the inventory deterministic
+        let walked = files.walk(content_dir);
+
+        for path in walked.iter().filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name == FOLDER_META_FILE)
+        }) {
+            let Some(folder) = path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(content_dir).ok())
+            else {
+                continue;
+            };
+            let meta = FolderMeta::parse(&files.read_to_string(path)?, path)?;
+            folders.insert(folder.to_string_lossy().replace('\\', "/"), meta);
+        }
 
-        let mut entries: Vec<_> = WalkDir::new(content_dir)
+        let entries = walked
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path().extension().is_some_and(|ext| ext == "md") && e.file_type().is_file()
-            })
-            .collect();
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"));
 
-        // Sort for deterministic order
-        entries.sort_by(|a, b| a.path().cmp(b.path()));
-
-        for entry in entries {
-            let path = entry.path().to_path_buf();
+        for path in entries {
             let Ok(relative) = path.strip_prefix(content_dir) else {
                 crate::diagnostics::warn_unexpected_content_path(&path);
                 continue;
@@ -141,6 +264,9 @@ impl PageInventory {
                     slug: base_slug,
                     locale: Some(locale_code.clone()),
                     version: version.map(String::from),
+                    listing: Listing::default(),
+                    nav_label: None,
+                    nav_order: None,
                 };
 
                 ordered.push(key.clone());
@@ -162,6 +288,9 @@ impl PageInventory {
                     slug: raw_slug.clone(),
                     locale: None,
                     version: version.map(String::from),
+                    listing: Listing::default(),
+                    nav_label: None,
+                    nav_order: None,
                 };
 
                 ordered.push(raw_slug.clone());
@@ -174,6 +303,8 @@ impl PageInventory {
             ordered,
             slug_aliases: HashMap::new(),
             discovered_locales,
+            unpublished: HashSet::new(),
+            folders,
         })
     }
 
@@ -229,6 +360,17 @@ impl PageInventory {
         }
     }
 
+    /// Leave the page at `key` out of the inventory, recording it as unpublished
+    /// so nav entries pointing at it are dropped without a warning. Returns the
+    /// page that was left out.
+    pub fn withhold(&mut self, key: &str) -> Option<PageInfo> {
+        let page = self.pages.remove(key)?;
+        self.ordered.retain(|k| k != key);
+        self.slug_aliases.retain(|_, target| target != key);
+        self.unpublished.insert(key.to_string());
+        Some(page)
+    }
+
     /// Resolve a wiki-link target within a specific locale.
     /// Looks up `{locale}:{normalized_target}`, then alias, then basename — all within the same locale.
     pub fn resolve_link_in_locale(&self, target: &str, locale: &str) -> Option<&PageInfo> {
@@ -324,8 +466,9 @@ impl PageInventory {
     /// and builds a subtree using the same logic as `nav_tree`. Optionally excludes
     /// a specific slug (e.g. when a group's header page should not appear as a child).
     pub fn nav_tree_for_folder(&self, folder: &str, exclude_slug: Option<&str>) -> Vec<NavNode> {
-        let prefix = format!("{}/", folder.trim_end_matches('/'));
-        let mut root: Vec<NavNode> = Vec::new();
+        let folder = folder.trim_end_matches('/');
+        let prefix = format!("{folder}/");
+        let mut pages = Vec::new();
 
         for slug in &self.ordered {
             if let Some(excluded) = exclude_slug
@@ -338,12 +481,14 @@ impl PageInventory {
                     continue;
                 }
                 let info = &self.pages[slug];
-                let parts: Vec<&str> = rest.split('/').collect();
-                insert_nav_node(&mut root, &parts, info);
+                if !info.listing.nav {
+                    continue;
+                }
+                pages.push((rest.split('/').collect(), info));
             }
         }
 
-        root
+        self.nav_nodes(folder, pages)
     }
 
     /// Build a navigation subtree for pages within a specific folder, filtered to a locale.
@@ -356,12 +501,13 @@ impl PageInventory {
         exclude_slug: Option<&str>,
         locale: &str,
     ) -> Vec<NavNode> {
-        let prefix = format!("{}/", folder.trim_end_matches('/'));
-        let mut root: Vec<NavNode> = Vec::new();
+        let folder = folder.trim_end_matches('/');
+        let prefix = format!("{folder}/");
+        let mut pages = Vec::new();
 
         for key in &self.ordered {
             let info = &self.pages[key];
-            if info.locale.as_deref() != Some(locale) {
+            if info.locale.as_deref() != Some(locale) || !info.listing.nav {
                 continue;
             }
             if let Some(excluded) = exclude_slug
@@ -373,73 +519,108 @@ impl PageInventory {
                 if rest.is_empty() {
                     continue;
                 }
-                let parts: Vec<&str> = rest.split('/').collect();
-                insert_nav_node(&mut root, &parts, info);
+                pages.push((rest.split('/').collect(), info));
             }
         }
 
-        root
+        self.nav_nodes(folder, pages)
     }
 
-    /// Build a navigation tree from the page inventory.
+    /// Build a navigation tree from the page inventory, leaving out pages
+    /// hidden from nav in their front matter.
     pub fn nav_tree(&self) -> Vec<NavNode> {
-        let mut root: Vec<NavNode> = Vec::new();
-
-        for slug in &self.ordered {
-            let info = &self.pages[slug];
-            let parts: Vec<&str> = info.slug.split('/').collect();
-            insert_nav_node(&mut root, &parts, info);
-        }
-
-        root
+        let pages = self
+            .ordered
+            .iter()
+            .map(|slug| &self.pages[slug])
+            .filter(|info| info.listing.nav)
+            .map(|info| (info.slug.split('/').collect(), info))
+            .collect();
+        self.nav_nodes("", pages)
     }
 
     /// Build a navigation tree for a specific locale's pages.
     pub fn nav_tree_for_locale(&self, locale: &str) -> Vec<NavNode> {
-        let mut root: Vec<NavNode> = Vec::new();
+        let pages = self
+            .ordered
+            .iter()
+            .map(|key| &self.pages[key])
+            .filter(|info| info.locale.as_deref() == Some(locale) && info.listing.nav)
+            .map(|info| (info.slug.split('/').collect(), info))
+            .collect();
+        self.nav_nodes("", pages)
+    }
 
-        for key in &self.ordered {
-            let info = &self.pages[key];
-            if info.locale.as_deref() != Some(locale) {
-                continue;
+    /// Arrange pages into nav nodes. Each page comes with its slug segments
+    /// below `folder` (`""` for the content root); pages sharing a first
+    /// segment are grouped under a subfolder.
+    ///
+    /// Siblings with a `nav_order` (or a folder `order`) come first, lowest
+    /// first; the rest keep their path order.
+    fn nav_nodes<'a>(
+        &'a self,
+        folder: &str,
+        pages: Vec<(Vec<&'a str>, &'a PageInfo)>,
+    ) -> Vec<NavNode> {
+        enum Item<'a> {
+            Page(&'a PageInfo),
+            Folder(&'a str, Vec<(Vec<&'a str>, &'a PageInfo)>),
+        }
+
+        let mut items: Vec<Item> = Vec::new();
+        for (parts, info) in pages {
+            match parts.as_slice() {
+                [] => {}
+                [_] => items.push(Item::Page(info)),
+                [dir, rest @ ..] => {
+                    let entry = (rest.to_vec(), info);
+                    match items
+                        .iter_mut()
+                        .find(|item| matches!(item, Item::Folder(name, _) if name == dir))
+                    {
+                        Some(Item::Folder(_, children)) => children.push(entry),
+                        _ => items.push(Item::Folder(dir, vec![entry])),
+                    }
+                }
             }
-            let parts: Vec<&str> = info.slug.split('/').collect();
-            insert_nav_node(&mut root, &parts, info);
         }
 
-        root
-    }
-}
+        let mut nodes: Vec<(Option<i64>, NavNode)> = items
+            .into_iter()
+            .map(|item| match item {
+                Item::Page(info) => (
+                    info.nav_order,
+                    NavNode::Page {
+                        label: info.nav_label.clone().unwrap_or_else(|| info.title.clone()),
+                        slug: info.slug.clone(),
+                        decoration: NavDecoration::default(),
+                    },
+                ),
+                Item::Folder(name, children) => {
+                    let path = if folder.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{folder}/{name}")
+                    };
+                    let meta = self.folders.get(&path);
+                    (
+                        meta.and_then(|m| m.order),
+                        NavNode::Group {
+                            label: meta
+                                .and_then(|m| m.label.clone())
+                                .unwrap_or_else(|| title_from_slug(name)),
+                            slug: None,
+                            children: self.nav_nodes(&path, children),
+                            decoration: NavDecoration::default(),
+                        },
+                    )
+                }
+            })
+            .collect();
 
-fn insert_nav_node(nodes: &mut Vec<NavNode>, parts: &[&str], info: &PageInfo) {
-    if parts.is_empty() {
-        return;
-    }
-
-    if parts.len() == 1 {
-        // Leaf page
-        nodes.push(NavNode::Page {
-            label: info.title.clone(),
-            slug: info.slug.clone(),
-        });
-    } else {
-        // Find or create directory node
-        let dir_label = title_from_slug(parts[0]);
-        let dir_node = nodes
-            .iter_mut()
-            .find(|n| matches!(n, NavNode::Group { label, slug: None, .. } if label == &dir_label));
-
-        if let Some(NavNode::Group { children, .. }) = dir_node {
-            insert_nav_node(children, &parts[1..], info);
-        } else {
-            let mut children = Vec::new();
-            insert_nav_node(&mut children, &parts[1..], info);
-            nodes.push(NavNode::Group {
-                label: dir_label,
-                slug: None,
-                children,
-            });
-        }
+        // Stable, so unordered siblings keep their path order
+        nodes.sort_by_key(|(order, _)| (order.is_none(), *order));
+        nodes.into_iter().map(|(_, node)| node).collect()
     }
 }
 
@@ -478,7 +659,7 @@ fn collect_breadcrumbs(
 ) {
     for node in nodes {
         match node {
-            NavNode::Page { label, slug } => {
+            NavNode::Page { label, slug, .. } => {
                 let mut trail = ancestors.to_vec();
                 trail.push(label.clone());
                 map.insert(slug.clone(), trail);
@@ -487,6 +668,7 @@ fn collect_breadcrumbs(
                 label,
                 slug,
                 children,
+                ..
             } => {
                 let mut trail = ancestors.to_vec();
                 trail.push(label.clone());
@@ -495,7 +677,7 @@ fn collect_breadcrumbs(
                 }
                 collect_breadcrumbs(children, &trail, map);
             }
-            NavNode::Separator { .. } => {}
+            NavNode::Link { .. } | NavNode::Separator { .. } => {}
         }
     }
 }
@@ -510,20 +692,21 @@ pub fn flatten_nav_pages(nodes: &[NavNode]) -> Vec<(String, String)> {
 fn collect_nav_pages(nodes: &[NavNode], out: &mut Vec<(String, String)>) {
     for node in nodes {
         match node {
-            NavNode::Page { label, slug } => {
+            NavNode::Page { label, slug, .. } => {
                 out.push((slug.clone(), label.clone()));
             }
             NavNode::Group {
                 label,
                 slug,
                 children,
+                ..
             } => {
                 if let Some(s) = slug {
                     out.push((s.clone(), label.clone()));
                 }
                 collect_nav_pages(children, out);
             }
-            NavNode::Separator { .. } => {}
+            NavNode::Link { .. } | NavNode::Separator { .. } => {}
         }
     }
 }
@@ -538,7 +721,7 @@ fn section_contains_active(node: &NavNode, current_slug: &str) -> bool {
                     .iter()
                     .any(|child| section_contains_active(child, current_slug))
         }
-        NavNode::Separator { .. } => false,
+        NavNode::Link { .. } | NavNode::Separator { .. } => false,
     }
 }
 
@@ -572,22 +755,41 @@ fn render_nav_node(
     let deep: String = " ".repeat(base + 4);
 
     match node {
-        NavNode::Page { label, slug } => {
+        NavNode::Page {
+            label,
+            slug,
+            decoration,
+        } => {
             let href = format!("{}{}.html", base_url, slug);
             let class = if slug == current_slug {
                 "nav-item active"
             } else {
                 "nav-item"
             };
+            let label = decorated_label(label, decoration);
             html.push_str(&format!(
                 "{indent}<li class=\"{class}\"><a href=\"{href}\">{label}</a></li>\n",
             ));
         }
+        NavNode::Link {
+            label,
+            url,
+            decoration,
+        } => {
+            let href = crate::util::html_escape(url);
+            let label = decorated_label(label, decoration);
+            let external = icons::svg("external", "nav-external-icon").unwrap_or_default();
+            html.push_str(&format!(
+                "{indent}<li class=\"nav-item nav-external\"><a href=\"{href}\" target=\"_blank\" rel=\"noopener noreferrer\">{label}{external}</a></li>\n",
+            ));
+        }
         NavNode::Group {
             label,
             slug,
             children,
+            decoration,
         } => {
+            let label = decorated_label(label, decoration);
             let is_open = section_contains_active(node, current_slug);
             let open_class = if is_open { " open" } else { "" };
             let aria = if is_open { "true" } else { "false" };
@@ -619,6 +821,95 @@ fn render_nav_node(
     }
 }
 
+/// Render a named menu (such as a header or footer menu) as a flat `<ul>`,
+/// with groups as nested lists. A link to a section's index page is also
+/// marked active on the section's other pages.
+pub fn render_menu(nodes: &[NavNode], current_slug: &str, base_url: &str) -> String {
+    if nodes.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from("<ul class=\"menu\">");
+    for node in nodes {
+        render_menu_node(node, current_slug, base_url, &mut html);
+    }
+    html.push_str("</ul>");
+    html
+}
+
+fn render_menu_node(node: &NavNode, current_slug: &str, base_url: &str, html: &mut String) {
+    let page_link = |label: &str, slug: &str, decoration: &NavDecoration| {
+        let href = format!("{}{}.html", base_url, slug);
+        let active = slug == current_slug
+            || slug
+                .strip_suffix("/index")
+                .and_then(|section| current_slug.strip_prefix(section))
+                .is_some_and(|rest| rest.starts_with('/'));
+        let class = if active { " class=\"active\"" } else { "" };
+        let label = decorated_label(label, decoration);
+        format!("<a href=\"{href}\"{class}>{label}</a>")
+    };
+    match node {
+        NavNode::Page {
+            label,
+            slug,
+            decoration,
+        } => {
+            html.push_str(&format!(
+                "<li class=\"menu-item\">{}</li>",
+                page_link(label, slug, decoration)
+            ));
+        }
+        NavNode::Link {
+            label,
+            url,
+            decoration,
+        } => {
+            let href = crate::util::html_escape(url);
+            let label = decorated_label(label, decoration);
+            html.push_str(&format!(
+                "<li class=\"menu-item menu-external\"><a href=\"{href}\" target=\"_blank\" rel=\"noopener noreferrer\">{label}</a></li>",
+            ));
+        }
+        NavNode::Group {
+            label,
+            slug,
+            children,
+            decoration,
+        } => {
+            let header = match slug {
+                Some(s) => page_link(label, s, decoration),
+                None => format!(
+                    "<span class=\"menu-group-label\">{}</span>",
+                    decorated_label(label, decoration)
+                ),
+            };
+            html.push_str(&format!("<li class=\"menu-group\">{header}<ul>"));
+            for child in children {
+                render_menu_node(child, current_slug, base_url, html);
+            }
+            html.push_str("</ul></li>");
+        }
+        NavNode::Separator { .. } => html.push_str("<li class=\"menu-separator\"></li>"),
+    }
+}
+
+/// A nav label with its icon before it and its badge after it.
+fn decorated_label(label: &str, decoration: &NavDecoration) -> String {
+    let icon = decoration
+        .icon
+        .as_deref()
+        .and_then(|name| icons::svg(name, "nav-icon"))
+        .unwrap_or_default();
+    let badge = decoration
+        .badge
+        .map(|badge| {
+            let badge = badge.as_str();
+            format!(" <span class=\"nav-badge nav-badge-{badge}\">{badge}</span>")
+        })
+        .unwrap_or_default();
+    format!("{icon}{label}{badge}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -729,6 +1020,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn withhold_page() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.md"), "# Home").unwrap();
+        fs::write(dir.path().join("draft.md"), "# Draft").unwrap();
+
+        let mut inv = PageInventory::scan(dir.path(), None, None, None).unwrap();
+        inv.update_slug("draft", "upcoming".to_string());
+        inv.withhold("upcoming");
+
+        assert_eq!(inv.ordered, ["index"]);
+        assert!(inv.resolve_link("upcoming").is_none());
+        assert!(inv.resolve_link("draft").is_none());
+        assert!(inv.unpublished.contains("upcoming"));
+    }
+
     #[test]
     fn resolve_link_with_alias() {
         let dir = tempfile::tempdir().unwrap();
@@ -761,6 +1068,151 @@ mod tests {
         assert!(tree.len() >= 2);
     }
 
+    #[test]
+    fn nav_tree_follows_nav_order_and_folder_meta() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("guides")).unwrap();
+        fs::create_dir_all(docs.join("reference")).unwrap();
+        fs::write(docs.join("about.md"), "# About").unwrap();
+        fs::write(docs.join("index.md"), "# Home").unwrap();
+        fs::write(docs.join("guides/advanced.md"), "# Advanced").unwrap();
+        fs::write(docs.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(docs.join("reference/cli.md"), "# CLI").unwrap();
+        fs::write(
+            docs.join("guides/_folder.toml"),
+            "label = \"User Guides\"\nweight = 2\n",
+        )
+        .unwrap();
+
+        let mut inv = PageInventory::scan(&docs, None, None, None).unwrap();
+        assert_eq!(inv.folders["guides"].order, Some(2));
+        inv.pages.get_mut("index").unwrap().nav_order = Some(1);
+        let setup = inv.pages.get_mut("guides/setup").unwrap();
+        setup.nav_order = Some(1);
+        setup.nav_label = Some("Installing".into());
+
+        let labels = |nodes: &[NavNode]| -> Vec<String> {
+            nodes
+                .iter()
+                .map(|node| match node {
+                    NavNode::Page { label, .. }
+                    | NavNode::Group { label, .. }
+                    | NavNode::Link { label, .. } => label.clone(),
+                    NavNode::Separator { .. } => String::new(),
+                })
+                .collect()
+        };
+
+        // Ordered siblings first, then the rest in path order
+        let tree = inv.nav_tree();
+        assert_eq!(labels(&tree), ["Home", "User Guides", "About", "Reference"]);
+        let NavNode::Group { children, .. } = &tree[1] else {
+            panic!("expected a group");
+        };
+        assert_eq!(labels(children), ["Installing", "Advanced"]);
+
+        let folder = inv.nav_tree_for_folder("guides/", None);
+        assert_eq!(labels(&folder), ["Installing", "Advanced"]);
+    }
+
+    #[test]
+    fn folder_meta_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("guides")).unwrap();
+        fs::write(docs.join("guides/_folder.toml"), "order = \"first\"\n").unwrap();
+
+        let err = PageInventory::scan(&docs, None, None, None).unwrap_err();
+        assert!(matches!(err, crate::error::Error::ConfigParse { .. }));
+    }
+
+    #[test]
+    fn nav_tree_skips_pages_hidden_from_nav() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(docs.join("legal")).unwrap();
+        fs::write(docs.join("index.md"), "# Home").unwrap();
+        fs::write(docs.join("legal/terms.md"), "# Terms").unwrap();
+        fs::write(docs.join("legal/privacy.md"), "# Privacy").unwrap();
+
+        let mut inv = PageInventory::scan(&docs, None, None, None).unwrap();
+        inv.pages.get_mut("legal/terms").unwrap().listing.nav = false;
+
+        let html = render_nav(&inv.nav_tree(), "index", "/");
+        assert!(html.contains("legal/privacy.html"));
+        assert!(!html.contains("legal/terms.html"));
+        let folder = inv.nav_tree_for_folder("legal", None);
+        assert_eq!(folder.len(), 1);
+        // Hidden pages are still part of the inventory
+        assert!(inv.pages.contains_key("legal/terms"));
+    }
+
+    #[test]
+    fn render_nav_links_icons_and_badges() {
+        let nodes = vec![
+            NavNode::Page {
+                label: "Setup".into(),
+                slug: "setup".into(),
+                decoration: NavDecoration {
+                    icon: Some("book".into()),
+                    badge: Some(Badge::New),
+                },
+            },
+            NavNode::Link {
+                label: "API Console".into(),
+                url: "https://example.com/console?a=1&b=2".into(),
+                decoration: NavDecoration {
+                    icon: None,
+                    badge: Some(Badge::Beta),
+                },
+            },
+        ];
+        let html = render_nav(&nodes, "setup", "/");
+        assert!(html.contains("<a href=\"/setup.html\"><svg class=\"nav-icon\""));
+        assert!(html.contains("Setup <span class=\"nav-badge nav-badge-new\">new</span></a>"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/console?a=1&amp;b=2\" target=\"_blank\" rel=\"noopener noreferrer\">API Console <span class=\"nav-badge nav-badge-beta\">beta</span><svg class=\"nav-external-icon\""
+        ));
+
+        // External links are not pages
+        assert_eq!(flatten_nav_pages(&nodes).len(), 1);
+        assert!(!build_breadcrumb_map(&nodes).contains_key("API Console"));
+    }
+
+    #[test]
+    fn render_menu_marks_active_section() {
+        let nodes = vec![
+            NavNode::Page {
+                label: "Guides".into(),
+                slug: "guides/index".into(),
+                decoration: NavDecoration::default(),
+            },
+            NavNode::Page {
+                label: "FAQ".into(),
+                slug: "faq".into(),
+                decoration: NavDecoration::default(),
+            },
+            NavNode::Link {
+                label: "GitHub".into(),
+                url: "https://github.com/example".into(),
+                decoration: NavDecoration::default(),
+            },
+        ];
+        let html = render_menu(&nodes, "guides/setup", "/");
+        assert!(html.starts_with("<ul class=\"menu\">"));
+        assert!(html.contains("<a href=\"/guides/index.html\" class=\"active\">Guides</a>"));
+        assert!(html.contains("<a href=\"/faq.html\">FAQ</a>"));
+        assert!(html.contains(
+            "<li class=\"menu-item menu-external\"><a href=\"https://github.com/example\" target=\"_blank\""
+        ));
+
+        // A page whose slug only shares a prefix is not in the section
+        let html = render_menu(&nodes, "guidesx/setup", "/");
+        assert!(!html.contains("class=\"active\""));
+        assert_eq!(render_menu(&[], "index", "/"), "");
+    }
+
     #[test]
     fn render_nav_collapsible_output() {
         let dir = tempfile::tempdir().unwrap();
@@ -793,10 +1245,12 @@ mod tests {
             NavNode::Page {
                 label: "Home".into(),
                 slug: "index".into(),
+                decoration: NavDecoration::default(),
             },
             NavNode::Page {
                 label: "About".into(),
                 slug: "about".into(),
+                decoration: NavDecoration::default(),
             },
         ];
         let map = build_breadcrumb_map(&nodes);
@@ -809,12 +1263,15 @@ mod tests {
         let nodes = vec![NavNode::Group {
             label: "Guides".into(),
             slug: None,
+            decoration: NavDecoration::default(),
             children: vec![NavNode::Group {
                 label: "Advanced".into(),
                 slug: None,
+                decoration: NavDecoration::default(),
                 children: vec![NavNode::Page {
                     label: "Setup".into(),
                     slug: "guides/advanced/setup".into(),
+                    decoration: NavDecoration::default(),
                 }],
             }],
         }];
@@ -830,9 +1287,11 @@ mod tests {
         let nodes = vec![NavNode::Group {
             label: "Guides".into(),
             slug: Some("guides/index".into()),
+            decoration: NavDecoration::default(),
             children: vec![NavNode::Page {
                 label: "Setup".into(),
                 slug: "guides/setup".into(),
+                decoration: NavDecoration::default(),
             }],
         }];
         let map = build_breadcrumb_map(&nodes);
@@ -849,6 +1308,7 @@ mod tests {
             NavNode::Page {
                 label: "Home".into(),
                 slug: "index".into(),
+                decoration: NavDecoration::default(),
             },
             NavNode::Separator { label: None },
         ];
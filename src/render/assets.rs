@@ -1,57 +1,89 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use crate::source::FileSource;
+
+/// List the static assets (custom CSS, `static/`, `assets/`) to copy into the
+/// output, as `(source path, output-relative destination)` pairs.
+///
+/// Pairs are returned in copy order: when two sources map to the same
+/// destination, the later one wins.
+pub fn list_assets(
+    files: &dyn FileSource,
+    project_root: &Path,
+    custom_css: Option<&str>,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut copies = Vec::new();
 
-/// Copy static assets (custom CSS, images, etc.) from the project to the output directory.
-pub fn copy_assets(project_root: &Path, output_dir: &Path, custom_css: Option<&str>) -> Result<()> {
     // Copy custom CSS if specified
     if let Some(css_path) = custom_css {
         let src = project_root.join(css_path);
-        if src.exists() {
-            let dest = output_dir.join(css_path);
-            if let Some(parent) = dest.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            std::fs::copy(&src, &dest)?;
+        if files.exists(&src) {
+            copies.push((src, PathBuf::from(css_path)));
         }
     }
 
     // Copy any files from a static/ directory if it exists
-    let static_dir = project_root.join("static");
-    if static_dir.exists() {
-        copy_dir_recursive(&static_dir, output_dir)?;
-    }
+    collect_dir(
+        files,
+        &project_root.join("static"),
+        Path::new(""),
+        &mut copies,
+    );
 
     // Copy assets/ directory (preserving it as a subdirectory)
-    let assets_dir = project_root.join("assets");
-    if assets_dir.exists() {
-        let dest = output_dir.join("assets");
-        std::fs::create_dir_all(&dest)?;
-        copy_dir_recursive(&assets_dir, &dest)?;
-    }
+    collect_dir(
+        files,
+        &project_root.join("assets"),
+        Path::new("assets"),
+        &mut copies,
+    );
 
-    Ok(())
+    copies
 }
 
-fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let path = entry.path();
+fn collect_dir(
+    files: &dyn FileSource,
+    src: &Path,
+    dest: &Path,
+    copies: &mut Vec<(PathBuf, PathBuf)>,
+) {
+    for path in files.walk(src) {
         let Ok(relative) = path.strip_prefix(src) else {
             crate::diagnostics::warn_unexpected_asset_path(&path);
             continue;
         };
         let target = dest.join(relative);
+        copies.push((path, target));
+    }
+}
 
-        if path.is_dir() {
-            std::fs::create_dir_all(&target)?;
-            copy_dir_recursive(&path, &dest.join(relative))?;
-        } else {
-            if let Some(parent) = target.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            std::fs::copy(&path, &target)?;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MemoryFiles;
+
+    #[test]
+    fn lists_custom_css_static_and_assets() {
+        let files = MemoryFiles::new()
+            .with("theme/custom.css", "body {}")
+            .with("static/CNAME", "docs.example.com")
+            .with("assets/img/logo.png", "png");
+
+        let copies = list_assets(&files, Path::new(""), Some("theme/custom.css"));
+        let dests: Vec<_> = copies.iter().map(|(_, dest)| dest.clone()).collect();
+        assert_eq!(
+            dests,
+            vec![
+                PathBuf::from("theme/custom.css"),
+                PathBuf::from("CNAME"),
+                PathBuf::from("assets/img/logo.png"),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_custom_css_is_skipped() {
+        let files = MemoryFiles::new();
+        assert!(list_assets(&files, Path::new(""), Some("missing.css")).is_empty());
     }
-    Ok(())
 }
@@ -0,0 +1,131 @@
+//! Built-in icons for navigation entries, drawn as inline SVG in the style of
+//! the default theme's other icons (24×24, stroked with `currentColor`).
+
+/// Icon names and their SVG contents, sorted by name.
+const ICONS: &[(&str, &str)] = &[
+    (
+        "alert",
+        r#"<path d="M10.29 3.86L1.82 18a2 2 0 0 0 1.71 3h16.94a2 2 0 0 0 1.71-3L13.71 3.86a2 2 0 0 0-3.42 0z"/><line x1="12" y1="9" x2="12" y2="13"/><line x1="12" y1="17" x2="12.01" y2="17"/>"#,
+    ),
+    (
+        "book",
+        r#"<path d="M4 19.5A2.5 2.5 0 0 1 6.5 17H20"/><path d="M6.5 2H20v20H6.5A2.5 2.5 0 0 1 4 19.5v-15A2.5 2.5 0 0 1 6.5 2z"/>"#,
+    ),
+    (
+        "code",
+        r#"<polyline points="16 18 22 12 16 6"/><polyline points="8 6 2 12 8 18"/>"#,
+    ),
+    (
+        "database",
+        r#"<ellipse cx="12" cy="5" rx="9" ry="3"/><path d="M21 12c0 1.66-4 3-9 3s-9-1.34-9-3"/><path d="M3 5v14c0 1.66 4 3 9 3s9-1.34 9-3V5"/>"#,
+    ),
+    (
+        "download",
+        r#"<path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4"/><polyline points="7 10 12 15 17 10"/><line x1="12" y1="15" x2="12" y2="3"/>"#,
+    ),
+    (
+        "external",
+        r#"<path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"/><polyline points="15 3 21 3 21 9"/><line x1="10" y1="14" x2="21" y2="3"/>"#,
+    ),
+    (
+        "file",
+        r#"<path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z"/><polyline points="14 2 14 8 20 8"/><line x1="16" y1="13" x2="8" y2="13"/><line x1="16" y1="17" x2="8" y2="17"/>"#,
+    ),
+    (
+        "folder",
+        r#"<path d="M22 19a2 2 0 0 1-2 2H4a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h5l2 3h9a2 2 0 0 1 2 2z"/>"#,
+    ),
+    (
+        "globe",
+        r#"<circle cx="12" cy="12" r="10"/><line x1="2" y1="12" x2="22" y2="12"/><path d="M12 2a15.3 15.3 0 0 1 4 10 15.3 15.3 0 0 1-4 10 15.3 15.3 0 0 1-4-10 15.3 15.3 0 0 1 4-10z"/>"#,
+    ),
+    (
+        "help",
+        r#"<circle cx="12" cy="12" r="10"/><path d="M9.09 9a3 3 0 0 1 5.83 1c0 2-3 3-3 3"/><line x1="12" y1="17" x2="12.01" y2="17"/>"#,
+    ),
+    (
+        "home",
+        r#"<path d="M3 9l9-7 9 7v11a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2z"/><polyline points="9 22 9 12 15 12 15 22"/>"#,
+    ),
+    (
+        "info",
+        r#"<circle cx="12" cy="12" r="10"/><line x1="12" y1="16" x2="12" y2="12"/><line x1="12" y1="8" x2="12.01" y2="8"/>"#,
+    ),
+    (
+        "key",
+        r#"<path d="M21 2l-2 2m-7.61 7.61a5.5 5.5 0 1 1-7.778 7.778 5.5 5.5 0 0 1 7.777-7.777zm0 0L15.5 7.5m0 0l3 3L22 7l-3-3m-3.5 3.5L19 4"/>"#,
+    ),
+    (
+        "layers",
+        r#"<polygon points="12 2 2 7 12 12 22 7 12 2"/><polyline points="2 17 12 22 22 17"/><polyline points="2 12 12 17 22 12"/>"#,
+    ),
+    (
+        "link",
+        r#"<path d="M10 13a5 5 0 0 0 7.54.54l3-3a5 5 0 0 0-7.07-7.07l-1.72 1.71"/><path d="M14 11a5 5 0 0 0-7.54-.54l-3 3a5 5 0 0 0 7.07 7.07l1.71-1.71"/>"#,
+    ),
+    (
+        "lock",
+        r#"<rect x="3" y="11" width="18" height="11" rx="2" ry="2"/><path d="M7 11V7a5 5 0 0 1 10 0v4"/>"#,
+    ),
+    ("play", r#"<polygon points="5 3 19 12 5 21 5 3"/>"#),
+    (
+        "search",
+        r#"<circle cx="11" cy="11" r="8"/><line x1="21" y1="21" x2="16.65" y2="16.65"/>"#,
+    ),
+    (
+        "star",
+        r#"<polygon points="12 2 15.09 8.26 22 9.27 17 14.14 18.18 21.02 12 17.77 5.82 21.02 7 14.14 2 9.27 8.91 8.26 12 2"/>"#,
+    ),
+    (
+        "terminal",
+        r#"<polyline points="4 17 10 11 4 5"/><line x1="12" y1="19" x2="20" y2="19"/>"#,
+    ),
+    (
+        "zap",
+        r#"<polygon points="13 2 3 14 12 14 11 22 21 10 12 10 13 2"/>"#,
+    ),
+];
+
+/// Names of every built-in icon, sorted.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    ICONS.iter().map(|(name, _)| *name)
+}
+
+/// Whether `name` is a built-in icon.
+pub fn exists(name: &str) -> bool {
+    ICONS.binary_search_by_key(&name, |(n, _)| n).is_ok()
+}
+
+/// The icon as an inline `<svg>` element with the given class, or `None` for
+/// an unknown name.
+pub fn svg(name: &str, class: &str) -> Option<String> {
+    let index = ICONS.binary_search_by_key(&name, |(n, _)| n).ok()?;
+    Some(format!(
+        "<svg class=\"{class}\" width=\"14\" height=\"14\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\" stroke-linecap=\"round\" stroke-linejoin=\"round\" aria-hidden=\"true\">{}</svg>",
+        ICONS[index].1
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_are_sorted() {
+        let names: Vec<_> = names().collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(exists("book"));
+        assert!(!exists("unicorn"));
+        let svg = svg("code", "nav-icon").unwrap();
+        assert!(svg.starts_with("<svg class=\"nav-icon\""));
+        assert!(svg.contains("<polyline points=\"16 18 22 12 16 6\"/>"));
+        assert_eq!(super::svg("unicorn", "nav-icon"), None);
+    }
+}
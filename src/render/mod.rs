@@ -1,2 +1,3 @@
 pub mod assets;
+pub mod icons;
 pub mod templates;
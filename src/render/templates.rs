@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use serde::Serialize;
 use tera::{Context, Tera};
 
@@ -5,7 +8,7 @@ use crate::config::ColorMode;
 use crate::error::{Error, Result};
 use crate::theme::Theme;
 
-/// A link to a previous or next page.
+/// A link to another page: the previous or next page, or a backlink.
 #[derive(Debug, Clone, Serialize)]
 pub struct PageLink {
     pub title: String,
@@ -36,24 +39,51 @@ pub struct LocaleInfo {
     pub has_page: bool,
 }
 
+/// The template that renders pages without a `layout` front matter field.
+pub const DEFAULT_LAYOUT: &str = "layout.html";
+
+/// The template name for a front matter `layout`: `landing` selects
+/// `landing.html`, while names with an extension (`blog/post.html`) are used as is.
+pub fn layout_template_name(layout: &str) -> String {
+    if Path::new(layout).extension().is_some() {
+        layout.to_string()
+    } else {
+        format!("{layout}.html")
+    }
+}
+
 /// Tera-based template renderer.
 pub struct TemplateRenderer {
     tera: Tera,
 }
 
 impl TemplateRenderer {
-    /// Create a new renderer from the resolved theme.
+    /// Create a new renderer from the resolved theme. `layout.html` and every
+    /// other template in `theme/templates/` are loaded together, so templates can
+    /// extend, include or import macros from each other.
     pub fn new(theme: &Theme) -> Result<Self> {
         let mut tera = Tera::default();
-        tera.add_raw_template("layout.html", &theme.layout_template)
+        let templates = std::iter::once((DEFAULT_LAYOUT, theme.layout_template.as_str())).chain(
+            theme
+                .templates
+                .iter()
+                .map(|(n, c)| (n.as_str(), c.as_str())),
+        );
+        tera.add_raw_templates(templates)
             .map_err(|e| Error::Render(format!("failed to parse template: {e}")))?;
         Ok(Self { tera })
     }
 
+    /// Whether a template called `name` was loaded.
+    pub fn has_template(&self, name: &str) -> bool {
+        self.tera.get_template_names().any(|t| t == name)
+    }
+
     /// Render a page with the given context values.
     pub fn render_page(&self, ctx: &PageContext) -> Result<String> {
         let mut context = Context::new();
         context.insert("page_title", &ctx.page_title);
+        context.insert("draft_notice", &ctx.draft_notice);
         context.insert("project_name", &ctx.project_name);
         context.insert("content", &ctx.content);
         context.insert("nav_html", &ctx.nav_html);
@@ -71,8 +101,12 @@ impl TemplateRenderer {
         context.insert("meta_description", &ctx.meta_description);
         context.insert("meta_author", &ctx.meta_author);
         context.insert("meta_date", &ctx.meta_date);
+        context.insert("noindex", &ctx.noindex);
+        context.insert("page", &serde_json::json!({ "meta": &ctx.page_meta }));
+        context.insert("menus", &ctx.menus);
         context.insert("prev_page", &ctx.prev_page);
         context.insert("next_page", &ctx.next_page);
+        context.insert("backlinks", &ctx.backlinks);
         context.insert("color_mode", &ctx.color_mode);
         context.insert("js_cachebust", &ctx.js_cachebust);
         context.insert("current_locale", &ctx.current_locale);
@@ -87,14 +121,19 @@ impl TemplateRenderer {
         context.insert("latest_version", &ctx.latest_version);
         context.insert("latest_version_url", &ctx.latest_version_url);
 
+        let layout = ctx.layout.as_deref().unwrap_or(DEFAULT_LAYOUT);
         self.tera
-            .render("layout.html", &context)
+            .render(layout, &context)
             .map_err(|e| Error::Render(format!("template render error: {e}")))
     }
 }
 
 /// All the data needed to render a single page.
 pub struct PageContext {
+    /// Template that renders the page, or [`DEFAULT_LAYOUT`] if `None`.
+    pub layout: Option<String>,
+    /// Banner text for a draft or scheduled page rendered by the dev server or `--drafts`.
+    pub draft_notice: Option<String>,
     pub page_title: String,
     pub project_name: String,
     pub content: String,
@@ -113,8 +152,16 @@ pub struct PageContext {
     pub meta_description: Option<String>,
     pub meta_author: Option<String>,
     pub meta_date: Option<String>,
+    /// Emit a `robots` meta tag asking search engines not to index the page.
+    pub noindex: bool,
+    /// Front matter keys DocAnvil doesn't use itself, exposed as `page.meta`.
+    pub page_meta: serde_json::Map<String, serde_json::Value>,
+    /// Rendered HTML for each `[[menus.<name>]]` menu in nav.toml, keyed by name.
+    pub menus: BTreeMap<String, String>,
     pub prev_page: Option<PageLink>,
     pub next_page: Option<PageLink>,
+    /// Pages of the same version and locale that link to this one.
+    pub backlinks: Vec<PageLink>,
     pub color_mode: ColorMode,
     pub js_cachebust: String,
     pub current_locale: Option<String>,
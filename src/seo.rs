@@ -19,7 +19,8 @@ pub fn generate_robots_txt(sitemap_url: Option<&str>) -> String {
     content
 }
 
-/// Generate a `sitemap.xml` file from the page inventory.
+/// Generate a `sitemap.xml` file from the page inventory, leaving out pages
+/// excluded from the sitemap in their front matter.
 /// Uses `site_url` for absolute URLs when available, otherwise falls back to
 /// `base_url` for relative paths. When `locale_config` is provided, emits
 /// `xhtml:link` hreflang annotations for multilingual pages.
@@ -29,7 +30,31 @@ pub fn generate_sitemap_xml(
     site_url: Option<&str>,
     locale_config: Option<&SitemapLocaleConfig>,
 ) -> String {
-    let has_i18n = locale_config.is_some();
+    let section = SitemapSection {
+        prefix: "",
+        inventory,
+        locale_config,
+    };
+    generate_sitemap_xml_sections(&[section], base_url, site_url)
+}
+
+/// One group of pages in a sitemap — a whole site, or one version of it.
+pub struct SitemapSection<'a> {
+    /// Output path prefix of the section's locale directories (e.g. `"v2/"`), used
+    /// for hreflang alternates. Empty for unversioned sites.
+    pub prefix: &'a str,
+    pub inventory: &'a PageInventory,
+    pub locale_config: Option<&'a SitemapLocaleConfig>,
+}
+
+/// Generate a single `sitemap.xml` covering several sections (e.g. every version).
+/// hreflang alternates only link pages within the same section.
+pub fn generate_sitemap_xml_sections(
+    sections: &[SitemapSection],
+    base_url: &str,
+    site_url: Option<&str>,
+) -> String {
+    let has_i18n = sections.iter().any(|s| s.locale_config.is_some());
     let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     if has_i18n {
         xml.push_str(
@@ -40,51 +65,48 @@ pub fn generate_sitemap_xml(
         xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
     }
 
-    for slug in &inventory.ordered {
-        let page = &inventory.pages[slug];
-        let path = page.output_path.to_string_lossy().replace('\\', "/");
+    let href = |path: &str| match site_url {
+        Some(url) => format!("{url}{path}"),
+        None => format!("{base_url}{path}"),
+    };
 
-        let loc = if let Some(url) = site_url {
-            format!("{url}{path}")
-        } else {
-            format!("{base_url}{path}")
-        };
+    for section in sections {
+        let prefix = section.prefix;
+        for slug in &section.inventory.ordered {
+            let page = &section.inventory.pages[slug];
+            if !page.listing.sitemap {
+                continue;
+            }
+            let path = page.output_path.to_string_lossy().replace('\\', "/");
+            let loc = href(&path);
+
+            xml.push_str(&format!("  <url>\n    <loc>{loc}</loc>\n"));
 
-        xml.push_str(&format!("  <url>\n    <loc>{loc}</loc>\n"));
-
-        // Emit hreflang alternates for i18n pages
-        if let Some(lc) = locale_config {
-            let base_slug = &page.slug;
-            if let Some(locales_with_page) = lc.slug_coverage.get(base_slug) {
-                for alt_locale in &lc.enabled {
-                    if locales_with_page.contains(alt_locale) {
-                        let alt_path = format!("{alt_locale}/{base_slug}.html");
-                        let alt_href = if let Some(url) = site_url {
-                            format!("{url}{alt_path}")
-                        } else {
-                            format!("{base_url}{alt_path}")
-                        };
+            // Emit hreflang alternates for i18n pages
+            if let Some(lc) = section.locale_config {
+                let base_slug = &page.slug;
+                if let Some(locales_with_page) = lc.slug_coverage.get(base_slug) {
+                    for alt_locale in &lc.enabled {
+                        if locales_with_page.contains(alt_locale) {
+                            let alt_href = href(&format!("{prefix}{alt_locale}/{base_slug}.html"));
+                            xml.push_str(&format!(
+                                "    <xhtml:link rel=\"alternate\" hreflang=\"{alt_locale}\" href=\"{alt_href}\"/>\n"
+                            ));
+                        }
+                    }
+                    // x-default points to the default locale's version (if it exists)
+                    if locales_with_page.contains(&lc.default_locale) {
+                        let default_href =
+                            href(&format!("{prefix}{}/{base_slug}.html", lc.default_locale));
                         xml.push_str(&format!(
-                            "    <xhtml:link rel=\"alternate\" hreflang=\"{alt_locale}\" href=\"{alt_href}\"/>\n"
+                            "    <xhtml:link rel=\"alternate\" hreflang=\"x-default\" href=\"{default_href}\"/>\n"
                         ));
                     }
                 }
-                // x-default points to the default locale's version (if it exists)
-                if locales_with_page.contains(&lc.default_locale) {
-                    let default_path = format!("{}/{base_slug}.html", lc.default_locale);
-                    let default_href = if let Some(url) = site_url {
-                        format!("{url}{default_path}")
-                    } else {
-                        format!("{base_url}{default_path}")
-                    };
-                    xml.push_str(&format!(
-                        "    <xhtml:link rel=\"alternate\" hreflang=\"x-default\" href=\"{default_href}\"/>\n"
-                    ));
-                }
             }
-        }
 
-        xml.push_str("  </url>\n");
+            xml.push_str("  </url>\n");
+        }
     }
 
     xml.push_str("</urlset>\n");
@@ -205,4 +227,34 @@ mod tests {
         assert!(!xml.contains("xmlns:xhtml="));
         assert!(!xml.contains("xhtml:link"));
     }
+
+    #[test]
+    fn sitemap_sections_prefix_hreflang_with_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs/v2");
+        fs::create_dir_all(&docs).unwrap();
+        fs::write(docs.join("index.en.md"), "# Home").unwrap();
+        fs::write(docs.join("index.fr.md"), "# Accueil").unwrap();
+
+        let locales = &["en".to_string(), "fr".to_string()];
+        let inv = PageInventory::scan(&docs, Some(locales), Some("en"), Some("v2")).unwrap();
+        let locale_config = SitemapLocaleConfig {
+            enabled: locales.to_vec(),
+            default_locale: "en".to_string(),
+            slug_coverage: inv.slug_locale_coverage(),
+        };
+        let section = SitemapSection {
+            prefix: "v2/",
+            inventory: &inv,
+            locale_config: Some(&locale_config),
+        };
+
+        let xml = generate_sitemap_xml_sections(&[section], "/", Some("https://example.com/"));
+
+        assert!(xml.contains("<loc>https://example.com/v2/fr/index.html</loc>"));
+        assert!(xml.contains("hreflang=\"fr\" href=\"https://example.com/v2/fr/index.html\""));
+        assert!(
+            xml.contains("hreflang=\"x-default\" href=\"https://example.com/v2/en/index.html\"")
+        );
+    }
 }
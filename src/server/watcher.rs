@@ -1,5 +1,6 @@
-use std::path::Path;
-use std::time::Duration;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use notify_debouncer_mini::{DebouncedEventKind, new_debouncer};
 use tokio::sync::broadcast;
@@ -13,9 +14,11 @@ pub fn watch(tx: broadcast::Sender<()>, project_root: &Path) -> crate::error::Re
 
     // Watch the docs/ and theme/ directories
     let watch_dirs = ["docs", "theme", "assets", "docanvil.toml", "nav.toml"];
+    let mut watched_paths = Vec::new();
     for dir in &watch_dirs {
         let path = project_root.join(dir);
         if path.exists() {
+            watched_paths.extend(path.canonicalize());
             let mode = if path.is_dir() {
                 notify::RecursiveMode::Recursive
             } else {
@@ -25,17 +28,43 @@ pub fn watch(tx: broadcast::Sender<()>, project_root: &Path) -> crate::error::Re
         }
     }
 
+    // Files pages include or quote from can live anywhere, so watch the
+    // directories of those the last build recorded. Only changes to the
+    // files themselves trigger a rebuild.
+    let mut dependency_dirs = HashSet::new();
+    let mut dependencies =
+        watch_dependencies(debouncer.watcher(), project_root, &mut dependency_dirs);
+
     eprintln!("Watching for changes...");
 
+    // Reading a file during a build is reported as an event too, so only
+    // files modified (or removed) since the last build count as changes.
+    let mut last_build = SystemTime::now();
+
     loop {
         match notify_rx.recv() {
             Ok(Ok(events)) => {
-                let has_changes = events.iter().any(|e| e.kind == DebouncedEventKind::Any);
+                let has_changes = events.iter().any(|e| {
+                    e.kind == DebouncedEventKind::Any
+                        && modified_since(&e.path, last_build)
+                        && canonical(&e.path).is_none_or(|path| {
+                            dependencies.contains(&path)
+                                || watched_paths
+                                    .iter()
+                                    .any(|watched| path.starts_with(watched))
+                        })
+                });
 
                 if has_changes {
                     eprintln!("Change detected, rebuilding...");
+                    last_build = SystemTime::now();
                     match crate::cli::build::run_with_options(project_root, true) {
                         Ok(()) => {
+                            dependencies = watch_dependencies(
+                                debouncer.watcher(),
+                                project_root,
+                                &mut dependency_dirs,
+                            );
                             let _ = tx.send(());
                         }
                         Err(e) => {
@@ -53,3 +82,41 @@ pub fn watch(tx: broadcast::Sender<()>, project_root: &Path) -> crate::error::Re
 
     Ok(())
 }
+
+/// Watch the directories of the dependencies recorded in the build cache, adding
+/// to those already in `watched_dirs`, and return the dependencies.
+fn watch_dependencies(
+    watcher: &mut dyn notify::Watcher,
+    project_root: &Path,
+    watched_dirs: &mut HashSet<PathBuf>,
+) -> BTreeSet<PathBuf> {
+    let dependencies: BTreeSet<PathBuf> = crate::cache::dependency_paths(project_root)
+        .iter()
+        .filter_map(|path| canonical(path))
+        .collect();
+    for path in &dependencies {
+        let Some(dir) = path.parent() else { continue };
+        if watched_dirs.insert(dir.to_path_buf()) {
+            let _ = watcher.watch(dir, notify::RecursiveMode::NonRecursive);
+        }
+    }
+    dependencies
+}
+
+/// Whether `path` was modified after `time` or no longer exists.
+fn modified_since(path: &Path, time: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_or(true, |modified| modified > time)
+}
+
+/// `path` with its directory resolved, so paths reported by the watcher compare
+/// equal to recorded ones. The file itself may no longer exist; `None` when
+/// its directory doesn't either.
+fn canonical(path: &Path) -> Option<PathBuf> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Some(dir.canonicalize().ok()?.join(path.file_name()?))
+}
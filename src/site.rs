@@ -0,0 +1,1706 @@
+//! Build a documentation site from Rust.
+//!
+//! [`Site`] is the engine behind `docanvil build` and `docanvil serve`. Embedders
+//! can give it an in-memory [`Config`], a virtual file tree and their own
+//! components, and get the rendered pages back without touching the disk:
+//!
+//! ```
+//! use docanvil::config::Config;
+//! use docanvil::site::Site;
+//! use docanvil::source::MemoryFiles;
+//!
+//! let files = MemoryFiles::new()
+//!     .with("docs/index.md", "# Home\n\nSee [[guide]].")
+//!     .with("docs/guide.md", "# Guide");
+//!
+//! let output = Site::builder(Config::default())
+//!     .files(files)
+//!     .build()
+//!     .unwrap()
+//!     .render()
+//!     .unwrap();
+//!
+//! let home = output.page("index.html").unwrap();
+//! assert!(home.html.contains("guide.html"));
+//! for warning in &output.diagnostics {
+//!     eprintln!("{}: {}", warning.message, warning.hint);
+//! }
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::cache::{self, BuildCache};
+use crate::components::{ComponentRegistry, templates};
+use crate::config::{Config, FieldRule};
+use crate::diagnostics::{self, Warning};
+use crate::error::{Error, Result};
+use crate::nav;
+use crate::pipeline;
+use crate::pipeline::backlinks::Backlinks;
+use crate::pipeline::frontmatter::{self, FrontMatter, Unpublished};
+use crate::pipeline::syntax::SyntaxHighlighter;
+use crate::plugins::{Plugins, wasm};
+use crate::project::{self, NavNode, PageInfo, PageInventory};
+use crate::render::assets;
+use crate::render::templates::{
+    self as page_templates, LocaleInfo, PageContext, PageLink, TemplateRenderer, VersionInfo,
+};
+use crate::search::{self, SearchSection};
+use crate::seo;
+use crate::source::{DiskFiles, FileSource};
+use crate::theme::Theme;
+use crate::util;
+
+/// Wrap an IO error with the file path that caused it.
+fn io_context(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |e| Error::General(format!("{}: {e}", path.display()))
+}
+
+/// A documentation project ready to be built. Create one with [`Site::builder`].
+pub struct Site {
+    config: Config,
+    root: PathBuf,
+    files: Box<dyn FileSource>,
+    registry: ComponentRegistry,
+    plugins: Plugins,
+    live_reload: bool,
+    drafts: bool,
+    profile: Option<String>,
+    jobs: usize,
+}
+
+/// Configures a [`Site`].
+pub struct Builder {
+    config: Config,
+    root: PathBuf,
+    files: Box<dyn FileSource>,
+    registry: ComponentRegistry,
+    live_reload: bool,
+    drafts: bool,
+    profile: Option<String>,
+    jobs: usize,
+}
+
+impl Builder {
+    /// Project root that content, nav, theme and asset paths are resolved
+    /// against. Defaults to the current directory.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Where project files are read from. Defaults to the local filesystem.
+    pub fn files(mut self, files: impl FileSource + 'static) -> Self {
+        self.files = Box::new(files);
+        self
+    }
+
+    /// Components available to `:::name` directives. Defaults to the built-ins.
+    /// WebAssembly components in `plugins/` and components from `[[plugins]]`
+    /// in the config are added on top, and `theme/components/` templates render
+    /// any other directive.
+    pub fn components(mut self, registry: ComponentRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Inject the dev server's live-reload script and serve from `/`.
+    pub fn live_reload(mut self, live_reload: bool) -> Self {
+        self.live_reload = live_reload;
+        self
+    }
+
+    /// Render draft and scheduled pages with a banner instead of leaving them
+    /// out. Always on with [`live_reload`](Self::live_reload).
+    pub fn drafts(mut self, drafts: bool) -> Self {
+        self.drafts = drafts;
+        self
+    }
+
+    /// Build profile that `:::only{profile="..."}` blocks are matched against.
+    /// Defaults to none, which leaves out every block that names a profile.
+    pub fn profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Maximum number of rendering threads. Defaults to one per CPU.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Finish configuring the site, loading its component templates and
+    /// WebAssembly components.
+    pub fn build(mut self) -> Result<Site> {
+        let templates_dir = self.root.join(templates::TEMPLATES_DIR);
+        self.registry
+            .load_templates(self.files.as_ref(), &templates_dir)?;
+        let plugins_dir = self.root.join(wasm::PLUGINS_DIR);
+        self.registry
+            .register_wasm_plugins(self.files.as_ref(), &plugins_dir)?;
+        let plugins = Plugins::new(&self.config.plugins, &self.root);
+        plugins.register_components(&mut self.registry);
+        Ok(Site {
+            config: self.config,
+            root: self.root,
+            files: self.files,
+            registry: self.registry,
+            plugins,
+            live_reload: self.live_reload,
+            drafts: self.drafts || self.live_reload,
+            profile: self.profile,
+            jobs: self.jobs,
+        })
+    }
+}
+
+/// A rendered documentation page.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// Page slug without locale or version, e.g. `guides/setup`.
+    pub slug: String,
+    pub title: String,
+    pub locale: Option<String>,
+    pub version: Option<String>,
+    /// Path relative to the output directory, e.g. `v2/fr/guides/setup.html`.
+    pub output_path: PathBuf,
+    pub html: String,
+}
+
+/// Everything produced by [`Site::render`].
+#[derive(Debug, Default)]
+pub struct SiteOutput {
+    /// Content pages in render order.
+    pub pages: Vec<Page>,
+    /// Every other output file (404 page, redirects, search indexes, sitemap,
+    /// scripts and assets), keyed by path relative to the output directory.
+    pub files: BTreeMap<PathBuf, Vec<u8>>,
+    /// Warnings raised while building.
+    pub diagnostics: Vec<Warning>,
+}
+
+impl SiteOutput {
+    /// Look up a rendered page by its output path.
+    pub fn page(&self, output_path: impl AsRef<Path>) -> Option<&Page> {
+        let output_path = output_path.as_ref();
+        self.pages.iter().find(|p| p.output_path == output_path)
+    }
+}
+
+impl Site {
+    /// Start configuring a site for `config`.
+    pub fn builder(config: Config) -> Builder {
+        Builder {
+            config,
+            root: PathBuf::from("."),
+            files: Box::new(DiskFiles),
+            registry: ComponentRegistry::with_builtins(),
+            live_reload: false,
+            drafts: false,
+            profile: None,
+            jobs: util::default_jobs(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Build the site in memory. Warnings are returned in
+    /// [`SiteOutput::diagnostics`] instead of being printed. Plugin
+    /// `post-build` hooks are not run, as nothing is written to disk.
+    pub fn render(&self) -> Result<SiteOutput> {
+        let (result, warnings) = diagnostics::capture(|| {
+            let mut output = MemoryOutput::default();
+            build_site(self, &mut output, |_| BuildCache::disabled()).map(|_| output)
+        });
+        let output = result?;
+        Ok(SiteOutput {
+            pages: output.pages,
+            files: output.files,
+            diagnostics: warnings,
+        })
+    }
+
+    /// Build the site into `output_dir`, rendering every page. Warnings are
+    /// printed to stderr.
+    pub fn write_to(&self, output_dir: &Path) -> Result<BuildStats> {
+        let mut output = DiskOutput { dir: output_dir };
+        let stats = build_site(self, &mut output, |_| BuildCache::disabled())?;
+        self.plugins.post_build(output_dir)?;
+        Ok(stats)
+    }
+
+    /// Like [`write_to`](Self::write_to), but skips pages whose inputs are
+    /// unchanged since the last build recorded in `.docanvil-cache/`. When
+    /// `read_cache` is `false`, every page is re-rendered and the cache is
+    /// rewritten.
+    pub(crate) fn write_incremental(
+        &self,
+        output_dir: &Path,
+        read_cache: bool,
+    ) -> Result<BuildStats> {
+        let mut output = DiskOutput { dir: output_dir };
+        let stats = build_site(self, &mut output, |theme| {
+            let fingerprint = site_fingerprint(self, output_dir, theme);
+            BuildCache::load(&self.root, fingerprint, read_cache)
+        })?;
+        self.plugins.post_build(output_dir)?;
+        Ok(stats)
+    }
+}
+
+/// Destination of a build. Paths are relative to the output directory.
+trait Output {
+    fn write(&mut self, path: &Path, contents: Vec<u8>) -> Result<()>;
+
+    fn write_page(&mut self, page: Page) -> Result<()> {
+        self.write(&page.output_path, page.html.into_bytes())
+    }
+
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Remove a file left by an earlier build, if there is one.
+    fn remove(&mut self, path: &Path) -> Result<()>;
+}
+
+/// Writes the build to a directory on disk.
+struct DiskOutput<'a> {
+    dir: &'a Path,
+}
+
+impl Output for DiskOutput<'_> {
+    fn write(&mut self, path: &Path, contents: Vec<u8>) -> Result<()> {
+        let path = self.dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, contents).map_err(io_context(&path))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.dir.join(path).exists()
+    }
+
+    fn remove(&mut self, path: &Path) -> Result<()> {
+        let path = self.dir.join(path);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_context(&path)(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Collects the build in memory for [`Site::render`].
+#[derive(Default)]
+struct MemoryOutput {
+    pages: Vec<Page>,
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl Output for MemoryOutput {
+    fn write(&mut self, path: &Path, contents: Vec<u8>) -> Result<()> {
+        self.files.insert(path.to_path_buf(), contents);
+        Ok(())
+    }
+
+    fn write_page(&mut self, page: Page) -> Result<()> {
+        self.pages.push(page);
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.pages.iter().any(|p| p.output_path == path)
+    }
+
+    fn remove(&mut self, path: &Path) -> Result<()> {
+        self.files.remove(path);
+        self.pages.retain(|p| p.output_path != path);
+        Ok(())
+    }
+}
+
+/// Page counts reported at the end of a build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildStats {
+    /// Total pages in the site, including those reused from the cache.
+    pub pages: usize,
+    /// Pages whose inputs were unchanged and were not re-rendered.
+    pub reused: usize,
+    /// Draft and scheduled pages: rendered with a banner when drafts are
+    /// included, left out of the site otherwise.
+    pub drafts: usize,
+    pub drafts_included: bool,
+}
+
+impl BuildStats {
+    /// ` (N unchanged)` when pages were reused, for the build summary line.
+    pub fn unchanged_note(&self) -> String {
+        if self.reused == 0 {
+            String::new()
+        } else {
+            format!(" ({} unchanged)", self.reused)
+        }
+    }
+
+    /// ` (N drafts skipped)` or ` (N drafts included)`, for the build summary line.
+    pub fn drafts_note(&self) -> String {
+        let plural = if self.drafts == 1 { "" } else { "s" };
+        match (self.drafts, self.drafts_included) {
+            (0, _) => String::new(),
+            (n, true) => format!(" ({n} draft{plural} included)"),
+            (n, false) => format!(" ({n} draft{plural} skipped)"),
+        }
+    }
+}
+
+/// The output of rendering a single page.
+struct RenderedPage {
+    html: String,
+    sections: Vec<SearchSection>,
+}
+
+/// Renders pages across threads and writes them out in inventory order.
+struct PageWriter<'a> {
+    output: &'a mut dyn Output,
+    jobs: usize,
+    cache: BuildCache,
+    count: usize,
+}
+
+impl PageWriter<'_> {
+    /// Render and write the pages at `keys`, appending their search sections.
+    ///
+    /// Pages whose inputs, including the pages linking to them in `backlinks`,
+    /// are unchanged are taken from the build cache; the rest
+    /// are rendered by `render` on up to `jobs` threads. Files, warnings and
+    /// search sections are then handled in key order, so the output is identical
+    /// to a serial build.
+    #[allow(clippy::too_many_arguments)]
+    fn write(
+        &mut self,
+        keys: &[String],
+        target: RenderTarget,
+        inventory: &PageInventory,
+        sources: &HashMap<String, String>,
+        inventory_hash: u64,
+        backlinks: &Backlinks,
+        search_entries: &mut Option<Vec<SearchSection>>,
+        render: impl Fn(&str) -> Result<RenderedPage> + Sync,
+    ) -> Result<()> {
+        let mut lookups = Vec::with_capacity(keys.len());
+        let mut misses = Vec::new();
+        for key in keys {
+            let page = &inventory.pages[key];
+            let input_hash = cache::page_hash(
+                &sources[key],
+                backlinks.to(&page.source_path),
+                inventory_hash,
+            );
+            let output = &*self.output;
+            let hit = self.cache.lookup(&page.output_path, input_hash, || {
+                output.exists(&page.output_path)
+            });
+            if hit.is_none() {
+                misses.push(key.as_str());
+            }
+            lookups.push((input_hash, hit));
+        }
+
+        let mut rendered = util::parallel_map(&misses, self.jobs, |key| {
+            diagnostics::capture(|| {
+                pipeline::popovers::reset_popover_ids();
+                pipeline::includes::track(|| render(key))
+            })
+        })
+        .into_iter();
+
+        for (key, (input_hash, hit)) in keys.iter().zip(lookups) {
+            let sections = match hit {
+                Some(sections) => sections,
+                None => {
+                    let ((result, included), warnings) =
+                        rendered.next().expect("one result per miss");
+                    diagnostics::replay(&warnings);
+                    let page_out = result?;
+
+                    let page = &inventory.pages[key];
+                    self.output.write_page(Page {
+                        slug: page.slug.clone(),
+                        title: page.title.clone(),
+                        locale: target.locale.map(String::from),
+                        version: target.version.map(String::from),
+                        output_path: page.output_path.clone(),
+                        html: page_out.html,
+                    })?;
+
+                    // Pages that emitted warnings are re-rendered next time so the
+                    // warnings are reported again.
+                    if warnings.is_empty() {
+                        self.cache.store(
+                            &page.output_path,
+                            input_hash,
+                            &page_out.sections,
+                            &included,
+                        );
+                    } else {
+                        self.cache.store_dependencies(&included);
+                    }
+                    page_out.sections
+                }
+            };
+            self.count += 1;
+            if let Some(entries) = search_entries {
+                entries.extend(sections);
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the build cache and report page counts.
+    fn finish(self) -> Result<BuildStats> {
+        let reused = self.cache.reused();
+        self.cache.save()?;
+        Ok(BuildStats {
+            pages: self.count,
+            reused,
+            drafts: 0,
+            drafts_included: false,
+        })
+    }
+}
+
+/// One content tree — the whole site, or a single version of it — after the
+/// front matter pre-pass.
+struct SiteContent {
+    inventory: PageInventory,
+    sources: HashMap<String, String>,
+    front_matters: HashMap<String, FrontMatter>,
+    /// Banners for draft and scheduled pages that are rendered, keyed like `sources`.
+    draft_notices: HashMap<String, String>,
+    /// Output paths of draft and scheduled pages that are left out, so files
+    /// an earlier `--drafts` build or `serve` wrote for them can be removed.
+    withheld: Vec<PathBuf>,
+}
+
+impl SiteContent {
+    /// Scan `dir`, read every page and apply front matter title and slug overrides
+    /// before nav and search are built. Front matter breaking `schema` is reported
+    /// as a warning. Draft pages and pages scheduled after `now` are withheld
+    /// from the inventory unless `drafts` is set.
+    #[allow(clippy::too_many_arguments)]
+    fn load(
+        files: &dyn FileSource,
+        dir: &Path,
+        enabled_locales: Option<&[String]>,
+        default_locale: Option<&str>,
+        version: Option<&str>,
+        schema: &BTreeMap<String, FieldRule>,
+        drafts: bool,
+        now: i64,
+    ) -> Result<Self> {
+        let mut inventory =
+            PageInventory::scan_files(files, dir, enabled_locales, default_locale, version)?;
+
+        let mut sources: HashMap<String, String> = HashMap::new();
+        let mut front_matters: HashMap<String, FrontMatter> = HashMap::new();
+        let mut slug_updates: Vec<(String, String)> = Vec::new();
+        let mut unpublished: Vec<(String, Unpublished)> = Vec::new();
+
+        for slug in &inventory.ordered {
+            let page = &inventory.pages[slug];
+            let source = files
+                .read_to_string(&page.source_path)
+                .map_err(io_context(&page.source_path))?;
+            let fm = frontmatter::extract(&source, &page.source_path);
+            let relative = page
+                .source_path
+                .strip_prefix(dir)
+                .unwrap_or(&page.source_path);
+            for violation in frontmatter::check_schema(&source, &fm, schema, relative) {
+                diagnostics::warn_frontmatter_schema(&page.source_path, &violation);
+            }
+            if let Some(page) = inventory.pages.get_mut(slug) {
+                if let Some(ref title) = fm.title {
+                    page.title = title.clone();
+                }
+                page.listing = fm.listing();
+                page.nav_label = fm.nav_label.clone();
+                page.nav_order = fm.nav_order;
+            }
+
+            // Determine slug override: explicit slug field takes priority, then title-derived.
+            // Skip title-derived slugs for "index" pages (well-known convention).
+            let current_basename = slug.rsplit('/').next().unwrap_or(slug);
+            let new_slug = if let Some(ref s) = fm.slug {
+                Some(slug::slugify(s))
+            } else if let Some(ref title) = fm.title
+                && current_basename != "index"
+            {
+                Some(slug::slugify(title))
+            } else {
+                None
+            };
+
+            // Only update if the slug actually changes (compare against filename portion)
+            if let Some(new_slug) = new_slug
+                && new_slug != current_basename
+            {
+                slug_updates.push((slug.clone(), new_slug));
+            }
+
+            if let Some(reason) = fm.unpublished(now) {
+                unpublished.push((slug.clone(), reason));
+            }
+            sources.insert(slug.clone(), source);
+            front_matters.insert(slug.clone(), fm);
+        }
+
+        // Apply slug updates after the loop to avoid mutating while iterating.
+        for (old_slug, new_slug) in slug_updates {
+            // Re-key source and front matter entries
+            if let Some(source) = sources.remove(&old_slug) {
+                let fm = front_matters.remove(&old_slug).unwrap_or_default();
+                inventory.update_slug(&old_slug, new_slug);
+                // Find the new full slug (with directory prefix preserved)
+                let full_new_slug = inventory
+                    .slug_aliases
+                    .get(&old_slug)
+                    .cloned()
+                    .unwrap_or(old_slug);
+                sources.insert(full_new_slug.clone(), source);
+                front_matters.insert(full_new_slug, fm);
+            }
+        }
+
+        // Withhold unpublished pages under their final slugs, so nav entries
+        // naming them are recognised
+        let mut draft_notices = HashMap::new();
+        let mut withheld = Vec::new();
+        for (slug, reason) in unpublished {
+            let key = inventory.slug_aliases.get(&slug).cloned().unwrap_or(slug);
+            if drafts {
+                draft_notices.insert(key, reason.to_string());
+            } else {
+                withheld.extend(inventory.withhold(&key).map(|page| page.output_path));
+                sources.remove(&key);
+                front_matters.remove(&key);
+            }
+        }
+
+        Ok(Self {
+            inventory,
+            sources,
+            front_matters,
+            draft_notices,
+            withheld,
+        })
+    }
+}
+
+/// One cell of the render matrix: the pages of a single version and locale,
+/// sharing a nav tree, URL prefix and search index. Unversioned and
+/// single-language sites are the `None` cases of each dimension.
+#[derive(Clone, Copy, PartialEq)]
+struct RenderTarget<'a> {
+    version: Option<&'a str>,
+    locale: Option<&'a str>,
+}
+
+impl RenderTarget<'_> {
+    /// Output path prefix shared by the target's pages, e.g. `v2/fr/`.
+    fn prefix(&self) -> String {
+        let mut prefix = String::new();
+        for part in [self.version, self.locale].into_iter().flatten() {
+            prefix.push_str(part);
+            prefix.push('/');
+        }
+        prefix
+    }
+
+    /// Load this target's navigation from the most specific nav file. Without a
+    /// nav file or `[[nav]]` entries, the sidebar follows the inventory's
+    /// directory structure.
+    fn nav(
+        &self,
+        files: &dyn FileSource,
+        project_root: &Path,
+        inventory: &PageInventory,
+        base_url: &str,
+    ) -> Result<TargetNav> {
+        let discovered = || match self.locale {
+            Some(locale) => inventory.nav_tree_for_locale(locale),
+            None => inventory.nav_tree(),
+        };
+        let Some(path) = nav::find_nav_file(files, project_root, self.version, self.locale) else {
+            return Ok(TargetNav {
+                sidebar: Sidebar::new(discovered(), base_url),
+                sections: BTreeMap::new(),
+                menus: BTreeMap::new(),
+            });
+        };
+        let content = files.read_to_string(&path).map_err(io_context(&path))?;
+        let config = nav::parse_nav_config(&content, &path)?;
+
+        // Nav warnings point at the nav file that was loaded.
+        Ok(diagnostics::in_file(&path, &content, || {
+            nav::validate_config(&config, inventory, self.locale);
+            let tree = |entries: &[nav::NavEntry]| {
+                nav::nav_tree_from_entries(entries, inventory, self.locale)
+            };
+            TargetNav {
+                sidebar: Sidebar::new(
+                    config.nav.as_deref().map(tree).unwrap_or_else(discovered),
+                    base_url,
+                ),
+                sections: config
+                    .sidebars
+                    .iter()
+                    .map(|(section, entries)| {
+                        (section.clone(), Sidebar::new(tree(entries), base_url))
+                    })
+                    .collect(),
+                menus: config
+                    .menus
+                    .iter()
+                    .map(|(name, entries)| (name.clone(), tree(entries)))
+                    .collect(),
+            }
+        }))
+    }
+}
+
+/// A render target's navigation: the main sidebar, sidebars for top-level
+/// sections and named menus.
+struct TargetNav {
+    sidebar: Sidebar,
+    sections: BTreeMap<String, Sidebar>,
+    menus: BTreeMap<String, Vec<NavNode>>,
+}
+
+impl TargetNav {
+    /// The sidebar for the page at `slug`: its top-level section's, if that
+    /// section has one, otherwise the main sidebar.
+    fn sidebar_for(&self, slug: &str) -> &Sidebar {
+        slug.split_once('/')
+            .and_then(|(section, _)| self.sections.get(section))
+            .unwrap_or(&self.sidebar)
+    }
+
+    /// Every menu rendered for the page at `slug`, keyed by menu name.
+    fn menus_html(&self, slug: &str, base_url: &str) -> BTreeMap<String, String> {
+        self.menus
+            .iter()
+            .map(|(name, nodes)| (name.clone(), project::render_menu(nodes, slug, base_url)))
+            .collect()
+    }
+}
+
+/// A sidebar tree with the breadcrumb trails and previous/next links that follow it.
+struct Sidebar {
+    tree: Vec<NavNode>,
+    breadcrumbs: HashMap<String, Vec<String>>,
+    prev_next: HashMap<String, (Option<PageLink>, Option<PageLink>)>,
+}
+
+impl Sidebar {
+    fn new(tree: Vec<NavNode>, base_url: &str) -> Self {
+        Self {
+            breadcrumbs: project::build_breadcrumb_map(&tree),
+            prev_next: build_prev_next_map(&tree, base_url),
+            tree,
+        }
+    }
+}
+
+/// Per-build values shared by every page's template context.
+struct SiteShell<'a> {
+    config: &'a Config,
+    theme: &'a Theme,
+    base_url: String,
+    logo_path: Option<String>,
+    favicon_path: Option<String>,
+    live_reload: bool,
+    js_cachebust: String,
+}
+
+impl SiteShell<'_> {
+    /// A template context for `content`, with every page-specific field empty.
+    fn context(
+        &self,
+        page_title: String,
+        content: String,
+        nav_html: String,
+        search_index_url: String,
+    ) -> PageContext {
+        PageContext {
+            layout: None,
+            draft_notice: None,
+            page_title,
+            project_name: self.config.project.name.clone(),
+            content,
+            nav_html,
+            default_css: self.theme.default_css.clone(),
+            css_overrides: self.theme.css_overrides.clone(),
+            custom_css_path: self.theme.custom_css_path.clone(),
+            custom_css: self.theme.custom_css.clone(),
+            base_url: self.base_url.clone(),
+            logo_path: self.logo_path.clone(),
+            favicon_path: self.favicon_path.clone(),
+            live_reload: self.live_reload,
+            mermaid_enabled: false,
+            mermaid_version: String::new(),
+            search_enabled: self.config.search.enabled,
+            meta_description: None,
+            meta_author: None,
+            meta_date: None,
+            noindex: false,
+            page_meta: Default::default(),
+            menus: BTreeMap::new(),
+            prev_page: None,
+            next_page: None,
+            backlinks: Vec::new(),
+            color_mode: self.config.theme.color_mode.clone(),
+            js_cachebust: self.js_cachebust.clone(),
+            current_locale: None,
+            current_flag: None,
+            available_locales: Vec::new(),
+            locale_auto_detect: false,
+            canonical_url: None,
+            x_default_url: None,
+            search_index_url,
+            current_version: None,
+            available_versions: Vec::new(),
+            latest_version: None,
+            latest_version_url: None,
+        }
+    }
+}
+
+/// Core build logic shared by every way of building a [`Site`].
+///
+/// Every build is a render matrix of version × locale targets; an unversioned,
+/// single-language site is a single target. `cache` creates the build cache
+/// once the theme is resolved.
+fn build_site(
+    site: &Site,
+    output: &mut dyn Output,
+    cache: impl FnOnce(&Theme) -> BuildCache,
+) -> Result<BuildStats> {
+    let Site {
+        config,
+        root,
+        files,
+        registry,
+        plugins,
+        live_reload,
+        drafts,
+        profile,
+        jobs,
+    } = site;
+    let (project_root, files, live_reload) = (root.as_path(), files.as_ref(), *live_reload);
+
+    let content_dir = project_root.join(&config.project.content_dir);
+    if !files.exists(&content_dir) {
+        return Err(Error::ContentDirNotFound(content_dir));
+    }
+
+    // Resolve theme and create template renderer
+    let theme = Theme::resolve_from(config, project_root, files);
+    let renderer = TemplateRenderer::new(&theme)?;
+
+    // The two dimensions of the render matrix.
+    let versions: Vec<Option<&str>> = if config.is_versioning_enabled() {
+        config
+            .version
+            .enabled
+            .iter()
+            .map(|v| Some(v.as_str()))
+            .collect()
+    } else {
+        vec![None]
+    };
+    let locales: Vec<Option<&str>> = if config.is_i18n_enabled() {
+        config
+            .locale
+            .enabled
+            .iter()
+            .map(|l| Some(l.as_str()))
+            .collect()
+    } else {
+        vec![None]
+    };
+    let enabled_locales = config
+        .is_i18n_enabled()
+        .then_some(config.locale.enabled.as_slice());
+
+    // Each version lives in its own subdirectory of content_dir (e.g. docs/v2/).
+    // All versions are loaded up front so the version switcher knows which pages
+    // exist in each one.
+    let mut contents: Vec<SiteContent> = Vec::with_capacity(versions.len());
+    let now = util::unix_now();
+    for version in &versions {
+        let dir = match version {
+            Some(version) => content_dir.join(version),
+            None => content_dir.clone(),
+        };
+        if !files.exists(&dir) {
+            return Err(Error::ContentDirNotFound(dir));
+        }
+        contents.push(SiteContent::load(
+            files,
+            &dir,
+            enabled_locales,
+            config.default_locale(),
+            *version,
+            &config.frontmatter.schema,
+            *drafts,
+            now,
+        )?);
+    }
+    let version_slug_sets: HashMap<String, HashSet<String>> = versions
+        .iter()
+        .zip(&contents)
+        .filter_map(|(version, content)| {
+            let slugs = content.inventory.pages.values().map(|p| p.slug.clone());
+            version.map(|v| (v.to_string(), slugs.collect()))
+        })
+        .collect();
+    // The version switcher on every page depends on which slugs exist in each version.
+    let version_sets_hash = {
+        let mut sets: Vec<(&String, Vec<&String>)> = version_slug_sets
+            .iter()
+            .map(|(ver, slugs)| {
+                let mut slugs: Vec<&String> = slugs.iter().collect();
+                slugs.sort();
+                (ver, slugs)
+            })
+            .collect();
+        sets.sort();
+        cache::Fingerprint::new().add(&sets).finish()
+    };
+
+    // Create syntax highlighter if enabled
+    let highlighter = if config.syntax.enabled {
+        Some(SyntaxHighlighter::new(&config.syntax.theme))
+    } else {
+        None
+    };
+
+    // Dev server always uses "/" — base_url only applies to static builds
+    let root_base_url = if live_reload {
+        "/".to_string()
+    } else {
+        config.base_url()
+    };
+    let site_url = config.site_url();
+
+    // Write JS file to output directory (shared across targets)
+    let js_content = if live_reload {
+        theme.default_js.clone()
+    } else {
+        minify_js_source(&theme.default_js)
+    };
+    output.write(Path::new("js/docanvil.js"), js_content.clone().into_bytes())?;
+
+    // Generate cachebust query string from JS content hash
+    let js_cachebust = {
+        let mut hasher = DefaultHasher::new();
+        js_content.hash(&mut hasher);
+        format!("?v={:x}", hasher.finish())
+    };
+
+    // Compute logo and favicon paths with root base_url prefix
+    let shell = SiteShell {
+        config,
+        theme: &theme,
+        base_url: root_base_url.clone(),
+        logo_path: config
+            .project
+            .logo
+            .as_ref()
+            .map(|p| format!("{}{}", root_base_url, p)),
+        favicon_path: config
+            .project
+            .favicon
+            .as_ref()
+            .map(|p| format!("{}{}", root_base_url, p)),
+        live_reload,
+        js_cachebust,
+    };
+
+    // A withheld page must not stay deployable from an earlier build
+    for path in contents.iter().flat_map(|c| &c.withheld) {
+        output.remove(path)?;
+    }
+
+    let mut pages = PageWriter {
+        output,
+        jobs: *jobs,
+        cache: cache(&theme),
+        count: 0,
+    };
+
+    // The root redirect and the 404 page point at the current version in the
+    // default locale.
+    let home = RenderTarget {
+        version: config
+            .current_version()
+            .filter(|_| config.is_versioning_enabled()),
+        locale: config.default_locale().filter(|_| config.is_i18n_enabled()),
+    };
+    let mut home_nav: Option<TargetNav> = None;
+    let latest_version = config.current_version().map(String::from);
+    let default_locale = config.default_locale().unwrap_or("en");
+
+    for (version, content) in versions.iter().copied().zip(&contents) {
+        let inventory = &content.inventory;
+        let inv_hash = cache::Fingerprint::new()
+            .add(&cache::inventory_hash(inventory))
+            .add(&version_sets_hash)
+            .finish();
+        let version_prefix = version.map(|v| format!("{v}/")).unwrap_or_default();
+        let slug_coverage = inventory.slug_locale_coverage();
+
+        for locale in locales.iter().copied() {
+            let target = RenderTarget { version, locale };
+            let target_base_url = format!("{}{}", root_base_url, target.prefix());
+
+            let target_nav = target.nav(files, project_root, inventory, &target_base_url)?;
+            let search_index_url = format!("{}search-index.json", target_base_url);
+            let variables = config.variables_for(version, locale);
+            let anchors = pipeline::anchors::Anchors::new();
+
+            let mut search_entries = if config.search.enabled {
+                Some(Vec::new())
+            } else {
+                None
+            };
+
+            let keys = match locale {
+                Some(locale) => inventory.ordered_for_locale(locale),
+                None => inventory.ordered.clone(),
+            };
+            let pipeline_ctx = pipeline::PipelineContext {
+                inventory,
+                registry,
+                base_url: &root_base_url,
+                highlighter: highlighter.as_ref(),
+                project_root,
+                files,
+                locale,
+                version,
+                profile: profile.as_deref(),
+                plugins,
+                variables: &variables,
+                anchors: &anchors,
+            };
+            let backlinks = Backlinks::collect(&keys, &content.sources, &pipeline_ctx, *jobs);
+
+            pages.write(
+                &keys,
+                target,
+                inventory,
+                &content.sources,
+                inv_hash,
+                &backlinks,
+                &mut search_entries,
+                |key| {
+                    let page = &inventory.pages[key];
+                    let fm = &content.front_matters[key];
+                    let slug = &page.slug;
+                    let sidebar = target_nav.sidebar_for(slug);
+
+                    let html_body =
+                        pipeline::process(&content.sources[key], &page.source_path, &pipeline_ctx)?;
+
+                    let sections = if config.search.enabled && page.listing.search {
+                        let crumbs = sidebar
+                            .breadcrumbs
+                            .get(slug)
+                            .cloned()
+                            .unwrap_or_else(|| vec![page.title.clone()]);
+                        search::extract_sections(
+                            &html_body,
+                            slug,
+                            &page.title,
+                            &target_base_url,
+                            crumbs,
+                        )
+                    } else {
+                        Vec::new()
+                    };
+
+                    let nav_html = project::render_nav(&sidebar.tree, slug, &target_base_url);
+                    let (prev_page, next_page) =
+                        sidebar.prev_next.get(slug).cloned().unwrap_or((None, None));
+                    let backlinks = backlinks
+                        .to(&page.source_path)
+                        .iter()
+                        .map(|key| {
+                            let linking = &inventory.pages[key];
+                            PageLink {
+                                title: linking.title.clone(),
+                                url: format!("{}{}", root_base_url, linking.output_path.display()),
+                            }
+                        })
+                        .collect();
+
+                    let canonical_url = site_url.as_deref().map(|site| {
+                        let site = site.trim_end_matches('/');
+                        let path = page.output_path.to_string_lossy().replace('\\', "/");
+                        format!("{site}/{path}")
+                    });
+
+                    // Language switcher
+                    let available_locales = match locale {
+                        Some(locale) => build_locale_info(
+                            config,
+                            slug,
+                            locale,
+                            &slug_coverage,
+                            &format!("{}{}", root_base_url, version_prefix),
+                            site_url
+                                .as_deref()
+                                .map(|site| format!("{site}{version_prefix}"))
+                                .as_deref(),
+                        ),
+                        None => Vec::new(),
+                    };
+                    let x_default_url = available_locales
+                        .iter()
+                        .find(|l| l.code == default_locale)
+                        .and_then(|l| l.absolute_url.clone().or(Some(l.url.clone())));
+
+                    // Version switcher
+                    let available_versions = match version {
+                        Some(version) => build_version_info(
+                            config,
+                            slug,
+                            version,
+                            locale,
+                            &version_slug_sets,
+                            &root_base_url,
+                        ),
+                        None => Vec::new(),
+                    };
+                    let latest_version_url = latest_version.as_deref().and_then(|lv| {
+                        available_versions
+                            .iter()
+                            .find(|v| v.code == lv)
+                            .map(|v| v.url.clone())
+                    });
+
+                    let ctx = PageContext {
+                        layout: page_layout(&renderer, fm, &content.sources[key], page),
+                        draft_notice: content.draft_notices.get(key).cloned(),
+                        mermaid_enabled: config.charts.enabled,
+                        mermaid_version: config.charts.mermaid_version.clone(),
+                        meta_description: fm.description.clone(),
+                        meta_author: fm.author.clone(),
+                        meta_date: fm.date.clone(),
+                        noindex: fm.noindex,
+                        page_meta: fm.extra.clone(),
+                        menus: target_nav.menus_html(slug, &target_base_url),
+                        prev_page,
+                        next_page,
+                        backlinks,
+                        current_locale: locale.map(String::from),
+                        current_flag: locale.map(|l| config.locale_flag(l)),
+                        available_locales,
+                        locale_auto_detect: locale.is_some() && config.locale.auto_detect,
+                        canonical_url,
+                        x_default_url,
+                        current_version: version.map(String::from),
+                        available_versions,
+                        latest_version: version.and(latest_version.clone()),
+                        latest_version_url,
+                        ..shell.context(
+                            page.title.clone(),
+                            html_body,
+                            nav_html,
+                            search_index_url.clone(),
+                        )
+                    };
+
+                    Ok(RenderedPage {
+                        html: renderer.render_page(&ctx)?,
+                        sections,
+                    })
+                },
+            )?;
+
+            // Write this target's search index
+            if let Some(entries) = search_entries {
+                let json = search::build_index(&entries);
+                let path = Path::new(&target.prefix()).join("search-index.json");
+                pages.output.write(&path, json.into_bytes())?;
+            }
+
+            if target == home || home_nav.is_none() {
+                home_nav = Some(target_nav);
+            }
+        }
+
+        // Emit missing translation warnings
+        if config.is_i18n_enabled() {
+            for (slug, locales_with_page) in &slug_coverage {
+                for locale in &config.locale.enabled {
+                    if !locales_with_page.contains(locale) {
+                        crate::diagnostics::warn_missing_translation(slug, locale);
+                    }
+                }
+            }
+        }
+    }
+
+    let home_base_url = format!("{}{}", root_base_url, home.prefix());
+
+    // Versioned and localised sites have no page at the root: redirect to the home target.
+    if config.is_versioning_enabled() || config.is_i18n_enabled() {
+        let redirect_html = format!(
+            "<!DOCTYPE html>\n\
+             <html>\n\
+             <head>\n\
+             <meta http-equiv=\"refresh\" content=\"0; url={url}\">\n\
+             <link rel=\"canonical\" href=\"{url}\">\n\
+             </head>\n\
+             <body>\n\
+             <p><a href=\"{url}\">Redirecting to {what}...</a></p>\n\
+             </body>\n\
+             </html>\n",
+            url = format!("{}index.html", home_base_url),
+            what = if config.is_versioning_enabled() {
+                "latest documentation"
+            } else {
+                "documentation"
+            }
+        );
+        pages
+            .output
+            .write(Path::new("index.html"), redirect_html.into_bytes())?;
+    }
+
+    // Generate robots.txt and sitemap.xml for production builds
+    if !live_reload {
+        if site_url.is_none() {
+            crate::diagnostics::warn_no_site_url();
+        }
+
+        let sitemap_url = site_url.as_deref().map(|u| format!("{u}sitemap.xml"));
+        let robots = seo::generate_robots_txt(sitemap_url.as_deref());
+        pages
+            .output
+            .write(Path::new("robots.txt"), robots.into_bytes())?;
+
+        // One sitemap across every version. Output paths already include the
+        // version and locale prefixes; hreflang alternates stay within a version.
+        let locale_configs: Vec<Option<seo::SitemapLocaleConfig>> = contents
+            .iter()
+            .map(|content| {
+                config.is_i18n_enabled().then(|| seo::SitemapLocaleConfig {
+                    enabled: config.locale.enabled.clone(),
+                    default_locale: default_locale.to_string(),
+                    slug_coverage: content.inventory.slug_locale_coverage(),
+                })
+            })
+            .collect();
+        let version_prefixes: Vec<String> = versions
+            .iter()
+            .map(|v| v.map(|v| format!("{v}/")).unwrap_or_default())
+            .collect();
+        let sections: Vec<seo::SitemapSection> = contents
+            .iter()
+            .zip(&locale_configs)
+            .zip(&version_prefixes)
+            .map(|((content, locale_config), prefix)| seo::SitemapSection {
+                prefix,
+                inventory: &content.inventory,
+                locale_config: locale_config.as_ref(),
+            })
+            .collect();
+        let sitemap =
+            seo::generate_sitemap_xml_sections(&sections, &root_base_url, site_url.as_deref());
+        pages
+            .output
+            .write(Path::new("sitemap.xml"), sitemap.into_bytes())?;
+    }
+
+    // Generate 404 page using the home target's nav
+    {
+        let (nav_html, menus) = match &home_nav {
+            Some(nav) => (
+                project::render_nav(&nav.sidebar.tree, "", &home_base_url),
+                nav.menus_html("", &home_base_url),
+            ),
+            None => (
+                project::render_nav(&[], "", &home_base_url),
+                BTreeMap::new(),
+            ),
+        };
+
+        let not_found_content = if config.is_versioning_enabled() {
+            let mut links = String::from(
+                "<div class=\"not-found\">\
+                 <h1>404</h1>\
+                 <p>The page you're looking for doesn't exist.</p>\
+                 <p>",
+            );
+            for ver in &config.version.enabled {
+                let display = config.version_display_name(ver);
+                let target = RenderTarget {
+                    version: Some(ver),
+                    locale: home.locale,
+                };
+                let ver_home = format!("{}{}index.html", root_base_url, target.prefix());
+                links.push_str(&format!("<a href=\"{}\">{}</a> ", ver_home, display));
+            }
+            links.push_str("</p></div>");
+            links
+        } else if config.is_i18n_enabled() {
+            let mut links = String::from(
+                "<div class=\"not-found\">\
+                 <h1>404</h1>\
+                 <p>The page you're looking for doesn't exist.</p>\
+                 <p>",
+            );
+            for locale in &config.locale.enabled {
+                let display = config.locale_display_name(locale);
+                let locale_home = format!("{}{}/index.html", root_base_url, locale);
+                links.push_str(&format!("<a href=\"{}\">{}</a> ", locale_home, display));
+            }
+            links.push_str("</p></div>");
+            links
+        } else {
+            format!(
+                "<div class=\"not-found\">\
+                 <h1>404</h1>\
+                 <p>The page you're looking for doesn't exist.</p>\
+                 <a href=\"{}\">Back to home</a>\
+                 </div>",
+                root_base_url
+            )
+        };
+
+        // The version switcher on the 404 page links to each version's home page.
+        let available_versions = match home.version {
+            Some(version) => build_version_info(
+                config,
+                "index",
+                version,
+                home.locale,
+                &version_slug_sets,
+                &root_base_url,
+            ),
+            None => Vec::new(),
+        };
+        let ctx = PageContext {
+            current_locale: home.locale.map(String::from),
+            current_version: home.version.map(String::from),
+            available_versions,
+            latest_version: home.version.and(latest_version.clone()),
+            menus,
+            ..shell.context(
+                "Page Not Found".to_string(),
+                not_found_content,
+                nav_html,
+                format!("{}search-index.json", home_base_url),
+            )
+        };
+        let html = renderer.render_page(&ctx)?;
+        pages
+            .output
+            .write(Path::new("404.html"), html.into_bytes())?;
+    }
+
+    // Copy static assets
+    for (src, dest) in assets::list_assets(files, project_root, config.theme.custom_css.as_deref())
+    {
+        let contents = files.read(&src).map_err(io_context(&src))?;
+        pages.output.write(&dest, contents)?;
+    }
+
+    let mut stats = pages.finish()?;
+    stats.drafts = contents
+        .iter()
+        .map(|c| c.inventory.unpublished.len() + c.draft_notices.len())
+        .sum();
+    stats.drafts_included = *drafts;
+    Ok(stats)
+}
+
+/// Map each page in nav order to its previous and next pages.
+fn build_prev_next_map(
+    nav_tree: &[NavNode],
+    base_url: &str,
+) -> HashMap<String, (Option<PageLink>, Option<PageLink>)> {
+    let flat_pages = project::flatten_nav_pages(nav_tree);
+    let link = |(slug, label): &(String, String)| PageLink {
+        title: label.clone(),
+        url: format!("{}{}.html", base_url, slug),
+    };
+    flat_pages
+        .iter()
+        .enumerate()
+        .map(|(i, (slug, _label))| {
+            let prev = i.checked_sub(1).map(|p| link(&flat_pages[p]));
+            let next = flat_pages.get(i + 1).map(link);
+            (slug.clone(), (prev, next))
+        })
+        .collect()
+}
+
+/// Hash every input that affects all pages: the docanvil version, build profile,
+/// config and nav files, the resolved theme and component templates, the asset listing (image
+/// paths are resolved against it), plugin scripts, the output location and
+/// whether live reload is injected.
+fn site_fingerprint(site: &Site, output_dir: &Path, theme: &Theme) -> u64 {
+    let project_root = site.root.as_path();
+    let files = site.files.as_ref();
+    let mut fp = cache::Fingerprint::new();
+    fp.add(env!("CARGO_PKG_VERSION"))
+        .add(&site.live_reload)
+        .add(&site.drafts)
+        .add(&site.profile)
+        .add(output_dir);
+
+    // docanvil.toml, nav.toml and every per-version / per-locale nav variant
+    let versions = site.config.version.enabled.iter();
+    let locales = site.config.locale.enabled.iter();
+    let mut inputs = vec!["docanvil.toml".to_string(), "nav.toml".to_string()];
+    inputs.extend(versions.clone().map(|v| format!("nav.{v}.toml")));
+    inputs.extend(locales.clone().map(|l| format!("nav.{l}.toml")));
+    for v in versions {
+        inputs.extend(locales.clone().map(|l| format!("nav.{v}.{l}.toml")));
+    }
+    for name in &inputs {
+        let path = project_root.join(name);
+        fp.add(name).add(&files.read(&path).ok());
+    }
+
+    fp.add(&theme.layout_template)
+        .add(&theme.templates)
+        .add(&theme.default_css)
+        .add(&theme.default_js)
+        .add(&theme.css_overrides)
+        .add(&theme.custom_css);
+
+    fp.add(&files.walk(&project_root.join("assets")));
+
+    for path in files.walk(&project_root.join(templates::TEMPLATES_DIR)) {
+        fp.add(&path).add(&files.read(&path).ok());
+    }
+
+    // WebAssembly components, and plugin commands and arguments that name
+    // project files, e.g. a script and the interpreter's `args = ["plugins/lint.py"]`
+    for path in wasm::list_modules(files, &project_root.join(wasm::PLUGINS_DIR)) {
+        fp.add(&path).add(&files.read(&path).ok());
+    }
+    for plugin in &site.config.plugins {
+        for name in std::iter::once(&plugin.command).chain(&plugin.args) {
+            fp.add(name).add(&files.read(&project_root.join(name)).ok());
+        }
+    }
+
+    fp.finish()
+}
+
+/// Build version info for the version switcher on a specific page.
+fn build_version_info(
+    config: &Config,
+    base_slug: &str,
+    current_version: &str,
+    locale: Option<&str>,
+    version_slug_sets: &HashMap<String, HashSet<String>>,
+    root_base_url: &str,
+) -> Vec<VersionInfo> {
+    config
+        .version
+        .enabled
+        .iter()
+        .map(|ver| {
+            let has_page = version_slug_sets
+                .get(ver)
+                .is_some_and(|slugs| slugs.contains(base_slug));
+            let url = if has_page {
+                if let Some(loc) = locale {
+                    format!("{}{}/{}/{}.html", root_base_url, ver, loc, base_slug)
+                } else {
+                    format!("{}{}/{}.html", root_base_url, ver, base_slug)
+                }
+            } else if let Some(loc) = locale {
+                format!("{}{}/{}/index.html", root_base_url, ver, loc)
+            } else {
+                format!("{}{}/index.html", root_base_url, ver)
+            };
+            VersionInfo {
+                code: ver.clone(),
+                display_name: config.version_display_name(ver),
+                url,
+                is_current: ver == current_version,
+                has_page,
+            }
+        })
+        .collect()
+}
+
+/// The template that renders `page`, from its front matter `layout`. A layout
+/// with no matching template is reported and the default layout is used.
+fn page_layout(
+    renderer: &TemplateRenderer,
+    fm: &FrontMatter,
+    source: &str,
+    page: &PageInfo,
+) -> Option<String> {
+    let template = page_templates::layout_template_name(fm.layout.as_deref()?);
+    if renderer.has_template(&template) {
+        return Some(template);
+    }
+    let line = frontmatter::field_line(source, "layout");
+    diagnostics::warn_unknown_layout(&page.source_path, line, &template);
+    None
+}
+
+/// Build locale info for the language switcher on a specific page.
+///
+/// `base_url` and `site_url` point at the directory holding the locale folders —
+/// the site root, or the version directory on versioned sites.
+fn build_locale_info(
+    config: &Config,
+    base_slug: &str,
+    current_locale: &str,
+    slug_coverage: &HashMap<String, HashSet<String>>,
+    base_url: &str,
+    site_url: Option<&str>,
+) -> Vec<LocaleInfo> {
+    config
+        .locale
+        .enabled
+        .iter()
+        .map(|code| {
+            let has_page = slug_coverage
+                .get(base_slug)
+                .is_some_and(|locales| locales.contains(code));
+            let url = if has_page {
+                format!("{}{}/{}.html", base_url, code, base_slug)
+            } else {
+                // Link to this locale's home page when the specific page doesn't exist
+                format!("{}{}/index.html", base_url, code)
+            };
+            let absolute_url = site_url.map(|site| {
+                let locale_path = if has_page {
+                    format!("{code}/{base_slug}.html")
+                } else {
+                    format!("{code}/index.html")
+                };
+                format!("{site}{locale_path}")
+            });
+            LocaleInfo {
+                code: code.clone(),
+                display_name: config.locale_display_name(code),
+                flag: config.locale_flag(code),
+                url,
+                absolute_url,
+                is_current: code == current_locale,
+                has_page,
+            }
+        })
+        .collect()
+}
+
+/// Minify JavaScript source for production builds using oxc.
+fn minify_js_source(source: &str) -> String {
+    let allocator = oxc::allocator::Allocator::default();
+    let source_type = oxc::span::SourceType::mjs();
+    let ret = oxc::parser::Parser::new(&allocator, source, source_type).parse();
+    if !ret.errors.is_empty() {
+        return source.to_string();
+    }
+    let mut program = ret.program;
+    let options = oxc::minifier::MinifierOptions {
+        mangle: Some(oxc::minifier::MangleOptions::default()),
+        compress: Some(oxc::minifier::CompressOptions::smallest()),
+    };
+    let ret = oxc::minifier::Minifier::new(options).minify(&allocator, &mut program);
+    oxc::codegen::Codegen::new()
+        .with_options(oxc::codegen::CodegenOptions::minify())
+        .with_scoping(ret.scoping)
+        .build(&program)
+        .code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Component, ComponentContext};
+    use crate::source::MemoryFiles;
+
+    fn config(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn render(config: Config, files: MemoryFiles) -> SiteOutput {
+        Site::builder(config)
+            .files(files)
+            .build()
+            .unwrap()
+            .render()
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_in_memory_site() {
+        let files = MemoryFiles::new()
+            .with("docs/index.md", "# Home\n\nSee [[guide]].")
+            .with("docs/guide.md", "# Guide")
+            .with("assets/logo.svg", "<svg/>");
+        let output = render(config("[project]\nname = \"Mem\""), files);
+
+        let slugs: Vec<_> = output.pages.iter().map(|p| p.slug.as_str()).collect();
+        assert_eq!(slugs, ["guide", "index"]);
+        let home = output.page("index.html").unwrap();
+        assert!(home.html.contains("/guide.html"));
+        assert!(home.html.contains("Mem"));
+
+        for path in [
+            "404.html",
+            "search-index.json",
+            "sitemap.xml",
+            "js/docanvil.js",
+            "assets/logo.svg",
+        ] {
+            assert!(output.files.contains_key(Path::new(path)), "{path}");
+        }
+    }
+
+    #[test]
+    fn returns_warnings_as_diagnostics() {
+        let files = MemoryFiles::new().with("docs/index.md", "# Home\n\n[[missing]]");
+        let output = render(Config::default(), files);
+
+        let messages: Vec<_> = output
+            .diagnostics
+            .iter()
+            .map(|w| w.message.as_str())
+            .collect();
+        assert!(messages.iter().any(|m| m.contains("site_url")));
+
+        let broken = output
+            .diagnostics
+            .iter()
+            .find(|w| w.check == "broken-wiki-link")
+            .unwrap();
+        assert_eq!(
+            broken.message,
+            "broken link [[missing]] in ./docs/index.md:3"
+        );
+        assert_eq!(broken.file.as_deref(), Some(Path::new("./docs/index.md")));
+        assert_eq!(broken.line, Some(3));
+        assert_eq!(broken.to_diagnostic().category, "content");
+    }
+
+    #[test]
+    fn nav_warnings_point_at_nav_file() {
+        let files = MemoryFiles::new().with("docs/index.md", "# Home").with(
+            "nav.toml",
+            "[[nav]]\npage = \"index\"\n\n[[nav]]\npage = \"gone\"\n",
+        );
+        let output = render(Config::default(), files);
+
+        let missing = output
+            .diagnostics
+            .iter()
+            .find(|w| w.check == "nav-missing-page")
+            .unwrap();
+        assert_eq!(missing.file.as_deref(), Some(Path::new("./nav.toml")));
+        assert_eq!(missing.line, Some(5));
+    }
+
+    #[test]
+    fn uses_custom_components() {
+        struct Shout;
+        impl Component for Shout {
+            fn name(&self) -> &str {
+                "shout"
+            }
+            fn render(&self, ctx: &ComponentContext) -> Result<String> {
+                Ok(format!(
+                    "<strong>{}</strong>",
+                    ctx.body_raw.trim().to_uppercase()
+                ))
+            }
+        }
+
+        let mut registry = ComponentRegistry::new();
+        registry.register(Box::new(Shout));
+        let files = MemoryFiles::new().with("docs/index.md", ":::shout\nhello\n:::\n");
+        let output = Site::builder(Config::default())
+            .files(files)
+            .components(registry)
+            .build()
+            .unwrap()
+            .render()
+            .unwrap();
+
+        assert!(output.pages[0].html.contains("<strong>HELLO</strong>"));
+    }
+
+    #[test]
+    fn uses_theme_component_templates() {
+        let files = MemoryFiles::new()
+            .with(
+                "docs/index.md",
+                ":::card{title=\"Tip\"}\nUse **templates**.\n:::\n",
+            )
+            .with(
+                "theme/components/card.html",
+                "<aside><b>{{ attributes.title }}</b>{{ body_html | safe }}</aside>",
+            );
+        let output = render(Config::default(), files);
+
+        let html = &output.pages[0].html;
+        assert!(html.contains("<aside><b>Tip</b><p>Use <strong>templates</strong>.</p>"));
+    }
+
+    #[test]
+    fn renders_versioned_i18n_matrix() {
+        let config = config(
+            r#"
+[locale]
+enabled = ["en", "fr"]
+default = "en"
+
+[version]
+enabled = ["v1", "v2"]
+current = "v2"
+"#,
+        );
+        let files = MemoryFiles::new()
+            .with("docs/v1/index.md", "# Home")
+            .with("docs/v2/index.md", "# Home")
+            .with("docs/v2/index.fr.md", "# Accueil");
+        let output = render(config, files);
+
+        let fr = output.page("v2/fr/index.html").unwrap();
+        assert_eq!(fr.locale.as_deref(), Some("fr"));
+        assert_eq!(fr.version.as_deref(), Some("v2"));
+        assert!(fr.html.contains("Accueil"));
+        assert!(output.page("v1/en/index.html").is_some());
+        let redirect = String::from_utf8_lossy(&output.files[Path::new("index.html")]).into_owned();
+        assert!(redirect.contains("/v2/en/index.html"));
+    }
+
+    #[test]
+    fn missing_content_dir_is_an_error() {
+        let result = Site::builder(Config::default())
+            .files(MemoryFiles::new())
+            .build()
+            .unwrap()
+            .render();
+        assert!(matches!(result, Err(Error::ContentDirNotFound(_))));
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use walkdir::WalkDir;
+
+/// Where a build reads project files from.
+///
+/// The CLI reads straight from disk ([`DiskFiles`]); embedders can supply an
+/// in-memory tree ([`MemoryFiles`]) or their own implementation. Paths are
+/// passed exactly as the build constructs them — the project root joined with
+/// a relative path such as `docs/index.md`.
+pub trait FileSource: Send + Sync {
+    /// Read a file's bytes.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Whether a file or directory exists at `path`.
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Every file below `dir`, recursively, in sorted order. A missing
+    /// directory yields no files.
+    fn walk(&self, dir: &Path) -> Vec<PathBuf>;
+
+    /// Read a UTF-8 text file.
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Reads project files from the local filesystem.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DiskFiles;
+
+impl FileSource for DiskFiles {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn walk(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+/// An in-memory project tree, keyed by path.
+///
+/// ```
+/// use docanvil::source::MemoryFiles;
+///
+/// let files = MemoryFiles::new()
+///     .with("docs/index.md", "# Home")
+///     .with("nav.toml", "[[nav]]\npage = \"index\"\n");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFiles {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl MemoryFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a file.
+    pub fn insert(&mut self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) {
+        self.files.insert(normalize(path.as_ref()), contents.into());
+    }
+
+    /// Builder-style [`insert`](Self::insert).
+    pub fn with(mut self, path: impl AsRef<Path>, contents: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, contents);
+        self
+    }
+}
+
+impl FileSource for MemoryFiles {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files
+            .get(&normalize(path))
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path.display().to_string()))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.files.keys().any(|file| file.starts_with(&path))
+    }
+
+    fn walk(&self, dir: &Path) -> Vec<PathBuf> {
+        // Like WalkDir, yield paths that start with `dir` exactly as given.
+        let prefix = normalize(dir);
+        self.files
+            .keys()
+            .filter_map(|file| file.strip_prefix(&prefix).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(|relative| dir.join(relative))
+            .collect()
+    }
+}
+
+/// Drop `.` components so `./docs/a.md` and `docs/a.md` are the same key.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_read_and_exists() {
+        let files = MemoryFiles::new().with("docs/index.md", "# Home");
+        assert_eq!(
+            files.read_to_string(Path::new("docs/index.md")).unwrap(),
+            "# Home"
+        );
+        assert!(files.exists(Path::new("docs")));
+        assert!(files.exists(Path::new("./docs/index.md")));
+        assert!(!files.exists(Path::new("assets")));
+        assert!(files.read(Path::new("missing.md")).is_err());
+    }
+
+    #[test]
+    fn memory_walk_is_recursive_and_sorted() {
+        let files = MemoryFiles::new()
+            .with("docs/b.md", "")
+            .with("docs/guides/a.md", "")
+            .with("docs/a.md", "")
+            .with("nav.toml", "");
+        assert_eq!(
+            files.walk(Path::new("docs")),
+            vec![
+                PathBuf::from("docs/a.md"),
+                PathBuf::from("docs/b.md"),
+                PathBuf::from("docs/guides/a.md"),
+            ]
+        );
+        assert!(files.walk(Path::new("assets")).is_empty());
+    }
+
+    #[test]
+    fn memory_walk_from_empty_root() {
+        let files = MemoryFiles::new().with("docs/a.md", "");
+        assert_eq!(
+            files.walk(&Path::new("").join("docs")),
+            vec![PathBuf::from("docs/a.md")]
+        );
+    }
+
+    #[test]
+    fn memory_walk_keeps_given_prefix() {
+        let files = MemoryFiles::new().with("docs/a.md", "");
+        assert_eq!(
+            files.walk(Path::new("./docs")),
+            vec![PathBuf::from("./docs/a.md")]
+        );
+    }
+
+    #[test]
+    fn disk_walk_lists_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/sub")).unwrap();
+        std::fs::write(dir.path().join("docs/sub/b.md"), "").unwrap();
+        std::fs::write(dir.path().join("docs/a.md"), "").unwrap();
+
+        let files = DiskFiles.walk(&dir.path().join("docs"));
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("docs/a.md"),
+                dir.path().join("docs/sub/b.md")
+            ]
+        );
+        assert!(DiskFiles.walk(&dir.path().join("missing")).is_empty());
+    }
+}
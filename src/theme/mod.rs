@@ -4,14 +4,21 @@ use std::path::Path;
 
 use crate::config::Config;
 use crate::diagnostics::warn_custom_css_not_found;
+use crate::source::{DiskFiles, FileSource};
 
 #[derive(Embed)]
 #[folder = "src/theme/default/"]
 struct DefaultTheme;
 
+/// Directory, relative to the project root, that page templates are loaded from.
+pub const TEMPLATES_DIR: &str = "theme/templates";
+
 /// Resolved theme resources ready for rendering.
 pub struct Theme {
     pub layout_template: String,
+    /// Every other file in `theme/templates/` (partials, macros, alternate
+    /// layouts), keyed by its path relative to that directory, e.g. `partials/footer.html`.
+    pub templates: Vec<(String, String)>,
     pub default_css: String,
     pub default_js: String,
     pub css_overrides: Option<String>,
@@ -20,9 +27,21 @@ pub struct Theme {
     pub custom_css: Option<String>,
 }
 
+/// The built-in `layout.html`.
+pub fn default_layout() -> String {
+    DefaultTheme::get("layout.html")
+        .map(|f| String::from_utf8_lossy(&f.data).into_owned())
+        .unwrap_or_default()
+}
+
 impl Theme {
     /// Resolve theme from config and project directory.
     pub fn resolve(config: &Config, project_root: &Path) -> Self {
+        Self::resolve_from(config, project_root, &DiskFiles)
+    }
+
+    /// Resolve theme from config, reading user overrides through `files`.
+    pub fn resolve_from(config: &Config, project_root: &Path, files: &dyn FileSource) -> Self {
         // Load default embedded files
         let default_css = DefaultTheme::get("style.css")
             .map(|f| String::from_utf8_lossy(&f.data).into_owned())
@@ -32,22 +51,24 @@ impl Theme {
             .map(|f| String::from_utf8_lossy(&f.data).into_owned())
             .unwrap_or_default();
 
-        let default_layout = DefaultTheme::get("layout.html")
-            .map(|f| String::from_utf8_lossy(&f.data).into_owned())
-            .unwrap_or_default();
+        let default_layout = default_layout();
 
         // Check for user template override
-        let user_layout_path = project_root.join("theme/templates/layout.html");
-        let layout_template = if user_layout_path.exists() {
-            std::fs::read_to_string(&user_layout_path).unwrap_or(default_layout)
+        let templates_dir = project_root.join(TEMPLATES_DIR);
+        let user_layout_path = templates_dir.join("layout.html");
+        let layout_template = if files.exists(&user_layout_path) {
+            files
+                .read_to_string(&user_layout_path)
+                .unwrap_or(default_layout)
         } else {
             default_layout
         };
+        let templates = load_templates(files, &templates_dir);
 
         // Check for user JS override
         let user_js_path = project_root.join("theme/docanvil.js");
-        let default_js = if user_js_path.exists() {
-            std::fs::read_to_string(&user_js_path).unwrap_or(default_js)
+        let default_js = if files.exists(&user_js_path) {
+            files.read_to_string(&user_js_path).unwrap_or(default_js)
         } else {
             default_js
         };
@@ -59,8 +80,8 @@ impl Theme {
         let (custom_css_path, custom_css) = match config.theme.custom_css.clone() {
             Some(css_path) => {
                 let full_path = project_root.join(&css_path);
-                if full_path.exists() {
-                    let content = std::fs::read_to_string(&full_path).ok();
+                if files.exists(&full_path) {
+                    let content = files.read_to_string(&full_path).ok();
                     (Some(css_path), content)
                 } else {
                     warn_custom_css_not_found(&css_path);
@@ -72,6 +93,7 @@ impl Theme {
 
         Self {
             layout_template,
+            templates,
             default_css,
             default_js,
             css_overrides,
@@ -81,6 +103,25 @@ impl Theme {
     }
 }
 
+/// Read every file under `dir` except `layout.html` and hidden files (e.g.
+/// `.DS_Store`). Files that can't be read as text are skipped.
+fn load_templates(files: &dyn FileSource, dir: &Path) -> Vec<(String, String)> {
+    let mut templates = Vec::new();
+    for path in files.walk(dir) {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        if name == "layout.html" || name.split('/').any(|part| part.starts_with('.')) {
+            continue;
+        }
+        if let Ok(content) = files.read_to_string(&path) {
+            templates.push((name, content));
+        }
+    }
+    templates
+}
+
 /// Build `:root { --key: val; }` overrides from config variables.
 fn build_css_overrides(variables: &HashMap<String, String>) -> Option<String> {
     if variables.is_empty() {
@@ -1,3 +1,6 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 /// Escape HTML entities in user-supplied content.
 pub fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -6,10 +9,106 @@ pub fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
+/// Apply `f` to every item using up to `jobs` threads, returning results in input order.
+///
+/// Work is handed out one item at a time, so uneven items (large pages next to
+/// small ones) balance across threads. With `jobs <= 1` everything runs on the
+/// calling thread.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = jobs.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else { break };
+                        done.push((i, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (i, result) in done {
+                results[i] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.expect("every item is processed"))
+        .collect()
+}
+
+/// Number of threads to use when the user doesn't pass `--jobs`.
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Seconds since the Unix epoch, for deciding whether scheduled pages are published.
+pub fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// `path` with `.` and `..` components resolved lexically, without touching
+/// the filesystem. Leading `..`s of a relative path are kept.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(
+            normalize_path(Path::new("./docs/guides/../setup.md")),
+            PathBuf::from("docs/setup.md")
+        );
+        assert_eq!(
+            normalize_path(Path::new("/site/docs/../src/lib.rs")),
+            PathBuf::from("/site/src/lib.rs")
+        );
+        assert_eq!(
+            normalize_path(Path::new("./../src/lib.rs")),
+            PathBuf::from("../src/lib.rs")
+        );
+        assert_eq!(normalize_path(Path::new("/../a")), PathBuf::from("/a"));
+    }
+
     #[test]
     fn no_special_chars() {
         assert_eq!(html_escape("hello world"), "hello world");
@@ -47,4 +146,24 @@ mod tests {
     fn already_escaped_entities() {
         assert_eq!(html_escape("&amp;"), "&amp;amp;");
     }
+
+    #[test]
+    fn parallel_map_preserves_order() {
+        let items: Vec<usize> = (0..100).collect();
+        let doubled = parallel_map(&items, 4, |n| n * 2);
+        assert_eq!(doubled, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parallel_map_serial_when_one_job() {
+        let caller = std::thread::current().id();
+        let ids = parallel_map(&[1, 2, 3], 1, |_| std::thread::current().id());
+        assert!(ids.iter().all(|id| *id == caller));
+    }
+
+    #[test]
+    fn parallel_map_empty() {
+        let items: Vec<u8> = Vec::new();
+        assert!(parallel_map(&items, 8, |n| *n).is_empty());
+    }
 }
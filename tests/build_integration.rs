@@ -208,6 +208,343 @@ Body text."#;
     );
 }
 
+#[test]
+fn test_yaml_and_toml_front_matter() {
+    let yaml = "---\ntitle: From YAML\nslug: yaml-page\n---\n\nYAML body.";
+    let toml = "+++\ntitle = \"From TOML\"\nslug = \"toml-page\"\n+++\n\nTOML body.";
+
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[("index.md", "# Home"), ("a.md", yaml), ("b.md", toml)],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    let html = read_output(dir.path(), "yaml-page.html");
+    assert!(html.contains("From YAML"));
+    assert!(!html.contains("slug: yaml-page"));
+
+    let html = read_output(dir.path(), "toml-page.html");
+    assert!(html.contains("From TOML"));
+    assert!(!html.contains("+++"));
+}
+
+#[test]
+fn test_front_matter_meta_in_layout() {
+    let page = "---\ntitle: Runbook\nowner: platform-team\nstatus: draft\n---\n\nBody.";
+    let dir = create_project(DEFAULT_CONFIG, &[("index.md", page)]);
+    let templates = dir.path().join("theme/templates");
+    std::fs::create_dir_all(&templates).unwrap();
+    std::fs::write(
+        templates.join("layout.html"),
+        "<p class=\"owner\">{{ page.meta.owner | default(value=\"none\") }}</p>\
+         {% if page.meta.status %}<span class=\"badge\">{{ page.meta.status }}</span>{% endif %}\
+         {% if page.meta.title %}title leaked{% endif %}{{ content | safe }}",
+    )
+    .unwrap();
+    build_project(dir.path()).expect("build should succeed");
+
+    let html = read_output(dir.path(), "index.html");
+    assert!(html.contains("<p class=\"owner\">platform-team</p>"));
+    assert!(html.contains("<span class=\"badge\">draft</span>"));
+    assert!(!html.contains("title leaked"));
+}
+
+#[test]
+fn test_front_matter_layout_selects_template() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "---\nlayout: landing\n---\n\n# Welcome"),
+            ("post.md", "+++\nlayout = \"blog/post.html\"\n+++\n\n# Post"),
+            ("guide.md", "# Guide"),
+        ],
+    );
+    let templates = dir.path().join("theme/templates");
+    std::fs::create_dir_all(templates.join("blog")).unwrap();
+    std::fs::create_dir_all(templates.join("partials")).unwrap();
+    std::fs::write(
+        templates.join("partials/footer.html"),
+        "<footer>{{ project_name }}</footer>",
+    )
+    .unwrap();
+    std::fs::write(
+        templates.join("landing.html"),
+        "<main class=\"landing\">{{ content | safe }}</main>{% include \"partials/footer.html\" %}",
+    )
+    .unwrap();
+    // Alternate layouts can extend the default layout.html
+    std::fs::write(
+        templates.join("blog/post.html"),
+        "{% extends \"layout.html\" %}{% block content %}<article class=\"post\">{{ content | safe }}</article>{% endblock %}",
+    )
+    .unwrap();
+    build_project(dir.path()).expect("build should succeed");
+
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.starts_with("<main class=\"landing\">"), "{index}");
+    assert!(index.contains("<footer>Test Docs</footer>"));
+
+    let post = read_output(dir.path(), "post.html");
+    assert!(post.contains("<article class=\"post\">"));
+    assert!(
+        post.contains("class=\"sidebar"),
+        "post should keep the default layout"
+    );
+
+    let guide = read_output(dir.path(), "guide.html");
+    assert!(!guide.contains("landing") && !guide.contains("<article"));
+}
+
+#[test]
+fn test_unknown_layout_falls_back_to_default() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(config, &[("index.md", "# Home")]);
+    build_project_strict(dir.path()).expect("strict build without warnings should succeed");
+
+    std::fs::write(
+        dir.path().join("docs/index.md"),
+        "---\ntitle: Home\nlayout: missing\n---\n\n# Home",
+    )
+    .unwrap();
+    build_project(dir.path()).expect("build should succeed");
+    let html = read_output(dir.path(), "index.html");
+    assert!(html.contains("class=\"sidebar"));
+    assert!(build_project_strict(dir.path()).is_err());
+}
+
+#[test]
+fn test_draft_and_scheduled_pages_excluded() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(
+        config,
+        &[
+            ("index.md", "# Home"),
+            (
+                "wip.md",
+                "---\ntitle: Work in Progress\ndraft: true\n---\n\n# WIP",
+            ),
+            (
+                "launch.md",
+                "+++\ntitle = \"Launch\"\npublish_after = 2999-01-01\n+++\n\n# Launch",
+            ),
+            (
+                "released.md",
+                "---\npublish_after: 2000-01-01T09:00:00Z\n---\n\n# Released",
+            ),
+        ],
+    );
+    // nav.toml may list pages before they are published
+    std::fs::write(
+        dir.path().join("nav.toml"),
+        "[[nav]]\npage = \"index\"\n\n[[nav]]\npage = \"work-in-progress\"\n\n[[nav]]\npage = \"released\"\n",
+    )
+    .unwrap();
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    assert!(output_exists(dir.path(), "released.html"));
+    assert!(!output_exists(dir.path(), "work-in-progress.html"));
+    assert!(!output_exists(dir.path(), "wip.html"));
+    assert!(!output_exists(dir.path(), "launch.html"));
+
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("released.html"));
+    assert!(!index.contains("work-in-progress.html"));
+    assert!(!read_output(dir.path(), "search-index.json").contains("WIP"));
+    assert!(!read_output(dir.path(), "sitemap.xml").contains("launch"));
+    assert!(!read_output(dir.path(), "released.html").contains("<div class=\"draft-banner\""));
+}
+
+#[test]
+fn test_pages_excluded_from_listings() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(
+        config,
+        &[
+            ("index.md", "# Home"),
+            (
+                "legal.md",
+                "---\nnav: false\nsearch: false\n---\n\n# Legal Notice",
+            ),
+            ("stub.md", "---\nsitemap: false\n---\n\n# Generated Stub"),
+            ("private.md", "---\nnoindex: true\n---\n\n# Private Notes"),
+        ],
+    );
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    // Every page is still built
+    for page in ["legal.html", "stub.html", "private.html"] {
+        assert!(output_exists(dir.path(), page), "{page} should be built");
+    }
+
+    let index = read_output(dir.path(), "index.html");
+    assert!(!index.contains("legal.html"));
+    assert!(index.contains("stub.html"));
+
+    let search = read_output(dir.path(), "search-index.json");
+    assert!(!search.contains("Legal Notice"));
+    assert!(search.contains("Generated Stub"));
+    assert!(search.contains("Private Notes"));
+
+    let sitemap = read_output(dir.path(), "sitemap.xml");
+    assert!(sitemap.contains("legal.html"));
+    assert!(!sitemap.contains("stub.html"));
+    assert!(!sitemap.contains("private.html"));
+
+    let robots = r#"<meta name="robots" content="noindex">"#;
+    assert!(read_output(dir.path(), "private.html").contains(robots));
+    assert!(!read_output(dir.path(), "stub.html").contains(robots));
+}
+
+#[test]
+fn test_front_matter_nav_order_and_labels() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "---\nnav_order: 1\n---\n\n# Home"),
+            ("about.md", "# About"),
+            ("guides/advanced.md", "# Advanced"),
+            (
+                "guides/setup.md",
+                "---\ntitle: Setting Up\nnav_label: Setup\nweight: 1\n---\n\n# Setting Up",
+            ),
+            (
+                "guides/_folder.toml",
+                "label = \"User Guides\"\norder = 2\n",
+            ),
+        ],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    let index = read_output(dir.path(), "index.html");
+    let position = |needle: &str| {
+        index
+            .find(needle)
+            .unwrap_or_else(|| panic!("{needle} not in nav"))
+    };
+    assert!(position(">Home<") < position("User Guides"));
+    assert!(position("User Guides") < position(">About<"));
+    assert!(position(">Setup<") < position(">Advanced<"));
+    assert!(!output_exists(dir.path(), "guides/_folder.toml"));
+}
+
+#[test]
+fn test_nav_external_links_icons_and_badges() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(config, &[("index.md", "# Home"), ("beta.md", "# Beta API")]);
+    let nav = r#"
+[[nav]]
+page = "index"
+icon = "home"
+
+[[nav]]
+page = "beta"
+badge = "beta"
+
+[[nav]]
+label = "API Console"
+url = "https://console.example.com"
+"#;
+    std::fs::write(dir.path().join("nav.toml"), nav).unwrap();
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("<svg class=\"nav-icon\""));
+    assert!(index.contains("<span class=\"nav-badge nav-badge-beta\">beta</span>"));
+    assert!(index.contains(
+        "<a href=\"https://console.example.com\" target=\"_blank\" rel=\"noopener noreferrer\">API Console"
+    ));
+
+    // Unknown icons are reported
+    std::fs::write(
+        dir.path().join("nav.toml"),
+        "[[nav]]\npage = \"index\"\nicon = \"unicorn\"\n",
+    )
+    .unwrap();
+    assert!(build_project_strict(dir.path()).is_err());
+}
+
+#[test]
+fn test_nav_menus_and_section_sidebars() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(
+        config,
+        &[
+            ("index.md", "# Home"),
+            ("guides/setup.md", "# Setup"),
+            ("api/index.md", "# API"),
+            ("api/endpoints.md", "# Endpoints"),
+        ],
+    );
+    let nav = r#"
+[[menus.header]]
+page = "api/index"
+label = "API Reference"
+
+[[menus.footer]]
+label = "GitHub"
+url = "https://github.com/example"
+
+[[sidebars.api]]
+page = "api/endpoints"
+label = "All Endpoints"
+"#;
+    std::fs::write(dir.path().join("nav.toml"), nav).unwrap();
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    // Menus appear on every page; the main sidebar is discovered
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("<nav class=\"top-header-menu\""));
+    assert!(index.contains("<a href=\"/api/index.html\">API Reference</a>"));
+    assert!(index.contains("<nav class=\"footer-menu\""));
+    assert!(index.contains("https://github.com/example"));
+    assert!(index.contains("href=\"/guides/setup.html\""));
+    assert!(!index.contains("All Endpoints"));
+
+    // Pages in the api section get its sidebar, and the header link is active
+    let endpoints = read_output(dir.path(), "api/endpoints.html");
+    assert!(endpoints.contains("All Endpoints"));
+    assert!(!endpoints.contains("href=\"/guides/setup.html\""));
+    assert!(endpoints.contains("<a href=\"/api/index.html\" class=\"active\">API Reference</a>"));
+
+    // A sidebar for a folder without pages is reported
+    std::fs::write(
+        dir.path().join("nav.toml"),
+        "[[sidebars.missing]]\npage = \"index\"\n",
+    )
+    .unwrap();
+    assert!(build_project_strict(dir.path()).is_err());
+}
+
+#[test]
+fn test_drafts_included_with_banner() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "# Home"),
+            ("wip.md", "---\ndraft: true\n---\n\n# WIP"),
+        ],
+    );
+    let config = docanvil::config::Config::load(dir.path()).unwrap();
+    let output = docanvil::site::Site::builder(config)
+        .root(dir.path())
+        .drafts(true)
+        .build()
+        .unwrap()
+        .render()
+        .unwrap();
+
+    let wip = output.page("wip.html").expect("draft page is rendered");
+    assert!(wip.html.contains("<div class=\"draft-banner\""));
+    assert!(wip.html.contains("This page is a draft"));
+    assert!(
+        !output
+            .page("index.html")
+            .unwrap()
+            .html
+            .contains("<div class=\"draft-banner\"")
+    );
+}
+
 #[test]
 fn test_title_derived_slug() {
     let page = r#"---
@@ -652,3 +989,405 @@ fn test_versioned_i18n_build_output_structure() {
         "v2/fr should contain French content"
     );
 }
+
+#[test]
+fn test_incremental_rebuild() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "# Home\n\nSee [[guide]]."),
+            ("guide.md", "# Guide\n\nOriginal guide text."),
+        ],
+    );
+    build_project(dir.path()).expect("first build should succeed");
+    assert!(dir.path().join(".docanvil-cache/build.json").exists());
+
+    // Unchanged rebuild keeps every page and the full search index.
+    build_project(dir.path()).expect("second build should succeed");
+    let search = read_output(dir.path(), "search-index.json");
+    assert!(search.contains("Original guide text"));
+    assert!(search.contains("guide.html"));
+
+    // Editing one page's body re-renders it.
+    std::fs::write(
+        dir.path().join("docs/guide.md"),
+        "# Guide\n\nUpdated guide text.",
+    )
+    .unwrap();
+    build_project(dir.path()).expect("third build should succeed");
+    assert!(read_output(dir.path(), "guide.html").contains("Updated guide text"));
+    assert!(read_output(dir.path(), "search-index.json").contains("Updated guide text"));
+
+    // A title change elsewhere re-renders pages whose nav mentions it.
+    std::fs::write(
+        dir.path().join("docs/guide.md"),
+        "---\n{\"title\": \"Setup Guide\"}\n---\n# Guide\n\nUpdated guide text.",
+    )
+    .unwrap();
+    build_project(dir.path()).expect("fourth build should succeed");
+    assert!(read_output(dir.path(), "index.html").contains("Setup Guide"));
+}
+
+#[test]
+fn test_incremental_rebuild_keeps_strict_warnings() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[("index.md", "# Home\n\nSee [[nonexistent]] page.")],
+    );
+    build_project(dir.path()).expect("non-strict build should succeed");
+
+    // The page emitted a warning, so it is not cached and strict mode still fails.
+    let result = build_project_strict(dir.path());
+    assert!(matches!(
+        result,
+        Err(docanvil::error::Error::StrictWarnings(_))
+    ));
+}
+
+#[test]
+fn test_includes() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(
+        config,
+        &[
+            (
+                "index.md",
+                "# Home\n\n:::include{file=\"includes/prereqs.md\"}\n\n![[setup#install]]\n",
+            ),
+            (
+                "setup.md",
+                "# Setup\n\n## Install\n\nRun the installer.\n\n## Configure\n\nEdit the config.\n",
+            ),
+        ],
+    );
+    std::fs::create_dir_all(dir.path().join("includes")).unwrap();
+    std::fs::write(
+        dir.path().join("includes/prereqs.md"),
+        ":::note\nYou need **Rust 1.85**.\n:::\n",
+    )
+    .unwrap();
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("<strong>Rust 1.85</strong>"));
+    assert!(index.contains("Run the installer."));
+    assert!(!index.contains("Edit the config."));
+
+    // Editing an included file re-renders the pages that include it
+    std::fs::write(
+        dir.path().join("includes/prereqs.md"),
+        "You need **Rust 1.90**.\n",
+    )
+    .unwrap();
+    build_project_strict(dir.path()).expect("rebuild should succeed");
+    assert!(read_output(dir.path(), "index.html").contains("<strong>Rust 1.90</strong>"));
+
+    // Missing include targets are reported
+    std::fs::remove_file(dir.path().join("includes/prereqs.md")).unwrap();
+    assert!(matches!(
+        build_project_strict(dir.path()),
+        Err(docanvil::error::Error::StrictWarnings(1))
+    ));
+}
+
+#[test]
+fn test_code_snippets() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com\"\n";
+    let dir = create_project(
+        config,
+        &[(
+            "index.md",
+            "# Home\n\n:::snippet{file=\"src/main.rs\" region=\"setup\"}\n",
+        )],
+    );
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(
+        dir.path().join("src/main.rs"),
+        "fn main() {\n    // region: setup\n    let answer = 42;\n    // endregion\n}\n",
+    )
+    .unwrap();
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    // The snippet is highlighted as Rust, going by the file extension
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("answer"));
+    assert!(index.contains("<pre style=\""));
+    assert!(!index.contains("fn main"));
+    assert!(!index.contains("region: setup"));
+
+    // Removing the region fails a strict build
+    std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+    assert!(matches!(
+        build_project_strict(dir.path()),
+        Err(docanvil::error::Error::StrictWarnings(1))
+    ));
+}
+
+#[test]
+fn test_variables_per_version_and_locale() {
+    let config = format!(
+        "{VERSION_I18N_CONFIG}\n[build]\nsite_url = \"https://example.com/\"\n\n\
+         [variables]\nproduct = \"Anvil\"\nrelease = \"2.0\"\n\n\
+         [version.variables.v1]\nrelease = \"1.4\"\n\n[locale.variables.fr]\nproduct = \"Enclume\"\n"
+    );
+    let page = "# About\n\n{{ product }} {{ release }}, not `{{ product }}`.\n";
+    let dir = create_project(
+        &config,
+        &[
+            ("v1/index.en.md", page),
+            ("v1/index.fr.md", page),
+            ("v2/index.en.md", page),
+            ("v2/index.fr.md", page),
+        ],
+    );
+    build_project_strict(dir.path()).expect("strict build should succeed");
+
+    assert!(
+        read_output(dir.path(), "v1/en/index.html")
+            .contains("Anvil 1.4, not <code>{{ product }}</code>")
+    );
+    assert!(read_output(dir.path(), "v1/fr/index.html").contains("Enclume 1.4"));
+    assert!(read_output(dir.path(), "v2/en/index.html").contains("Anvil 2.0"));
+    assert!(read_output(dir.path(), "v2/fr/index.html").contains("Enclume 2.0"));
+
+    // An undefined variable fails a strict build
+    std::fs::write(
+        dir.path().join("docs/v2/index.en.md"),
+        "# About\n\n{{ edition }}\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        build_project_strict(dir.path()),
+        Err(docanvil::error::Error::StrictWarnings(1))
+    ));
+}
+
+#[test]
+fn test_only_blocks_per_version_and_locale() {
+    let page = "# About\n\n:::only{version=\"v2\"}\nNew in v2.\n:::\n\n\
+                :::only{locale=\"fr\"}\nFrench only.\n:::\n\n\
+                :::only{version=\"v1\" locale=\"!fr\"}\nLegacy English.\n:::\n";
+    let dir = create_project(
+        VERSION_I18N_CONFIG,
+        &[
+            ("v1/index.en.md", page),
+            ("v1/index.fr.md", page),
+            ("v2/index.en.md", page),
+            ("v2/index.fr.md", page),
+        ],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    let v1_en = read_output(dir.path(), "v1/en/index.html");
+    assert!(v1_en.contains("Legacy English."));
+    assert!(!v1_en.contains("New in v2."));
+    assert!(!v1_en.contains("French only."));
+
+    let v1_fr = read_output(dir.path(), "v1/fr/index.html");
+    assert!(v1_fr.contains("French only."));
+    assert!(!v1_fr.contains("Legacy English."));
+
+    let v2_fr = read_output(dir.path(), "v2/fr/index.html");
+    assert!(v2_fr.contains("New in v2."));
+    assert!(v2_fr.contains("French only."));
+    assert!(!v2_fr.contains("Legacy English."));
+}
+
+#[test]
+fn test_markdown_links_to_md_files() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nbase_url = \"/docs/\"\n";
+    let dir = create_project(
+        config,
+        &[
+            (
+                "index.md",
+                "# Home\n\nSee [Setup](guides/setup.md#install) and [Old](guides/missing.md).",
+            ),
+            (
+                "guides/setup.md",
+                "---\nslug: installation\n---\n\n# Setup\n\n## Install\n\n[Home](../index.md)",
+            ),
+        ],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    // Slug overrides and base_url apply
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("href=\"/docs/guides/installation.html#install\""));
+    assert!(index.contains("href=\"guides/missing.md\""));
+    let setup = read_output(dir.path(), "guides/installation.html");
+    assert!(setup.contains("href=\"/docs/index.html\""));
+
+    // The missing page is a broken link
+    assert!(matches!(
+        build_project_strict(dir.path()),
+        Err(docanvil::error::Error::StrictWarnings(_))
+    ));
+}
+
+#[test]
+fn test_markdown_links_keep_version_and_locale() {
+    let dir = create_project(
+        VERSION_I18N_CONFIG,
+        &[
+            ("v1/index.en.md", "# Home\n\n[Setup](setup.md)"),
+            ("v1/index.fr.md", "# Accueil\n\n[Installation](setup.md)"),
+            ("v1/setup.en.md", "# Setup"),
+            ("v1/setup.fr.md", "# Installation"),
+            ("v2/index.en.md", "# Home"),
+            ("v2/index.fr.md", "# Accueil"),
+        ],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    let v1_en = read_output(dir.path(), "v1/en/index.html");
+    assert!(v1_en.contains("href=\"/v1/en/setup.html\""));
+    let v1_fr = read_output(dir.path(), "v1/fr/index.html");
+    assert!(v1_fr.contains("href=\"/v1/fr/setup.html\""));
+}
+
+#[test]
+fn test_links_to_anchors() {
+    let config =
+        "[project]\nname = \"Test Docs\"\n\n[build]\nsite_url = \"https://example.com/\"\n";
+    let dir = create_project(
+        config,
+        &[
+            (
+                "index.md",
+                "# Home\n\n## Local {#here}\n\n[[setup#Getting Started]] [[#here|Up]] \
+                  [Setup](setup.md#getting-started)",
+            ),
+            (
+                "setup.md",
+                "# Setup\n\n## Getting Started\n\n:::note\n## Inside\n:::\n\n\
+                 :::card{name=\"faq\"}\nAnswers\n:::\n\n[[index#here]]",
+            ),
+            (
+                "other.md",
+                "# Other\n\nSee [[setup#inside]] and [FAQ](setup.md#card-faq).",
+            ),
+        ],
+    );
+    // IDs from component output count too
+    let components = dir.path().join("theme/components");
+    std::fs::create_dir_all(&components).unwrap();
+    std::fs::write(
+        components.join("card.html"),
+        "<div id=\"card-{{ attributes.name }}\">{{ body_html | safe }}</div>",
+    )
+    .unwrap();
+    build_project_strict(dir.path()).expect("anchors should resolve");
+
+    // Anchors match heading IDs as written or slugified
+    let index = read_output(dir.path(), "index.html");
+    assert!(index.contains("href=\"/setup.html#getting-started\""));
+    assert!(index.contains("href=\"#here\">Up</a>"));
+    let setup = read_output(dir.path(), "setup.html");
+    assert!(setup.contains("href=\"/index.html#here\""));
+
+    // Warnings on a linked page are only counted once, for that page
+    let setup = dir.path().join("docs/setup.md");
+    let original = std::fs::read_to_string(&setup).unwrap();
+    std::fs::write(&setup, format!("{original}\n\n{{{{ undefined }}}}")).unwrap();
+    match build_project_strict(dir.path()) {
+        Err(docanvil::error::Error::StrictWarnings(count)) => assert_eq!(count, 1),
+        other => panic!("expected a strict failure, got {other:?}"),
+    }
+    std::fs::write(&setup, original).unwrap();
+
+    // `#nowhere` is missing, and so is the `#here` the unchanged setup page links to
+    std::fs::write(
+        dir.path().join("docs/index.md"),
+        "# Home\n\n[[setup#getting-started]] [[#nowhere]]",
+    )
+    .unwrap();
+    match build_project_strict(dir.path()) {
+        Err(docanvil::error::Error::StrictWarnings(count)) => assert_eq!(count, 2),
+        other => panic!("expected a strict failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_backlinks() {
+    let config = "[project]\nname = \"Test Docs\"\n\n[build]\nbase_url = \"/docs/\"\n";
+    let dir = create_project(
+        config,
+        &[
+            (
+                "index.md",
+                "# Home\n\n[[setup]] and [[setup#install|again]]",
+            ),
+            (
+                "setup.md",
+                "# Setup\n\n## Install\n\n[[#install]] [[index]]",
+            ),
+            ("guide.md", "# Guide\n\nSee [Setup](setup.md)."),
+        ],
+    );
+    build_project(dir.path()).expect("build should succeed");
+
+    // The "Referenced by" panel of a page, with URLs as the template escapes them
+    let backlinks = |path: &str| {
+        let html = read_output(dir.path(), path);
+        html.split_once("<aside class=\"backlinks\"")
+            .and_then(|(_, panel)| panel.split_once("</aside>"))
+            .map(|(panel, _)| panel.replace("&#x2F;", "/"))
+    };
+
+    // Each linking page is listed once, and a page never lists itself
+    let setup = backlinks("setup.html").expect("setup is linked to");
+    assert!(setup.contains("Referenced by"));
+    assert_eq!(setup.matches("<li>").count(), 2);
+    assert!(setup.contains("<a href=\"/docs/index.html\">Home</a>"));
+    assert!(setup.contains("<a href=\"/docs/guide.html\">Guide</a>"));
+    let index = backlinks("index.html").expect("index is linked to");
+    assert!(index.contains("<a href=\"/docs/setup.html\">Setup</a>"));
+    assert!(backlinks("guide.html").is_none());
+
+    // Removing a link updates the linked page, though its source is unchanged
+    std::fs::write(dir.path().join("docs/guide.md"), "# Guide").unwrap();
+    build_project(dir.path()).expect("rebuild should succeed");
+    let setup = backlinks("setup.html").unwrap();
+    assert!(!setup.contains("Guide"));
+    assert!(setup.contains("<a href=\"/docs/index.html\">Home</a>"));
+}
+
+#[test]
+fn test_versioned_i18n_links_keep_version_prefix() {
+    let config = format!("{VERSION_I18N_CONFIG}\n[build]\nsite_url = \"https://example.com/\"\n");
+    let dir = create_project(
+        &config,
+        &[
+            ("v1/index.en.md", "# Home v1"),
+            ("v1/index.fr.md", "# Accueil v1"),
+            ("v2/index.en.md", "# Home v2"),
+            ("v2/index.fr.md", "# Accueil v2"),
+        ],
+    );
+    build_project(dir.path()).expect("versioned + i18n build should succeed");
+
+    // The language switcher stays within the current version.
+    let v1_en = read_output(dir.path(), "v1/en/index.html");
+    assert!(v1_en.contains("&#x2F;v1&#x2F;fr&#x2F;index.html"));
+
+    // hreflang alternates in the sitemap include the version prefix.
+    let sitemap = read_output(dir.path(), "sitemap.xml");
+    assert!(sitemap.contains("hreflang=\"fr\" href=\"https://example.com/v1/fr/index.html\""));
+    assert!(sitemap.contains("hreflang=\"fr\" href=\"https://example.com/v2/fr/index.html\""));
+}
+
+#[test]
+fn test_versioned_404_page() {
+    let dir = create_project(
+        VERSION_CONFIG,
+        &[("v1/index.md", "# Home v1"), ("v2/index.md", "# Home v2")],
+    );
+    build_project(dir.path()).expect("versioned build should succeed");
+
+    let html = read_output(dir.path(), "404.html");
+    assert!(html.contains("version-switcher-trigger"));
+    assert!(html.contains("href=\"/v1/index.html\""));
+    assert!(html.contains("href=\"/v2/index.html\""));
+    assert!(html.contains("/v2/search-index.json"));
+}
@@ -23,6 +23,97 @@ fn test_cli_build_success() {
     assert!(dir.path().join("dist/index.html").exists());
 }
 
+#[test]
+fn test_cli_build_drafts() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "# Hello"),
+            ("wip.md", "---\ndraft: true\n---\n\n# WIP"),
+        ],
+    );
+
+    docanvil_cmd()
+        .args(["build", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Built 1 page (1 draft skipped)"));
+    assert!(!dir.path().join("dist/wip.html").exists());
+
+    docanvil_cmd()
+        .args(["build", "--drafts", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Built 2 pages (1 draft included)"));
+    assert!(dir.path().join("dist/wip.html").exists());
+}
+
+#[test]
+fn test_cli_build_removes_drafts_from_earlier_builds() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "# Hello"),
+            ("wip.md", "---\ndraft: true\n---\n\n# WIP"),
+            (
+                "launch.md",
+                "---\nslug: launch-day\npublish_after: 2999-01-01\n---\n\n# Launch",
+            ),
+        ],
+    );
+
+    docanvil_cmd()
+        .args(["build", "--drafts", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    assert!(dir.path().join("dist/wip.html").exists());
+    assert!(dir.path().join("dist/launch-day.html").exists());
+
+    // A production build into the same output directory leaves nothing to deploy
+    docanvil_cmd()
+        .args(["build", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    assert!(!dir.path().join("dist/wip.html").exists());
+    assert!(!dir.path().join("dist/launch-day.html").exists());
+    assert!(dir.path().join("dist/index.html").exists());
+}
+
+#[test]
+fn test_cli_build_profile() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[(
+            "index.md",
+            "# Hello\n\n:::only{profile=\"internal\"}\nStaff only.\n:::\n\n:::only{profile=\"!internal\"}\nPublic.\n:::\n",
+        )],
+    );
+    let index = dir.path().join("dist/index.html");
+
+    docanvil_cmd()
+        .args(["build", "--quiet", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    let html = std::fs::read_to_string(&index).unwrap();
+    assert!(html.contains("Public."));
+    assert!(!html.contains("Staff only."));
+
+    // Changing the profile re-renders cached pages
+    docanvil_cmd()
+        .args(["build", "--quiet", "--profile", "internal", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    let html = std::fs::read_to_string(&index).unwrap();
+    assert!(html.contains("Staff only."));
+    assert!(!html.contains("Public."));
+}
+
 #[test]
 fn test_cli_build_missing_project() {
     docanvil_cmd()
@@ -47,3 +138,542 @@ fn test_cli_build_strict_broken_link() {
         .failure()
         .stderr(predicate::str::contains("warning"));
 }
+
+#[test]
+fn test_cli_build_parallel_matches_serial() {
+    let pages: Vec<(String, String)> = (0..12)
+        .map(|i| {
+            (
+                format!("page{i}.md"),
+                format!("# Page {i}\n\nNote^[popover {i}] and [[missing{i}]].\n"),
+            )
+        })
+        .collect();
+    let pages: Vec<(&str, &str)> = pages
+        .iter()
+        .map(|(f, c)| (f.as_str(), c.as_str()))
+        .collect();
+    let dir = create_project(DEFAULT_CONFIG, &pages);
+
+    let build = |jobs: &str, out: &str| {
+        docanvil_cmd()
+            .args([
+                "build",
+                "--quiet",
+                "--no-cache",
+                "--jobs",
+                jobs,
+                "--out",
+                out,
+            ])
+            .arg("--path")
+            .arg(dir.path())
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .get_output()
+            .stderr
+            .clone()
+    };
+    let serial_stderr = build("1", "serial");
+    let parallel_stderr = build("8", "parallel");
+    assert_eq!(
+        String::from_utf8_lossy(&serial_stderr),
+        String::from_utf8_lossy(&parallel_stderr),
+        "warnings should be reported in the same order"
+    );
+
+    for i in 0..12 {
+        let file = format!("page{i}.html");
+        let serial = std::fs::read_to_string(dir.path().join("serial").join(&file)).unwrap();
+        let parallel = std::fs::read_to_string(dir.path().join("parallel").join(&file)).unwrap();
+        assert_eq!(
+            serial, parallel,
+            "{file} differs between serial and parallel builds"
+        );
+        assert!(serial.contains("id=\"popover-0\""));
+    }
+}
+
+#[test]
+fn test_cli_build_format_json() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[("index.md", "# Home\n\nBroken [[nonexistent]] link.")],
+    );
+
+    let output = docanvil_cmd()
+        .args(["build", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // Warnings go to stdout as JSON instead of stderr
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("broken link"));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let broken = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["check"] == "broken-wiki-link")
+        .expect("broken link diagnostic");
+    assert_eq!(broken["category"], "content");
+    assert_eq!(broken["severity"], "warning");
+    assert_eq!(broken["file"], "docs/index.md");
+    assert_eq!(broken["line"], 3);
+}
+
+#[test]
+fn test_cli_build_format_checkstyle() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[("index.md", "# Home\n\nBroken [[nonexistent]] link.")],
+    );
+
+    docanvil_cmd()
+        .args(["build", "--format", "checkstyle", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<file name=\"docs/index.md\">"))
+        .stdout(predicate::str::contains(
+            "line=\"3\" column=\"0\" severity=\"warning\"",
+        ))
+        .stdout(predicate::str::contains(
+            "source=\"docanvil.content.broken-wiki-link\"",
+        ));
+}
+
+#[test]
+fn test_cli_build_invalid_front_matter() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[(
+            "index.md",
+            "---\ntitle: Home\nauthor: [unclosed\n---\n\nBody.",
+        )],
+    );
+
+    let output = docanvil_cmd()
+        .args(["build", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let invalid = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["check"] == "frontmatter-parse-error")
+        .expect("front matter diagnostic");
+    assert_eq!(invalid["file"], "docs/index.md");
+    assert!(
+        invalid["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid YAML front matter in "),
+        "{invalid}"
+    );
+    assert!(invalid["line"].as_u64().unwrap() >= 3, "{invalid}");
+}
+
+#[test]
+fn test_cli_build_invalid_directive_attribute() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[(
+            "index.md",
+            "# Home\n\n:::note{titel=\"Heads up\"}\nBody.\n:::\n",
+        )],
+    );
+
+    let output = docanvil_cmd()
+        .args(["build", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let invalid = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["check"] == "invalid-directive-attribute")
+        .expect("invalid attribute diagnostic");
+    assert_eq!(
+        invalid["message"],
+        ":::note: unknown attribute 'titel' (accepts title)"
+    );
+    assert_eq!(invalid["file"], "docs/index.md");
+    assert_eq!(invalid["line"], 3);
+}
+
+#[test]
+fn test_cli_doctor_invalid_directive_attributes() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[(
+            "index.md",
+            "# Home\n\n:::note{titel=\"Heads up\"}\nBody.\n:::\n\nStatus: :::lozenge{type=\"purple\",text=\"Done\"}\n\n`:::lozenge{type=\"purple\"}` in code is ignored.\n",
+        )],
+    );
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let invalid: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "invalid-directive-attribute")
+        .map(|d| (d["line"].as_u64().unwrap(), d["message"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        invalid,
+        [
+            (3, ":::note: unknown attribute 'titel' (accepts title)"),
+            (
+                7,
+                ":::lozenge: attribute 'type' must be one of default, in-progress, warning, success, error, got 'purple'"
+            ),
+        ]
+    );
+}
+
+const SCHEMA_CONFIG: &str = r#"
+[frontmatter.schema.owner]
+required = true
+folders = ["compliance"]
+
+[frontmatter.schema.review_date]
+type = "date"
+required = true
+folders = ["compliance"]
+"#;
+
+#[test]
+fn test_cli_build_frontmatter_schema() {
+    let config = format!("{DEFAULT_CONFIG}{SCHEMA_CONFIG}");
+    let dir = create_project(
+        &config,
+        &[
+            ("index.md", "# Home\n"),
+            (
+                "compliance/policy.md",
+                "---\nowner: security\nreview_date: soon\n---\n# Policy\n",
+            ),
+        ],
+    );
+
+    let output = docanvil_cmd()
+        .args(["build", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let schema: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "frontmatter-schema")
+        .collect();
+    assert_eq!(schema.len(), 1, "{json}");
+    assert_eq!(schema[0]["file"], "docs/compliance/policy.md");
+    assert_eq!(schema[0]["line"], 3);
+    assert!(
+        schema[0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("field 'review_date' must be a date in YYYY-MM-DD format"),
+        "{}",
+        schema[0]
+    );
+
+    docanvil_cmd()
+        .args(["build", "--strict", "--quiet", "--path"])
+        .arg(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_cli_doctor_frontmatter_schema() {
+    let config = format!("{DEFAULT_CONFIG}{SCHEMA_CONFIG}");
+    let dir = create_project(
+        &config,
+        &[
+            ("index.md", "# Home\n"),
+            (
+                "compliance/policy.md",
+                "---\ntitle: Policy\n---\n# Policy\n",
+            ),
+        ],
+    );
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let missing: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "frontmatter-schema")
+        .map(|d| d["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        missing,
+        [
+            "missing required field 'owner'",
+            "missing required field 'review_date'"
+        ]
+    );
+}
+
+#[test]
+fn test_cli_doctor_folder_meta_parse_error() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            ("index.md", "# Home\n"),
+            ("guides/setup.md", "# Setup\n"),
+            ("guides/_folder.toml", "order = \"first\"\n"),
+        ],
+    );
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let errors: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "folder-meta-parse-error")
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("_folder.toml")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_build_plugins() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let config = r#"[project]
+name = "Test Project"
+content_dir = "docs"
+
+[[plugins]]
+name = "shout"
+command = "./shout.sh"
+hooks = ["html", "post-build"]
+components = ["greeting"]
+"#;
+    let dir = create_project(config, &[("index.md", "# Home\n\n:::greeting\nhi\n:::\n")]);
+
+    // Renders :::greeting, warns about every page and leaves a marker after the build
+    let script = r#"#!/bin/sh
+while read -r line; do
+  case "$line" in
+    *'"hook":"component"'*) echo '{"content": "<p>hello from plugin</p>"}' ;;
+    *'"hook":"html"'*) echo '{"warnings": ["page was shouted at"]}' ;;
+    *'"hook":"post-build"'*) touch built.marker; echo '{}' ;;
+  esac
+done
+"#;
+    let path = dir.path().join("shout.sh");
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    docanvil_cmd()
+        .args(["build", "--path"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "plugin 'shout': page was shouted at",
+        ));
+
+    let index = std::fs::read_to_string(dir.path().join("dist/index.html")).unwrap();
+    assert!(index.contains("<p>hello from plugin</p>"));
+    assert!(dir.path().join("built.marker").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_build_plugin_error() {
+    let config = r#"[project]
+name = "Test Project"
+content_dir = "docs"
+
+[[plugins]]
+name = "broken"
+command = "./missing.sh"
+hooks = ["markdown"]
+"#;
+    let dir = create_project(config, &[("index.md", "# Home")]);
+
+    docanvil_cmd()
+        .args(["build", "--path"])
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .code(4)
+        .stderr(predicate::str::contains("plugin 'broken' failed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_build_plugin_component_error() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let config = r#"[project]
+name = "Test Project"
+content_dir = "docs"
+
+[[plugins]]
+name = "chart"
+command = "./chart.sh"
+components = ["chart"]
+"#;
+    let dir = create_project(config, &[("index.md", "# Home\n\n:::chart\n:::\n")]);
+    let path = dir.path().join("chart.sh");
+    std::fs::write(
+        &path,
+        "#!/bin/sh\nwhile read -r line; do echo '{\"error\": \"no data\"}'; done\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    // Fails every time, rather than caching the failed page
+    for _ in 0..2 {
+        docanvil_cmd()
+            .args(["build", "--path"])
+            .arg(dir.path())
+            .assert()
+            .failure()
+            .code(4)
+            .stderr(predicate::str::contains("plugin 'chart' failed: no data"));
+    }
+    assert!(!dir.path().join("dist/index.html").exists());
+}
+
+#[test]
+fn test_cli_doctor_broken_markdown_link() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            (
+                "index.md",
+                "---\ntitle: Home\n---\n# Home\n\n[Setup](setup.md)\n\n[Gone](gone.md)\n\n\
+                 :::only{profile=\"internal\"}\n[Hidden](hidden.md)\n:::\n\n\
+                 :::include{file=\"shared/links.md\"}\n",
+            ),
+            ("setup.md", "# Setup\n"),
+        ],
+    );
+    let shared = dir.path().join("shared");
+    std::fs::create_dir_all(&shared).unwrap();
+    std::fs::write(shared.join("links.md"), "[Lost](lost.md)\n").unwrap();
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let broken: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "broken-markdown-link")
+        .collect();
+    // The link in the `:::only` block is left out, the included one is checked
+    assert_eq!(broken.len(), 2);
+    assert_eq!(broken[0]["line"], 8);
+    assert!(broken[1]["message"].as_str().unwrap().contains("lost.md"));
+}
+
+#[test]
+fn test_cli_doctor_broken_anchor() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            (
+                "index.md",
+                "# Home\n\n[[setup#install]] [[setup#gone]]\n\n[[#home]]\n\n[Setup](setup.md#nope)\n",
+            ),
+            ("setup.md", "# Setup\n\n## Install\n"),
+        ],
+    );
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let lines: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["check"] == "broken-anchor")
+        .map(|d| d["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, [3, 7]);
+}
+
+#[test]
+fn test_cli_doctor_broken_include() {
+    let dir = create_project(
+        DEFAULT_CONFIG,
+        &[
+            (
+                "index.md",
+                "# Home\n\n![[setup#install]]\n\n:::include{file=\"includes/missing.md\"}\n",
+            ),
+            ("setup.md", "# Setup\n\n## Install\n\nRun it.\n"),
+        ],
+    );
+
+    let output = docanvil_cmd()
+        .args(["doctor", "--format", "json", "--path"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diags = json.as_array().unwrap();
+    // The page embed resolves and isn't mistaken for a wiki-link
+    assert!(!diags.iter().any(|d| d["check"] == "broken-wiki-link"));
+    let broken: Vec<_> = diags
+        .iter()
+        .filter(|d| d["check"] == "broken-include")
+        .collect();
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0]["line"], 5);
+}
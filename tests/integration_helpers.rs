@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::Path;
 
+use docanvil::cli::OutputFormat;
 use tempfile::TempDir;
 
 /// Minimal docanvil.toml that produces a working build.
@@ -34,18 +35,43 @@ pub fn create_project(config_toml: &str, pages: &[(&str, &str)]) -> TempDir {
 }
 
 /// Run the build pipeline on a project directory.
+#[allow(dead_code)] // Only build_integration uses it
 pub fn build_project(dir: &Path) -> docanvil::error::Result<()> {
     let out = dir.join("dist");
-    docanvil::cli::build::run(dir, &out, false, true, false)
+    docanvil::cli::build::run(
+        dir,
+        &out,
+        false,
+        true,
+        false,
+        false,
+        false,
+        None,
+        None,
+        &OutputFormat::Human,
+    )
 }
 
 /// Run the build pipeline in strict mode.
+#[allow(dead_code)] // Only build_integration uses it
 pub fn build_project_strict(dir: &Path) -> docanvil::error::Result<()> {
     let out = dir.join("dist");
-    docanvil::cli::build::run(dir, &out, false, true, true)
+    docanvil::cli::build::run(
+        dir,
+        &out,
+        false,
+        true,
+        true,
+        false,
+        false,
+        None,
+        None,
+        &OutputFormat::Human,
+    )
 }
 
 /// Read a file from the build output directory.
+#[allow(dead_code)] // Only build_integration uses it
 pub fn read_output(dir: &Path, path: &str) -> String {
     let full = dir.join("dist").join(path);
     fs::read_to_string(&full)
@@ -53,6 +79,7 @@ pub fn read_output(dir: &Path, path: &str) -> String {
 }
 
 /// Check whether a file exists in the build output directory.
+#[allow(dead_code)] // Only build_integration uses it
 pub fn output_exists(dir: &Path, path: &str) -> bool {
     dir.join("dist").join(path).exists()
 }